@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Args;
+use tempdir::TempDir;
+
+use reaboot_core::api::InstallerConfig;
+use reaboot_core::bundle::BundleWriter;
+use reaboot_core::installer::{InstallError, InstallerNewArgs};
+use reaboot_core::reaboot_util::create_http_client_for_config;
+use reaboot_core::reaper_platform::ReaperPlatform;
+use reaboot_reapack::model::VersionRef;
+
+use crate::commands::http_args::HttpArgs;
+use crate::commands::install::listener::CliInstallerListener;
+use crate::commands::install::report::print_report;
+use crate::commands::lock::parse_platform;
+use crate::commands::recipe_args::RecipeSelectionArgs;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct BundleArgs {
+    /// Path of the bundle to be written.
+    #[arg(short, long, default_value = "reaboot-bundle.tar.zst")]
+    output: PathBuf,
+    /// URLs of ReaPack packages to be bundled.
+    #[arg(short, long)]
+    package_url: Option<Vec<String>>,
+    /// Platforms for which to bundle the packages, e.g. `windows-x64`.
+    ///
+    /// Can be provided multiple times. If not provided, ReaBoot uses the platform on which it's
+    /// running.
+    #[arg(long, value_parser = parse_platform)]
+    platform: Vec<ReaperPlatform>,
+    /// If set, also bundles the REAPER installer for each platform.
+    #[arg(long, default_value_t = false)]
+    include_reaper: bool,
+    /// REAPER version to bundle if `--include-reaper` is set.
+    ///
+    /// You can either provide a specific version number (pre-releases are supported as well)
+    /// or use `latest` or `latest-pre`.
+    #[arg(long, default_value = "latest")]
+    reaper_version: String,
+    /// If set, doesn't include ReaPack itself in the bundle.
+    #[arg(long, default_value_t = false)]
+    skip_reapack: bool,
+    /// Determines the maximum number of concurrent downloads.
+    #[arg(long, default_value_t = 5)]
+    concurrent_downloads: u32,
+    #[command(flatten)]
+    recipe: RecipeSelectionArgs,
+    #[command(flatten)]
+    http: HttpArgs,
+}
+
+/// Downloads everything necessary for an offline installation into one bundle file.
+pub async fn bundle(args: BundleArgs) -> anyhow::Result<()> {
+    let reaper_version: VersionRef = args
+        .reaper_version
+        .parse()
+        .context("You didn't provide a valid REAPER version string.")?;
+    let platforms = if args.platform.is_empty() {
+        vec![ReaperPlatform::from_reaboot_build()]
+    } else {
+        args.platform
+    };
+    // We don't install anything, so we use the OS temp dir
+    let temp_parent_dir = TempDir::new("reaboot-bundle-")
+        .context("couldn't create temporary directory for bundling")?;
+    let http_config = args.http.into_http_config()?;
+    // Bundle the packages of the selected recipe features
+    let client = create_http_client_for_config(&InstallerConfig {
+        http: Some(http_config.clone()),
+        ..Default::default()
+    })?;
+    let (recipe, selected_features) = args.recipe.resolve(&client).await?;
+    let mut writer = BundleWriter::new()?;
+    for platform in platforms {
+        let config = InstallerConfig {
+            custom_platform: Some(platform),
+            package_urls: args.package_url.clone().unwrap_or_default(),
+            temp_parent_dir: Some(temp_parent_dir.path().to_path_buf()),
+            concurrent_downloads: Some(args.concurrent_downloads),
+            recipe: recipe.clone(),
+            selected_features: selected_features.clone(),
+            reaper_version: Some(reaper_version.clone()),
+            install_reaper: Some(false),
+            install_reapack: Some(!args.skip_reapack),
+            http: Some(http_config.clone()),
+            ..Default::default()
+        };
+        let (interaction_sender, interaction_receiver) = tokio::sync::broadcast::channel(10);
+        let installer_new_args = InstallerNewArgs {
+            config,
+            temp_dir_for_reaper_download: temp_parent_dir.path().to_path_buf(),
+            interactions: interaction_receiver,
+            listener: CliInstallerListener::new(interaction_sender),
+        };
+        println!("Bundling packages for platform {platform:?}...\n");
+        match writer
+            .add_platform(installer_new_args, args.include_reaper)
+            .await
+        {
+            Ok(_) => {}
+            Err(InstallError::SomePackagesFailed(r)) => {
+                print_report(&r, false);
+                Err(InstallError::SomePackagesFailed(r))?;
+            }
+            Err(InstallError::Other(e)) => {
+                Err(e.context("Bundling failed"))?;
+            }
+        }
+    }
+    let manifest = writer.finish(&args.output)?;
+    println!(
+        "\nBundled {} files for {} platforms in {:?}",
+        manifest.files.len(),
+        manifest.platforms.len(),
+        &args.output
+    );
+    Ok(())
+}
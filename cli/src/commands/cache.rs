@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Args, Subcommand};
+
+use reaboot_core::download_cache::{DownloadCache, DEFAULT_MAX_DOWNLOAD_CACHE_SIZE};
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct CacheArgs {
+    /// Custom download cache directory.
+    ///
+    /// If not provided, ReaBoot uses `reaboot/downloads` within the user cache directory.
+    #[arg(long, global = true)]
+    dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    /// Removes all files from the download cache.
+    Clean,
+    /// Shows the number of files and the total size of the download cache.
+    Stats,
+}
+
+pub async fn cache(args: CacheArgs) -> anyhow::Result<()> {
+    let dir = args
+        .dir
+        .or_else(DownloadCache::default_dir)
+        .context("couldn't determine download cache directory")?;
+    let cache = DownloadCache::new(dir, DEFAULT_MAX_DOWNLOAD_CACHE_SIZE);
+    match args.command {
+        CacheCommand::Clean => {
+            let stats = cache.stats()?;
+            cache.clean()?;
+            println!(
+                "Removed {} files ({}) from {:?}",
+                stats.file_count,
+                format_size(stats.total_size),
+                stats.dir
+            );
+        }
+        CacheCommand::Stats => {
+            let stats = cache.stats()?;
+            println!("Directory: {:?}", stats.dir);
+            println!("Files: {}", stats.file_count);
+            println!(
+                "Size: {} (max. {})",
+                format_size(stats.total_size),
+                format_size(stats.max_size)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+    if unit_index == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit_index])
+    }
+}
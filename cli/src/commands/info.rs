@@ -0,0 +1,128 @@
+use anyhow::Context;
+use clap::Args;
+use tempdir::TempDir;
+
+use reaboot_core::api::{InstallerConfig, PackageDetails};
+use reaboot_core::package_details::get_package_details;
+use reaboot_core::reaboot_util::create_downloader_for_config;
+use reaboot_core::reaper_platform::ReaperPlatform;
+use reaboot_reapack::model::PackageUrl;
+
+use crate::commands::http_args::HttpArgs;
+use crate::commands::lock::parse_platform;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct InfoArgs {
+    /// Package URL, e.g. `https://github.com/ReaTeam/ReaScripts/raw/master/index.xml#p=Various/rodilab_Color%20palette.lua`.
+    ///
+    /// The version part of the URL determines for which version the files are shown.
+    package_url: String,
+    /// Platform for which the files should be shown, e.g. `windows-x64`.
+    ///
+    /// If not provided, ReaBoot uses the platform on which it's running.
+    #[arg(long, value_parser = parse_platform)]
+    platform: Option<ReaperPlatform>,
+    /// If set, prints the package details as JSON.
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    #[command(flatten)]
+    http: HttpArgs,
+}
+
+impl InfoArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        !self.json
+    }
+}
+
+pub async fn info(args: InfoArgs) -> anyhow::Result<()> {
+    let package_url = PackageUrl::parse(&args.package_url)
+        .with_context(|| format!("invalid package URL {}", args.package_url))?;
+    let platform = args
+        .platform
+        .unwrap_or_else(ReaperPlatform::from_reaboot_build);
+    let config = InstallerConfig {
+        http: Some(args.http.into_http_config()?),
+        ..Default::default()
+    };
+    let downloader = create_downloader_for_config(&config)?;
+    let temp_dir = TempDir::new("reaboot-info-").context("couldn't create temporary directory")?;
+    let details = get_package_details(&package_url, &downloader, temp_dir.path(), platform).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+    } else {
+        termimad::print_text(&build_markdown(&details));
+    }
+    Ok(())
+}
+
+fn build_markdown(details: &PackageDetails) -> String {
+    let title = details.description.as_ref().unwrap_or(&details.package);
+    let mut markdown = format!("# {title}\n");
+    markdown += &format!(
+        "*{}/{}* ({}) from repository *{}*\n",
+        details.category, details.package, details.typ, details.repository
+    );
+    // ReaPack descriptions are usually RTF, which doesn't render well in the terminal
+    if let Some(about) = details.about.as_ref().filter(|a| !a.starts_with("{\\rtf")) {
+        markdown += &format!("\n{about}\n");
+    }
+    if !details.links.is_empty() {
+        markdown += "\n## Links\n";
+        for link in &details.links {
+            match &link.name {
+                None => markdown += &format!("- {}: {}\n", link.rel, link.url),
+                Some(name) => markdown += &format!("- {}: {name} ({})\n", link.rel, link.url),
+            }
+        }
+    }
+    markdown += &format!(
+        "\n## Files of version {} on {:?}\n",
+        details.selected_version, details.platform
+    );
+    if details.files.is_empty() {
+        markdown += "This version is not available for this platform.\n";
+    } else {
+        // Termimad needs the first line in order to print the top table border
+        markdown += "|:-|:-\n";
+        markdown += "|**Destination**|**Download URL**\n";
+        markdown += "|:-|:-\n";
+        for file in &details.files {
+            markdown += &format!(
+                "|{}|{}\n",
+                escape_cell(&file.relative_path),
+                escape_cell(&file.url)
+            );
+        }
+        // Termimad needs this in order to print the bottom table border
+        markdown += "|-|-\n";
+    }
+    markdown += "\n## Versions\n";
+    for version in &details.versions {
+        let mut facts = vec![];
+        if let Some(time) = &version.time {
+            // Just the date part of the RFC 3339 date time
+            facts.push(time.split('T').next().unwrap_or(time).to_string());
+        }
+        if let Some(author) = &version.author {
+            facts.push(format!("by {author}"));
+        }
+        if version.pre_release {
+            facts.push("pre-release".to_string());
+        }
+        markdown += &format!("\n### {}\n", version.name);
+        if !facts.is_empty() {
+            markdown += &format!("*{}*\n", facts.join(", "));
+        }
+        if let Some(changelog) = &version.changelog {
+            markdown += &format!("\n{}\n", changelog.trim());
+        }
+    }
+    markdown
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
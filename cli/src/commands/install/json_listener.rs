@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tokio::sync::broadcast::Sender;
+
+use reaboot_core::api::{ConfirmationRequest, InstallationStage, PreparationReportInfo};
+use reaboot_core::installer::{InstallerListener, InstallerTask};
+
+/// Installer listener which writes each event as one JSON object per line to stdout.
+///
+/// Meant for driving ReaBoot from scripts.
+pub struct JsonLinesInstallerListener {
+    /// Last emitted progress percentage per task, in order to not flood the output.
+    task_progress: Mutex<HashMap<u32, u64>>,
+    interaction_sender: Sender<bool>,
+}
+
+/// A single line of JSON output.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JsonEvent<'a> {
+    StageChanged {
+        label: String,
+        stage: &'a InstallationStage,
+    },
+    StageProgressed {
+        progress: f64,
+    },
+    TaskStarted {
+        task_id: u32,
+        label: &'a str,
+    },
+    TaskProgressed {
+        task_id: u32,
+        progress: f64,
+    },
+    TaskFinished {
+        task_id: u32,
+    },
+    Warning {
+        message: String,
+    },
+    /// Answered by writing one line to stdin: `no` declines, anything else confirms.
+    ConfirmationRequested {
+        request: &'a ConfirmationRequest,
+    },
+    Report {
+        actually_installed_things: bool,
+        report: &'a PreparationReportInfo,
+    },
+    ManualReaperInstallRequired {
+        installer: &'a Path,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl JsonEvent<'_> {
+    pub fn emit(&self) {
+        match serde_json::to_string(self) {
+            Ok(json) => println!("{json}"),
+            Err(e) => tracing::warn!(msg = "Couldn't serialize JSON event", %e),
+        }
+    }
+}
+
+impl JsonLinesInstallerListener {
+    pub fn new(interaction_sender: Sender<bool>) -> Self {
+        Self {
+            task_progress: Default::default(),
+            interaction_sender,
+        }
+    }
+}
+
+impl InstallerListener for JsonLinesInstallerListener {
+    fn installation_stage_changed(&self, event: InstallationStage) {
+        JsonEvent::StageChanged {
+            label: event.to_string(),
+            stage: &event,
+        }
+        .emit();
+    }
+
+    fn installation_stage_progressed(&self, progress: f64) {
+        JsonEvent::StageProgressed { progress }.emit();
+    }
+
+    fn task_started(&self, task_id: u32, task: InstallerTask) {
+        self.task_progress.lock().unwrap().insert(task_id, 0);
+        JsonEvent::TaskStarted {
+            task_id,
+            label: &task.label,
+        }
+        .emit();
+    }
+
+    fn task_progressed(&self, task_id: u32, progress: f64) {
+        let percent = (progress * 100.0).round() as u64;
+        let previous_percent = self.task_progress.lock().unwrap().insert(task_id, percent);
+        if previous_percent != Some(percent) {
+            JsonEvent::TaskProgressed { task_id, progress }.emit();
+        }
+    }
+
+    fn task_finished(&self, task_id: u32) {
+        self.task_progress.lock().unwrap().remove(&task_id);
+        JsonEvent::TaskFinished { task_id }.emit();
+    }
+
+    fn warn(&self, message: impl Display + Debug) {
+        JsonEvent::Warning {
+            message: message.to_string(),
+        }
+        .emit();
+    }
+
+    fn info(&self, _message: impl Display + Debug) {}
+
+    fn debug(&self, _message: impl Display + Debug) {}
+
+    fn confirm(&self, request: ConfirmationRequest) {
+        JsonEvent::ConfirmationRequested { request: &request }.emit();
+        let mut answer = String::new();
+        let confirmation_result = match std::io::stdin().lock().read_line(&mut answer) {
+            Ok(n) if n > 0 => !matches!(answer.trim(), "no" | "n"),
+            _ => false,
+        };
+        self.interaction_sender
+            .send(confirmation_result)
+            .expect("couldn't send confirmation result");
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::Context;
 use console::Key;
+use reaboot_core::http_client::HttpClient;
 use reaboot_core::reaper_util::get_reaper_eula;
 
 use std::time::Duration;
 
-pub async fn confirm_license() -> anyhow::Result<bool> {
+pub async fn confirm_license(http_client: &HttpClient) -> anyhow::Result<bool> {
     let term = console::Term::stdout();
     loop {
         let _ = term.clear_screen();
@@ -15,7 +16,7 @@ pub async fn confirm_license() -> anyhow::Result<bool> {
         println!("- [D]isagree");
         match term.read_key().ok() {
             Some(Key::Char('s')) => {
-                let eula = get_reaper_eula().await.context("Couldn't download REAPER EULA. Consider using command line option `--non-interactive`.")?;
+                let eula = get_reaper_eula(http_client).await.context("Couldn't download REAPER EULA. Consider using command line option `--non-interactive`.")?;
                 let _ = term.clear_screen();
                 for line in eula.lines() {
                     tokio::time::sleep(Duration::from_millis(50)).await;
@@ -1,19 +1,39 @@
 use std::path::PathBuf;
 
+use anyhow::bail;
 use anyhow::Context;
-use clap::Args;
+use clap::{Args, ValueEnum};
 use tempdir::TempDir;
+use tokio::sync::broadcast::Receiver;
 
-use reaboot_core::api::InstallerConfig;
-use reaboot_core::installer::{InstallError, Installer, InstallerNewArgs};
+use reaboot_core::api::{AutoInstallMode, InstallerConfig};
+use reaboot_core::installer::{InstallError, Installer, InstallerListener, InstallerNewArgs};
+use reaboot_core::lockfile::Lockfile;
+use reaboot_core::reaboot_util::create_http_client_for_config;
+use reaboot_core::PreparationReport;
 
+use crate::commands::http_args::HttpArgs;
+use crate::commands::install::json_listener::{JsonEvent, JsonLinesInstallerListener};
 use crate::commands::install::license_agreement::confirm_license;
 use crate::commands::install::listener::CliInstallerListener;
 use crate::commands::install::report::print_report;
+use crate::commands::reapack_settings_args::ReaPackSettingsArgs;
+use crate::commands::recipe_args::RecipeSelectionArgs;
 
+pub(crate) mod json_listener;
 mod license_agreement;
-mod listener;
-mod report;
+pub(crate) mod listener;
+pub(crate) mod report;
+
+/// How the installer reports progress and results.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Progress bars and a report for display in the terminal.
+    #[default]
+    Human,
+    /// One JSON object per line on stdout, for consumption by scripts.
+    JsonLines,
+}
 
 #[derive(Debug, Args)]
 #[command(author, version, about, long_about = None)]
@@ -37,15 +57,21 @@ pub struct InstallArgs {
     /// Determines the maximum number of concurrent downloads.
     #[arg(long, default_value_t = 5)]
     concurrent_downloads: u32,
+    /// Limits the number of concurrent downloads from the same host.
+    #[arg(long)]
+    concurrent_downloads_per_host: Option<u32>,
+    /// Limits the combined download rate (in bytes per second).
+    #[arg(long)]
+    max_download_rate: Option<u64>,
     /// If set, skips the last step of actually moving everything to the destination directory.
     #[arg(long, default_value_t = false)]
     dry_run: bool,
     /// If set, skips all license prompts.
     #[arg(long, default_value_t = false)]
     accept_licenses: bool,
-    /// If set, ReaBoot won't prompt you for anything. At the moment, this only means automatically
-    /// accepting licenses. In the future, there might be more interactivity that this flag will
-    /// suppress.
+    /// If set, ReaBoot won't prompt you for anything. At the moment, this means automatically
+    /// accepting licenses and not asking before auto-installing packages of new remotes. In the
+    /// future, there might be more interactivity that this flag will suppress.
     #[arg(long, default_value_t = false)]
     non_interactive: bool,
     /// If set, packages that couldn't be downloaded or are not installable for other reasons will
@@ -67,6 +93,66 @@ pub struct InstallArgs {
     /// URLs of ReaPack packages to be installed.
     #[arg(short, long)]
     package_url: Option<Vec<String>>,
+    /// Installs the requested packages in exactly the versions and files pinned in the given
+    /// lockfile (see `reaboot lock`).
+    ///
+    /// Package URLs not contained in the lockfile are refused. Downloaded files whose hash
+    /// doesn't match the lockfile are treated as failure. If neither package URLs nor a recipe
+    /// are given, installs all packages contained in the lockfile.
+    #[arg(long)]
+    locked: Option<PathBuf>,
+    /// If set, downloads all package files even if they are contained in the download cache.
+    #[arg(long, default_value_t = false)]
+    no_download_cache: bool,
+    /// Custom download cache directory.
+    ///
+    /// If not provided, ReaBoot uses `reaboot/downloads` within the user cache directory.
+    #[arg(long)]
+    download_cache_dir: Option<PathBuf>,
+    /// Installs from the given offline bundle (see `reaboot bundle`) without accessing the
+    /// network.
+    ///
+    /// The requested packages are pinned to the versions contained in the bundle, just like with
+    /// `--locked`. If neither package URLs nor a recipe are given, installs all packages
+    /// contained in the bundle. REAPER is only installed if the bundle contains a REAPER
+    /// installer.
+    #[arg(long)]
+    from_bundle: Option<PathBuf>,
+    /// If set, installs all packages of remotes that ReaBoot adds or enables and that have
+    /// auto-install enabled, just like ReaPack would do on its next synchronization.
+    ///
+    /// Auto-install is enabled for a remote if the recipe says so or, if not specified, if
+    /// ReaPack's global auto-install setting is enabled (see `--reapack-auto-install`).
+    ///
+    /// ReaBoot asks for confirmation before installing these packages, unless
+    /// `--non-interactive` is set.
+    #[arg(long, default_value_t = false)]
+    auto_install_new_remotes: bool,
+    #[command(flatten)]
+    recipe: RecipeSelectionArgs,
+    /// Output format.
+    ///
+    /// With `json-lines`, license prompts are not available, so you need to pass
+    /// `--accept-licenses` or `--non-interactive` if REAPER is going to be installed.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+    #[command(flatten)]
+    http: HttpArgs,
+    #[command(flatten)]
+    reapack_settings: ReaPackSettingsArgs,
+    /// If set, the recipe may change the network security settings of ReaPack (`proxy` and
+    /// `verify_peer`).
+    ///
+    /// By default, these settings of the recipe are ignored.
+    #[arg(long, default_value_t = false)]
+    allow_recipe_network_settings: bool,
+}
+
+impl InstallArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        self.output == OutputFormat::Human
+    }
 }
 
 pub async fn install(args: InstallArgs) -> anyhow::Result<()> {
@@ -74,7 +160,11 @@ pub async fn install(args: InstallArgs) -> anyhow::Result<()> {
         .reaper_version
         .parse()
         .context("You didn't provide a valid REAPER version string.")?;
-    let config = InstallerConfig {
+    let lockfile = args
+        .locked
+        .map(|path| Lockfile::load_from_file(&path))
+        .transpose()?;
+    let mut config = InstallerConfig {
         custom_reaper_resource_dir: args.reaper_resource_dir,
         custom_platform: None,
         package_urls: args.package_url.unwrap_or_default(),
@@ -83,55 +173,147 @@ pub async fn install(args: InstallArgs) -> anyhow::Result<()> {
         installation_id: None,
         keep_temp_dir: args.keep_temp_dir,
         concurrent_downloads: Some(args.concurrent_downloads),
+        concurrent_downloads_per_host: args.concurrent_downloads_per_host,
+        max_download_bytes_per_second: args.max_download_rate,
         dry_run: args.dry_run,
         reaper_version: Some(reaper_version),
         update_reaper: args.update_reaper,
         skip_failed_packages: args.skip_failed_packages,
+        // Includes are resolved when loading the recipe
         recipe: None,
+        recipe_location: None,
         selected_features: Default::default(),
         install_reaper: None,
         install_reapack: None,
+        update_installed_packages: None,
+        lockfile,
+        use_download_cache: Some(!args.no_download_cache),
+        download_cache_dir: args.download_cache_dir,
+        max_download_cache_size: None,
+        bundle: args.from_bundle,
+        http: Some(args.http.into_http_config()?),
+        reapack_settings: Some(args.reapack_settings.into_reapack_settings()),
+        allow_recipe_network_settings: Some(args.allow_recipe_network_settings),
+        auto_install_mode: Some(if !args.auto_install_new_remotes {
+            AutoInstallMode::Off
+        } else if args.non_interactive {
+            AutoInstallMode::NewRemotes
+        } else {
+            AutoInstallMode::AskForNewRemotes
+        }),
     };
+    let (recipe, selected_features) = args
+        .recipe
+        .resolve(&create_http_client_for_config(&config)?)
+        .await?;
+    config.recipe = recipe;
+    config.selected_features = selected_features;
+    let skip_license_prompts = args.non_interactive || args.accept_licenses;
+    run_installer(config, skip_license_prompts, args.output).await
+}
+
+/// Runs the installer with the given config, reporting progress and the final report in the
+/// given output format.
+pub async fn run_installer(
+    config: InstallerConfig,
+    skip_license_prompts: bool,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
     let (interaction_sender, interaction_receiver) = tokio::sync::broadcast::channel(10);
-    let listener = CliInstallerListener::new(interaction_sender);
+    match output {
+        OutputFormat::Human => {
+            let listener = CliInstallerListener::new(interaction_sender);
+            run_installer_with_listener(
+                config,
+                skip_license_prompts,
+                output,
+                interaction_receiver,
+                listener,
+            )
+            .await
+        }
+        OutputFormat::JsonLines => {
+            let listener = JsonLinesInstallerListener::new(interaction_sender);
+            let result = run_installer_with_listener(
+                config,
+                skip_license_prompts,
+                output,
+                interaction_receiver,
+                listener,
+            )
+            .await;
+            if let Err(e) = &result {
+                JsonEvent::Error {
+                    message: format!("{e:#}"),
+                }
+                .emit();
+            }
+            result
+        }
+    }
+}
+
+async fn run_installer_with_listener<L: InstallerListener>(
+    config: InstallerConfig,
+    skip_license_prompts: bool,
+    output: OutputFormat,
+    interactions: Receiver<bool>,
+    listener: L,
+) -> anyhow::Result<()> {
     let temp_dir_for_reaper_download = TempDir::new("reaboot-")
         .context("couldn't create temporary directory for REAPER download")?;
     let installer_new_args = InstallerNewArgs {
         config,
         temp_dir_for_reaper_download: temp_dir_for_reaper_download.path().to_path_buf(),
-        interactions: interaction_receiver,
+        interactions,
         listener,
     };
     let installer = Installer::new(installer_new_args).await?;
     // Show REAPER EULA if necessary
-    let skip_license_prompts = args.non_interactive || args.accept_licenses;
     let resolved_config = installer.resolved_config();
     if !skip_license_prompts
         && !resolved_config.reaper_exe_exists
         && resolved_config.reaper_is_installable
         && resolved_config.install_reaper
-        && !confirm_license().await?
     {
-        println!("You haven't agreed to the license terms. Exiting.");
-        return Ok(());
+        if output == OutputFormat::JsonLines {
+            bail!("REAPER is going to be installed, which requires accepting its license. Pass `--accept-licenses` if you agree to the license terms.");
+        }
+        if !confirm_license(installer.http_client()).await? {
+            println!("You haven't agreed to the license terms. Exiting.");
+            return Ok(());
+        }
     }
     // Install everything
-    println!("Starting installation process...\n");
+    if output == OutputFormat::Human {
+        println!("Starting installation process...\n");
+    }
     match installer.install().await {
         Ok(outcome) => {
-            print_report(
+            report(
+                output,
                 &outcome.preparation_report,
                 outcome.actually_installed_things,
             );
             if let Some(installer) = outcome.manual_reaper_install_path {
                 // This makes the REAPER download temp directory survive.
                 temp_dir_for_reaper_download.into_path();
-                eprintln!("\nReaBoot couldn't install REAPER automatically. Please do it manually instead! The installer is located here:\n{installer:?}");
+                match output {
+                    OutputFormat::Human => {
+                        eprintln!("\nReaBoot couldn't install REAPER automatically. Please do it manually instead! The installer is located here:\n{installer:?}");
+                    }
+                    OutputFormat::JsonLines => {
+                        JsonEvent::ManualReaperInstallRequired {
+                            installer: &installer,
+                        }
+                        .emit();
+                    }
+                }
             }
         }
         Err(e) => match e {
             InstallError::SomePackagesFailed(r) => {
-                print_report(&r, false);
+                report(output, &r, false);
                 Err(InstallError::SomePackagesFailed(r))?;
             }
             InstallError::Other(e) => {
@@ -141,3 +323,14 @@ pub async fn install(args: InstallArgs) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+fn report(output: OutputFormat, report: &PreparationReport, actually_installed_things: bool) {
+    match output {
+        OutputFormat::Human => print_report(report, actually_installed_things),
+        OutputFormat::JsonLines => JsonEvent::Report {
+            actually_installed_things,
+            report: &report.to_info(),
+        }
+        .emit(),
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use reaboot_core::api::InstalledPackageInfo;
+use reaboot_core::installed_packages::{list_installed_packages, InstalledPackageFilter};
+use reaboot_core::reaboot_util::resolve_reaper_resource_dir;
+use reaboot_reapack::model::PackageType;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct ListArgs {
+    /// Custom REAPER resource directory whose packages should be listed.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long)]
+    reaper_resource_dir: Option<PathBuf>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+    format: ListFormat,
+    /// Only lists packages from the given remote (can be provided multiple times).
+    #[arg(long)]
+    remote: Option<Vec<String>>,
+    /// Only lists packages of the given type, e.g. `script` or `extension` (can be provided
+    /// multiple times).
+    #[arg(long = "type", value_name = "TYPE")]
+    typ: Option<Vec<PackageType>>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ListFormat {
+    /// Table for display in the terminal.
+    Table,
+    /// JSON array.
+    Json,
+    /// Markdown table.
+    Markdown,
+}
+
+impl ListArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        matches!(self.format, ListFormat::Table)
+    }
+}
+
+pub async fn list(args: ListArgs) -> anyhow::Result<()> {
+    let (reaper_resource_dir, _) = resolve_reaper_resource_dir(args.reaper_resource_dir)?;
+    let filter = InstalledPackageFilter {
+        remotes: args.remote.unwrap_or_default(),
+        types: args.typ.unwrap_or_default(),
+    };
+    let packages = list_installed_packages(&reaper_resource_dir, &filter).await?;
+    match args.format {
+        ListFormat::Table => {
+            let markdown = build_markdown_table(&packages, true);
+            termimad::print_text(&markdown);
+        }
+        ListFormat::Json => {
+            let json = serde_json::to_string_pretty(&packages)?;
+            println!("{json}");
+        }
+        ListFormat::Markdown => {
+            print!("{}", build_markdown_table(&packages, false));
+        }
+    }
+    Ok(())
+}
+
+fn build_markdown_table(packages: &[InstalledPackageInfo], optimize_for_termimad: bool) -> String {
+    let mut markdown = String::new();
+    if optimize_for_termimad {
+        // Termimad needs this in order to print the top table border
+        markdown += "|:-|:-|:-|:-|:-|:-|-:\n";
+    }
+    markdown += "|**Remote**|**Category**|**Package**|**Version**|**Author**|**Type**|**Files**\n";
+    markdown += "|:-|:-|:-|:-|:-|:-|-:\n";
+    for p in packages {
+        markdown += &format!(
+            "|{}|{}|{}|{}|{}|{}|{}\n",
+            escape_cell(&p.remote),
+            escape_cell(&p.category),
+            escape_cell(&p.package),
+            escape_cell(&p.version),
+            escape_cell(&p.author),
+            p.typ,
+            p.file_count
+        );
+    }
+    if optimize_for_termimad {
+        // Termimad needs this in order to print the bottom table border
+        markdown += "|-|-|-|-|-|-|-\n";
+    }
+    markdown
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Args;
+use tempdir::TempDir;
+
+use reaboot_core::api::InstallerConfig;
+use reaboot_core::installer::{InstallError, Installer, InstallerNewArgs};
+use reaboot_core::reaboot_util::create_http_client_for_config;
+use reaboot_core::reaper_platform::ReaperPlatform;
+
+use crate::commands::http_args::HttpArgs;
+use crate::commands::install::listener::CliInstallerListener;
+use crate::commands::install::report::print_report;
+use crate::commands::recipe_args::RecipeSelectionArgs;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct LockArgs {
+    /// Path of the lockfile to be written.
+    #[arg(short, long, default_value = "reaboot-lock.json")]
+    output: PathBuf,
+    /// URLs of ReaPack packages to be locked.
+    #[arg(short, long)]
+    package_url: Option<Vec<String>>,
+    /// Platform for which to lock the packages, e.g. `windows-x64`.
+    ///
+    /// If not provided, ReaBoot uses the platform on which it's running.
+    #[arg(long, value_parser = parse_platform)]
+    platform: Option<ReaperPlatform>,
+    /// If set, doesn't include ReaPack itself in the lockfile.
+    #[arg(long, default_value_t = false)]
+    skip_reapack: bool,
+    /// Determines the maximum number of concurrent downloads.
+    #[arg(long, default_value_t = 5)]
+    concurrent_downloads: u32,
+    #[command(flatten)]
+    recipe: RecipeSelectionArgs,
+    #[command(flatten)]
+    http: HttpArgs,
+}
+
+/// Resolves packages to exact versions and writes them into a lockfile.
+pub async fn lock(args: LockArgs) -> anyhow::Result<()> {
+    // We don't install anything, so we use the OS temp dir
+    let temp_parent_dir =
+        TempDir::new("reaboot-lock-").context("couldn't create temporary directory for locking")?;
+    let mut config = InstallerConfig {
+        custom_platform: args.platform,
+        package_urls: args.package_url.unwrap_or_default(),
+        temp_parent_dir: Some(temp_parent_dir.path().to_path_buf()),
+        concurrent_downloads: Some(args.concurrent_downloads),
+        install_reaper: Some(false),
+        install_reapack: Some(!args.skip_reapack),
+        http: Some(args.http.into_http_config()?),
+        ..Default::default()
+    };
+    // Lock the packages of the selected recipe features
+    let (recipe, selected_features) = args
+        .recipe
+        .resolve(&create_http_client_for_config(&config)?)
+        .await?;
+    config.recipe = recipe;
+    config.selected_features = selected_features;
+    let (interaction_sender, interaction_receiver) = tokio::sync::broadcast::channel(10);
+    let installer_new_args = InstallerNewArgs {
+        config,
+        temp_dir_for_reaper_download: temp_parent_dir.path().to_path_buf(),
+        interactions: interaction_receiver,
+        listener: CliInstallerListener::new(interaction_sender),
+    };
+    let installer = Installer::new_for_locking(installer_new_args).await?;
+    println!("Resolving packages...\n");
+    match installer.lock().await {
+        Ok(lockfile) => {
+            lockfile.save_to_file(&args.output)?;
+            println!(
+                "\nLocked {} packages in {:?}",
+                lockfile.packages.len(),
+                &args.output
+            );
+        }
+        Err(InstallError::SomePackagesFailed(r)) => {
+            print_report(&r, false);
+            Err(InstallError::SomePackagesFailed(r))?;
+        }
+        Err(InstallError::Other(e)) => {
+            Err(e.context("Locking failed"))?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_platform(value: &str) -> Result<ReaperPlatform, serde_json::Error> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+}
@@ -1 +1,15 @@
+pub mod bundle;
+pub mod cache;
+pub mod http_args;
+pub mod info;
 pub mod install;
+pub mod list;
+pub mod lock;
+pub mod reapack_settings_args;
+pub mod recipe;
+pub mod recipe_args;
+pub mod remote;
+pub mod restore;
+pub mod search;
+pub mod uninstall;
+pub mod update;
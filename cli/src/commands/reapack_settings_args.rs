@@ -0,0 +1,48 @@
+use clap::Args;
+
+use reaboot_core::api::ReaPackSettings;
+
+/// ReaPack settings to be written to `reapack.ini`.
+///
+/// Settings that are not provided keep their current value.
+#[derive(Debug, Args)]
+pub struct ReaPackSettingsArgs {
+    /// Whether ReaPack should automatically install new packages from enabled remotes.
+    #[arg(long)]
+    reapack_auto_install: Option<bool>,
+    /// Whether ReaPack should consider pre-releases for all packages.
+    ///
+    /// ReaBoot honors this setting as well: If enabled (here, in the recipe or in the existing
+    /// `reapack.ini`), `latest` resolves to the latest version including pre-releases.
+    #[arg(long)]
+    reapack_pre_releases: Option<bool>,
+    /// Whether ReaPack should prompt to uninstall packages that are no longer available.
+    #[arg(long)]
+    reapack_prompt_obsolete: Option<bool>,
+    /// Proxy ReaPack should use, in the form `host:port` (empty for no proxy).
+    #[arg(long)]
+    reapack_proxy: Option<String>,
+    /// Whether ReaPack should verify SSL certificates.
+    #[arg(long)]
+    reapack_verify_peer: Option<bool>,
+    /// Number of seconds after which ReaPack downloads a cached repository index again.
+    #[arg(long)]
+    reapack_stale_threshold: Option<u64>,
+    /// Network timeout of ReaPack in seconds.
+    #[arg(long)]
+    reapack_network_timeout: Option<u32>,
+}
+
+impl ReaPackSettingsArgs {
+    pub fn into_reapack_settings(self) -> ReaPackSettings {
+        ReaPackSettings {
+            auto_install: self.reapack_auto_install,
+            pre_releases: self.reapack_pre_releases,
+            prompt_obsolete: self.reapack_prompt_obsolete,
+            proxy: self.reapack_proxy,
+            verify_peer: self.reapack_verify_peer,
+            stale_threshold: self.reapack_stale_threshold,
+            network_timeout: self.reapack_network_timeout,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{bail, ensure, Context};
+use clap::{Args, Subcommand};
+use tempdir::TempDir;
+
+use reaboot_core::api::InstallerConfig;
+use reaboot_core::reaboot_util::{create_downloader_for_config, create_http_client_for_config};
+use reaboot_core::reaper_platform::ReaperPlatform;
+use reaboot_core::recipe::{load_recipe, load_recipe_json, Recipe, RecipePackage};
+use reaboot_core::recipe_check::{check_recipe, validate_recipe_json};
+
+use crate::commands::http_args::HttpArgs;
+use crate::commands::lock::parse_platform;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct RecipeArgs {
+    #[command(subcommand)]
+    command: RecipeCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum RecipeCommand {
+    /// Shows the features and packages of a recipe.
+    Show {
+        /// Path or URL of the recipe.
+        recipe: String,
+        #[command(flatten)]
+        http: HttpArgs,
+    },
+    /// Checks a recipe for problems such as invalid or missing packages.
+    ///
+    /// Validates the recipe and the recipes it includes against the JSON schema, looks up each
+    /// package (of all features) in its repository and checks whether the packages can be
+    /// installed together on each platform. Exits with a non-zero exit code if it finds any
+    /// problem.
+    Check {
+        /// Path or URL of the recipe.
+        recipe: String,
+        /// Platform to be checked, e.g. `windows-x64` (can be provided multiple times).
+        ///
+        /// If not provided, ReaBoot checks all platforms.
+        #[arg(long, value_parser = parse_platform)]
+        platform: Vec<ReaperPlatform>,
+        #[command(flatten)]
+        http: HttpArgs,
+    },
+}
+
+pub async fn recipe(args: RecipeArgs) -> anyhow::Result<()> {
+    match args.command {
+        RecipeCommand::Show { recipe, http } => {
+            let config = InstallerConfig {
+                http: Some(http.into_http_config()?),
+                ..Default::default()
+            };
+            let client = create_http_client_for_config(&config)?;
+            let recipe = load_recipe(&recipe, &client).await?;
+            termimad::print_text(&build_markdown(&recipe));
+        }
+        RecipeCommand::Check {
+            recipe,
+            platform,
+            http,
+        } => {
+            let config = InstallerConfig {
+                http: Some(http.into_http_config()?),
+                ..Default::default()
+            };
+            check(&recipe, &config, platform).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn check(
+    location: &str,
+    config: &InstallerConfig,
+    platforms: Vec<ReaperPlatform>,
+) -> anyhow::Result<()> {
+    let client = create_http_client_for_config(config)?;
+    // Validate the recipe and each included recipe against the schema
+    let validate = |location: &str, json: &str| -> anyhow::Result<()> {
+        let schema_violations = validate_recipe_json(json)
+            .with_context(|| format!("recipe {location} is not valid JSON"))?;
+        for violation in &schema_violations {
+            println!("- {violation}");
+        }
+        ensure!(
+            schema_violations.is_empty(),
+            "Recipe {location} doesn't match the recipe schema ({} problems)",
+            schema_violations.len()
+        );
+        Ok(())
+    };
+    let json = load_recipe_json(location, &client).await?;
+    validate(location, &json)?;
+    let recipe: Recipe = serde_json::from_str(&json).context("couldn't parse recipe")?;
+    let recipe = recipe
+        .resolve_includes_inspecting(Some(location), &client, validate)
+        .await?;
+    let platforms = if platforms.is_empty() {
+        ReaperPlatform::all().collect()
+    } else {
+        platforms
+    };
+    let temp_dir = TempDir::new("reaboot-check-").context("couldn't create temporary directory")?;
+    let downloader = create_downloader_for_config(config)?;
+    println!("Checking recipe \"{}\"...\n", recipe.name);
+    let problems = check_recipe(&recipe, &downloader, temp_dir.path(), &platforms).await;
+    for problem in &problems {
+        match problem.platform {
+            None => println!("- {}", problem.message),
+            Some(p) => println!("- [{p:?}] {}", problem.message),
+        }
+    }
+    if !problems.is_empty() {
+        bail!("Recipe check found {} problems", problems.len());
+    }
+    println!("No problems found.");
+    Ok(())
+}
+
+fn build_markdown(recipe: &Recipe) -> String {
+    let mut markdown = format!("# {}\n", recipe.name);
+    if let Some(sub_title) = &recipe.sub_title {
+        markdown += &format!("*{sub_title}*\n");
+    }
+    if let Some(description) = &recipe.description {
+        markdown += &format!("\n{description}\n");
+    }
+    if let Some(website) = &recipe.website {
+        markdown += &format!("\nWebsite: {website}\n");
+    }
+    markdown += "\n## Required packages\n";
+    let required_packages = recipe.required_packages.as_deref().unwrap_or_default();
+    if required_packages.is_empty() {
+        markdown += "None\n";
+    }
+    for package in required_packages {
+        markdown += &format!("- {}\n", describe_package(package));
+    }
+    if let Some(remotes) = recipe.remotes.as_ref().filter(|r| !r.is_empty()) {
+        markdown += "\n## Remotes\n";
+        for remote in remotes {
+            let disabled_suffix = if remote.enabled == Some(false) {
+                " (disabled)"
+            } else {
+                ""
+            };
+            markdown += &format!("- **{}**: {}{disabled_suffix}\n", remote.name, remote.url);
+        }
+    }
+    markdown += "\n## Features\n";
+    let Some(features) = recipe.features.as_ref().filter(|f| !f.is_empty()) else {
+        markdown += "None\n";
+        return markdown;
+    };
+    // Termimad needs this in order to print the top table border
+    markdown += "|:-|:-|:-|:-\n";
+    markdown += "|**ID**|**Name**|**Default**|**Packages**\n";
+    markdown += "|:-|:-|:-|:-\n";
+    for (id, feature) in features {
+        let packages: Vec<_> = feature
+            .packages
+            .iter()
+            .flatten()
+            .map(describe_package)
+            .collect();
+        markdown += &format!(
+            "|{}|{}|{}|{}\n",
+            escape_cell(id),
+            escape_cell(&feature.name),
+            if feature.default == Some(true) {
+                "yes"
+            } else {
+                "no"
+            },
+            escape_cell(&packages.join(" "))
+        );
+        // Termimad needs this in order to print the row border
+        markdown += "|-|-|-|-\n";
+    }
+    markdown
+}
+
+/// Returns the package URL followed by the package conditions, if any.
+fn describe_package(package: &RecipePackage) -> String {
+    let RecipePackage::Conditional(p) = package else {
+        return package.url().to_string();
+    };
+    let mut conditions = vec![];
+    if let Some(platforms) = &p.platforms {
+        let platforms: Vec<_> = platforms.iter().map(|p| format!("{p:?}")).collect();
+        conditions.push(format!("only on {}", platforms.join("/")));
+    }
+    if let Some(v) = &p.min_reaper_version {
+        conditions.push(format!("REAPER >= {v}"));
+    }
+    if let Some(v) = &p.max_reaper_version {
+        conditions.push(format!("REAPER <= {v}"));
+    }
+    if p.portable_only == Some(true) {
+        conditions.push("portable only".to_string());
+    }
+    if conditions.is_empty() {
+        return p.url.clone();
+    }
+    format!("{} ({})", p.url, conditions.join(", "))
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
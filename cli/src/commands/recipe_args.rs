@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+use clap::Args;
+
+use reaboot_core::http_client::HttpClient;
+use reaboot_core::recipe::{load_recipe, Recipe};
+
+/// Selection of a recipe and its features.
+#[derive(Debug, Args)]
+pub struct RecipeSelectionArgs {
+    /// Path or URL of a recipe whose packages should be included.
+    #[arg(long)]
+    recipe: Option<String>,
+    /// ID of a recipe feature to be included (can be provided multiple times).
+    #[arg(long, requires = "recipe")]
+    feature: Vec<String>,
+    /// If set, includes all features of the recipe.
+    #[arg(long, default_value_t = false, requires = "recipe")]
+    all_features: bool,
+    /// If set, includes the features that the recipe marks as default.
+    #[arg(long, default_value_t = false, requires = "recipe")]
+    default_features: bool,
+}
+
+impl RecipeSelectionArgs {
+    /// Loads the recipe (if any) and returns it together with the IDs of the selected features.
+    pub async fn resolve(
+        self,
+        client: &HttpClient,
+    ) -> anyhow::Result<(Option<Recipe>, HashSet<String>)> {
+        let Some(recipe) = &self.recipe else {
+            return Ok((None, HashSet::new()));
+        };
+        let recipe = load_recipe(recipe, client).await?;
+        for id in &self.feature {
+            if !recipe.feature_ids().any(|f| f == id) {
+                bail!("Recipe doesn't have a feature with ID {id:?}");
+            }
+        }
+        let mut selected_features: HashSet<String> = self.feature.into_iter().collect();
+        if self.all_features {
+            selected_features.extend(recipe.feature_ids().cloned());
+        }
+        if self.default_features {
+            selected_features.extend(recipe.default_feature_ids().cloned());
+        }
+        // Report unsatisfiable selections before downloading anything
+        recipe.resolve_features(&selected_features)?;
+        Ok((Some(recipe), selected_features))
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use url::Url;
+
+use reaboot_core::api::RemoteInfo;
+use reaboot_core::reaboot_util::resolve_reaper_resource_dir;
+use reaboot_core::remotes::{change_remotes, list_remotes, RemoteChange};
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct RemoteArgs {
+    /// Custom REAPER resource directory whose ReaPack remotes should be managed.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long, global = true)]
+    reaper_resource_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: RemoteCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum RemoteCommand {
+    /// Lists the remotes (repositories) configured in ReaPack.
+    List {
+        /// If set, prints the remotes as JSON array.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Adds a remote.
+    ///
+    /// If a remote with the same name and URL exists already, its settings are updated.
+    Add {
+        /// Name of the remote.
+        name: String,
+        /// URL of the repository index.
+        url: Url,
+        /// If set, adds the remote in disabled state.
+        #[arg(long, default_value_t = false)]
+        disabled: bool,
+        /// Whether ReaPack should automatically install new packages from this remote.
+        ///
+        /// If not provided, the global ReaPack setting applies.
+        #[arg(long)]
+        auto_install: Option<bool>,
+    },
+    /// Removes a remote, leaving its installed packages untouched.
+    Remove {
+        /// Name of the remote.
+        name: String,
+    },
+    /// Enables a remote.
+    Enable {
+        /// Name of the remote.
+        name: String,
+    },
+    /// Disables a remote.
+    Disable {
+        /// Name of the remote.
+        name: String,
+    },
+}
+
+impl RemoteArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        !matches!(self.command, RemoteCommand::List { json: true })
+    }
+}
+
+pub async fn remote(args: RemoteArgs) -> anyhow::Result<()> {
+    let (reaper_resource_dir, _) = resolve_reaper_resource_dir(args.reaper_resource_dir)?;
+    let change = match args.command {
+        RemoteCommand::List { json } => {
+            let remotes = list_remotes(&reaper_resource_dir)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&remotes)?);
+            } else {
+                termimad::print_text(&build_markdown_table(&remotes));
+            }
+            return Ok(());
+        }
+        RemoteCommand::Add {
+            name,
+            url,
+            disabled,
+            auto_install,
+        } => RemoteChange::Add {
+            name,
+            url,
+            enabled: !disabled,
+            auto_install,
+        },
+        RemoteCommand::Remove { name } => RemoteChange::Remove { name },
+        RemoteCommand::Enable { name } => RemoteChange::SetEnabled {
+            name,
+            enabled: true,
+        },
+        RemoteCommand::Disable { name } => RemoteChange::SetEnabled {
+            name,
+            enabled: false,
+        },
+    };
+    let backup_dir = change_remotes(&reaper_resource_dir, change, None).await?;
+    println!("Updated reapack.ini. The previous version has been backed up to {backup_dir:?}.");
+    Ok(())
+}
+
+fn build_markdown_table(remotes: &[RemoteInfo]) -> String {
+    // Termimad needs this in order to print the top table border
+    let mut markdown = "|:-|:-|:-|:-\n".to_string();
+    markdown += "|**Name**|**URL**|**Enabled**|**Auto-install**\n";
+    markdown += "|:-|:-|:-|:-\n";
+    for r in remotes {
+        let auto_install = match r.auto_install {
+            None => "global setting",
+            Some(true) => "yes",
+            Some(false) => "no",
+        };
+        markdown += &format!(
+            "|{}|{}|{}|{auto_install}\n",
+            escape_cell(&r.name),
+            escape_cell(&r.url),
+            if r.enabled { "yes" } else { "no" },
+        );
+    }
+    // Termimad needs this in order to print the bottom table border
+    markdown += "|-|-|-|-\n";
+    markdown
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use reaboot_core::reaboot_util::resolve_reaper_resource_dir;
+use reaboot_core::restorer::{
+    list_past_installations, restore_installation, PastInstallationsAsMarkdown,
+    RestorationReportAsMarkdown, RestorerConfig,
+};
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct RestoreArgs {
+    /// ID of the past installation to be reverted (see `--list`).
+    #[arg(required_unless_present = "list")]
+    installation_id: Option<String>,
+    /// Lists all past installations that can be reverted, together with the files they touched.
+    #[arg(long, default_value_t = false, conflicts_with = "installation_id")]
+    list: bool,
+    /// Custom REAPER resource directory.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long)]
+    reaper_resource_dir: Option<PathBuf>,
+    /// If set, only checks whether the installation could be reverted without actually
+    /// changing anything.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+pub async fn restore(args: RestoreArgs) -> anyhow::Result<()> {
+    let Some(installation_id) = args.installation_id else {
+        let (reaper_resource_dir, _) = resolve_reaper_resource_dir(args.reaper_resource_dir)?;
+        let installations = list_past_installations(&reaper_resource_dir)?;
+        let markdown = PastInstallationsAsMarkdown::new(&installations).to_string();
+        termimad::print_text(&markdown);
+        return Ok(());
+    };
+    let config = RestorerConfig {
+        custom_reaper_resource_dir: args.reaper_resource_dir,
+        installation_id,
+        restoration_id: None,
+        dry_run: args.dry_run,
+    };
+    let report = restore_installation(config).await?;
+    let markdown = RestorationReportAsMarkdown::new(&report).to_string();
+    termimad::print_text(&markdown);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Args;
+use tempdir::TempDir;
+
+use reaboot_core::api::{InstallerConfig, PackageSearchResult};
+use reaboot_core::reaboot_util::{create_downloader_for_config, resolve_reaper_resource_dir};
+use reaboot_core::search::{search_packages, PackageSearchQuery};
+use reaboot_reapack::model::PackageType;
+
+use crate::commands::http_args::HttpArgs;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct SearchArgs {
+    /// Search terms, e.g. `color palette`.
+    ///
+    /// All terms must match the package name, display name, category, author or description.
+    /// Similar words match as well.
+    query: Vec<String>,
+    /// Custom REAPER resource directory whose remotes should be searched.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long)]
+    reaper_resource_dir: Option<PathBuf>,
+    /// Only searches the given remote (can be provided multiple times).
+    #[arg(long)]
+    remote: Option<Vec<String>>,
+    /// Only finds packages of the given type, e.g. `script` or `extension` (can be provided
+    /// multiple times).
+    #[arg(long = "type", value_name = "TYPE")]
+    typ: Option<Vec<PackageType>>,
+    /// Whether to consider pre-releases when determining the latest version.
+    ///
+    /// If not provided, ReaBoot uses the pre-release setting of ReaPack.
+    #[arg(long)]
+    pre_releases: Option<bool>,
+    /// If set, doesn't download any repository index but uses the ones cached by ReaPack.
+    #[arg(long, default_value_t = false)]
+    offline: bool,
+    /// Maximum number of results to print.
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+    /// If set, prints the results as JSON array.
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    #[command(flatten)]
+    http: HttpArgs,
+}
+
+impl SearchArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        !self.json
+    }
+}
+
+pub async fn search(args: SearchArgs) -> anyhow::Result<()> {
+    let (reaper_resource_dir, _) = resolve_reaper_resource_dir(args.reaper_resource_dir)?;
+    let downloader = if args.offline {
+        None
+    } else {
+        let config = InstallerConfig {
+            http: Some(args.http.into_http_config()?),
+            ..Default::default()
+        };
+        Some(create_downloader_for_config(&config)?)
+    };
+    let query = PackageSearchQuery {
+        text: args.query.join(" "),
+        types: args.typ.unwrap_or_default(),
+        remotes: args.remote.unwrap_or_default(),
+        include_pre_releases: args.pre_releases,
+    };
+    let temp_dir =
+        TempDir::new("reaboot-search-").context("couldn't create temporary directory")?;
+    let mut outcome = search_packages(
+        &reaper_resource_dir,
+        downloader.as_ref(),
+        temp_dir.path(),
+        &query,
+    )
+    .await?;
+    for problem in &outcome.problems {
+        eprintln!("- {problem}");
+    }
+    outcome.results.truncate(args.limit);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outcome.results)?);
+    } else if outcome.results.is_empty() {
+        println!("No packages found.");
+    } else {
+        termimad::print_text(&build_markdown_table(&outcome.results));
+    }
+    Ok(())
+}
+
+fn build_markdown_table(results: &[PackageSearchResult]) -> String {
+    // Termimad needs the first line in order to print the top table border
+    let mut markdown = String::from("|:-|:-|:-|:-|:-\n");
+    markdown += "|**Package**|**Version**|**Author**|**Type**|**Package URL**\n";
+    markdown += "|:-|:-|:-|:-|:-\n";
+    for r in results {
+        let package = match &r.description {
+            Some(d) if d != &r.package => format!("{d} ({}/{})", r.category, r.package),
+            _ => format!("{}/{}", r.category, r.package),
+        };
+        markdown += &format!(
+            "|{}|{}|{}|{}|{}\n",
+            escape_cell(&package),
+            escape_cell(&r.version),
+            escape_cell(r.author.as_deref().unwrap_or_default()),
+            r.typ,
+            escape_cell(&r.package_url)
+        );
+    }
+    // Termimad needs this in order to print the bottom table border
+    markdown += "|-|-|-|-|-\n";
+    markdown
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use reaboot_core::uninstaller::{
+    uninstall_packages, UninstallationReportAsMarkdown, UninstallerConfig,
+};
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct UninstallArgs {
+    /// Custom REAPER resource directory from which to uninstall the packages.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long)]
+    reaper_resource_dir: Option<PathBuf>,
+    /// If set, only checks whether the packages could be uninstalled without actually
+    /// removing anything.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+    /// URLs of ReaPack packages to be uninstalled.
+    ///
+    /// The repository URL must correspond to a remote configured in ReaPack. Version refs are
+    /// ignored.
+    #[arg(short, long)]
+    package_url: Option<Vec<String>>,
+    /// IDs of ReaPack packages to be uninstalled, in the form `remote/category/package`.
+    #[arg(long)]
+    package_id: Option<Vec<String>>,
+}
+
+pub async fn uninstall(args: UninstallArgs) -> anyhow::Result<()> {
+    let config = UninstallerConfig {
+        custom_reaper_resource_dir: args.reaper_resource_dir,
+        package_urls: args.package_url.unwrap_or_default(),
+        package_ids: args.package_id.unwrap_or_default(),
+        installation_id: None,
+        dry_run: args.dry_run,
+    };
+    let report = uninstall_packages(config).await?;
+    let markdown = UninstallationReportAsMarkdown::new(&report).to_string();
+    termimad::print_text(&markdown);
+    Ok(())
+}
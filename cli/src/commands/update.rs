@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use reaboot_core::api::InstallerConfig;
+
+use crate::commands::http_args::HttpArgs;
+use crate::commands::install::{run_installer, OutputFormat};
+use crate::commands::reapack_settings_args::ReaPackSettingsArgs;
+
+#[derive(Debug, Args)]
+#[command(author, version, about, long_about = None)]
+pub struct UpdateArgs {
+    /// Custom REAPER resource directory in which to update the installed packages.
+    ///
+    /// If not provided, ReaBoot uses the main REAPER installation.
+    #[arg(long)]
+    reaper_resource_dir: Option<PathBuf>,
+    /// Creates the temporary directory for downloads within the given custom directory.
+    ///
+    /// If not provided, ReaBoot creates the temporary directory in `REAPER_RESOURCE_DIR/ReaBoot`.
+    #[arg(long)]
+    temp_parent_dir: Option<PathBuf>,
+    /// If set, doesn't delete the temporary directory when the update is finished.
+    #[arg(long, default_value_t = false)]
+    keep_temp_dir: bool,
+    /// Determines the maximum number of concurrent downloads.
+    #[arg(long, default_value_t = 5)]
+    concurrent_downloads: u32,
+    /// Limits the number of concurrent downloads from the same host.
+    #[arg(long)]
+    concurrent_downloads_per_host: Option<u32>,
+    /// Limits the combined download rate (in bytes per second).
+    #[arg(long)]
+    max_download_rate: Option<u64>,
+    /// If set, only shows which packages would be updated without actually updating them.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+    /// If set, packages that couldn't be downloaded or are not installable for other reasons will
+    /// be skipped and not considered as failure.
+    #[arg(long, default_value_t = false)]
+    skip_failed_packages: bool,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+    #[command(flatten)]
+    http: HttpArgs,
+    #[command(flatten)]
+    reapack_settings: ReaPackSettingsArgs,
+}
+
+impl UpdateArgs {
+    /// Returns whether the output is meant for humans (as opposed to machines).
+    pub fn has_human_readable_output(&self) -> bool {
+        self.output == OutputFormat::Human
+    }
+}
+
+/// Updates all packages installed via ReaPack, just like ReaPack's "Synchronize packages".
+pub async fn update(args: UpdateArgs) -> anyhow::Result<()> {
+    let config = InstallerConfig {
+        custom_reaper_resource_dir: args.reaper_resource_dir,
+        temp_parent_dir: args.temp_parent_dir,
+        keep_temp_dir: args.keep_temp_dir,
+        concurrent_downloads: Some(args.concurrent_downloads),
+        concurrent_downloads_per_host: args.concurrent_downloads_per_host,
+        max_download_bytes_per_second: args.max_download_rate,
+        dry_run: args.dry_run,
+        skip_failed_packages: args.skip_failed_packages,
+        // ReaPack itself is updated like any other installed package
+        install_reapack: Some(false),
+        install_reaper: Some(false),
+        update_installed_packages: Some(true),
+        http: Some(args.http.into_http_config()?),
+        reapack_settings: Some(args.reapack_settings.into_reapack_settings()),
+        ..Default::default()
+    };
+    run_installer(config, true, args.output).await
+}
@@ -1,4 +1,15 @@
+use crate::commands::bundle::{bundle, BundleArgs};
+use crate::commands::cache::{cache, CacheArgs};
+use crate::commands::info::{info, InfoArgs};
 use crate::commands::install::{install, InstallArgs};
+use crate::commands::list::{list, ListArgs};
+use crate::commands::lock::{lock, LockArgs};
+use crate::commands::recipe::{recipe, RecipeArgs};
+use crate::commands::remote::{remote, RemoteArgs};
+use crate::commands::restore::{restore, RestoreArgs};
+use crate::commands::search::{search, SearchArgs};
+use crate::commands::uninstall::{uninstall, UninstallArgs};
+use crate::commands::update::{update, UpdateArgs};
 use anyhow::Context;
 use clap::{Parser, Subcommand};
 
@@ -10,10 +21,25 @@ mod commands;
 async fn main() -> anyhow::Result<()> {
     let _ = init_tracing();
     let app = App::parse();
+    let human_readable_output = app.command.has_human_readable_output();
     match app.command {
         Command::Install(args) => install(args).await?,
+        Command::Uninstall(args) => uninstall(args).await?,
+        Command::List(args) => list(args).await?,
+        Command::Update(args) => update(args).await?,
+        Command::Lock(args) => lock(args).await?,
+        Command::Restore(args) => restore(args).await?,
+        Command::Cache(args) => cache(args).await?,
+        Command::Bundle(args) => bundle(args).await?,
+        Command::Recipe(args) => recipe(args).await?,
+        Command::Remote(args) => remote(args).await?,
+        Command::Search(args) => search(args).await?,
+        Command::Info(args) => info(args).await?,
+    }
+    // Machine-readable output must not contain anything else
+    if human_readable_output {
+        println!();
     }
-    println!();
     Ok(())
 }
 
@@ -26,6 +52,8 @@ fn init_tracing() -> anyhow::Result<()> {
         .with_thread_names(true)
         // .compact()
         .with_env_filter(env_filter)
+        // Keep stdout free for the actual output (e.g. JSON lines)
+        .with_writer(std::io::stderr)
         .finish();
     tracing::subscriber::set_global_default(subscriber)
         .context("setting default subscriber failed")?;
@@ -42,4 +70,34 @@ pub struct App {
 #[derive(Debug, Subcommand)]
 enum Command {
     Install(InstallArgs),
+    Uninstall(UninstallArgs),
+    List(ListArgs),
+    Update(UpdateArgs),
+    Lock(LockArgs),
+    Restore(RestoreArgs),
+    Cache(CacheArgs),
+    Bundle(BundleArgs),
+    Recipe(RecipeArgs),
+    Remote(RemoteArgs),
+    Search(SearchArgs),
+    Info(InfoArgs),
+}
+
+impl Command {
+    fn has_human_readable_output(&self) -> bool {
+        match self {
+            Command::Install(args) => args.has_human_readable_output(),
+            Command::List(args) => args.has_human_readable_output(),
+            Command::Update(args) => args.has_human_readable_output(),
+            Command::Remote(args) => args.has_human_readable_output(),
+            Command::Search(args) => args.has_human_readable_output(),
+            Command::Info(args) => args.has_human_readable_output(),
+            Command::Uninstall(_)
+            | Command::Lock(_)
+            | Command::Restore(_)
+            | Command::Cache(_)
+            | Command::Bundle(_)
+            | Command::Recipe(_) => true,
+        }
+    }
 }
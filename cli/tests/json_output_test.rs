@@ -0,0 +1,39 @@
+use std::fs;
+use std::process::Command;
+
+use tempdir::TempDir;
+
+/// With `--output json-lines`, stdout should only contain JSON events, even if logging is
+/// enabled.
+#[test]
+fn json_lines_output_is_not_mixed_with_log_messages() {
+    let reaper_resource_dir = TempDir::new("reaboot-cli-test-").unwrap();
+    for file in ["reaper.ini", "reaper", "reaper.exe", "REAPER.app"] {
+        fs::write(reaper_resource_dir.path().join(file), "").unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_reaboot"))
+        .env("REABOOT_LOG", "debug")
+        .arg("install")
+        .arg("--output=json-lines")
+        .arg("--dry-run")
+        .arg("--non-interactive")
+        .arg("--reaper-resource-dir")
+        .arg(reaper_resource_dir.path())
+        // Make sure that nothing is downloaded from the internet
+        .arg("--proxy=http://127.0.0.1:1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        !stdout.trim().is_empty(),
+        "there should be some JSON events"
+    );
+    for line in stdout.lines() {
+        assert!(
+            serde_json::from_str::<serde_json::Value>(line).is_ok(),
+            "stdout line is not JSON: {line}"
+        );
+    }
+    // The log messages should still be there
+    assert!(!output.stderr.is_empty());
+}
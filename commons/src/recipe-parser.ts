@@ -10,22 +10,153 @@
 // match the expected interface, even if the JSON is valid.
 
 export interface Recipe {
-    author?: null | string;
     description?: null | string;
     features?: { [key: string]: Feature } | null;
+    /**
+     * Paths or URLs of other recipes whose packages and features should be merged into this
+     * one.
+     *
+     * Relative paths are resolved against the location of this recipe. Later includes take
+     * precedence over earlier ones and this recipe takes precedence over all of its includes.
+     */
+    includes?: string[] | null;
     name: string;
-    required_packages?: string[] | null;
+    /**
+     * ReaPack settings to be written to `reapack.ini`, e.g. whether to use pre-releases.
+     *
+     * The network security settings `proxy` and `verify_peer` are ignored unless the user
+     * allows them explicitly.
+     */
+    reapack_settings?: null | ReaPackSettings;
+    /**
+     * ReaPack remotes that should be registered, even if none of their packages is installed.
+     *
+     * Remotes that are registered already (same name or URL) are left untouched.
+     */
+    remotes?: RecipeRemote[] | null;
+    required_packages?: Array<ConditionalRecipePackage | string> | null;
     skip_additional_packages?: boolean | null;
+    sub_title?: null | string;
     website?: null | string;
 
     [property: string]: any;
 }
 
 export interface Feature {
+    /**
+     * IDs of features that can't be installed together with this feature.
+     */
+    conflicts_with?: string[] | null;
     default?: boolean | null;
     description?: null | string;
     name: string;
-    packages?: string[] | null;
+    packages?: Array<ConditionalRecipePackage | string> | null;
+    /**
+     * IDs of features that are automatically installed along with this feature.
+     */
+    requires?: string[] | null;
+
+    [property: string]: any;
+}
+
+/**
+ * Package URL together with the conditions that must be met in order to install the package.
+ */
+export interface ConditionalRecipePackage {
+    /**
+     * Maximum REAPER version (inclusive).
+     */
+    max_reaper_version?: null | string;
+    /**
+     * Minimum REAPER version (inclusive).
+     */
+    min_reaper_version?: null | string;
+    /**
+     * Platforms on which the package should be installed. If not set, all platforms qualify.
+     */
+    platforms?: ReaperPlatform[] | null;
+    /**
+     * If `true`, the package is only installed into portable REAPER installations.
+     */
+    portable_only?: boolean | null;
+    url: string;
+
+    [property: string]: any;
+}
+
+export enum ReaperPlatform {
+    LinuxAarch64 = "linux-aarch64",
+    LinuxArmv7L = "linux-armv7l",
+    LinuxI686 = "linux-i686",
+    LinuxX8664 = "linux-x86_64",
+    MACOSArm64 = "macos-arm64",
+    MACOSI386 = "macos-i386",
+    MACOSX8664 = "macos-x86_64",
+    WindowsX64 = "windows-x64",
+    WindowsX86 = "windows-x86",
+}
+
+/**
+ * ReaPack settings stored in `reapack.ini`.
+ *
+ * Settings that are not provided keep their current value (or ReaPack's default).
+ */
+export interface ReaPackSettings {
+    /**
+     * Whether ReaPack should automatically install new packages from enabled remotes.
+     */
+    auto_install?: boolean | null;
+    /**
+     * Network timeout of ReaPack in seconds.
+     */
+    network_timeout?: number | null;
+    /**
+     * Whether ReaPack should consider pre-releases for all packages, not just for the ones
+     * marked as "bleeding edge".
+     *
+     * ReaBoot honors this as well: If enabled, `latest` resolves to the latest version
+     * including pre-releases.
+     */
+    pre_releases?: boolean | null;
+    /**
+     * Whether ReaPack should prompt to uninstall packages that are no longer available in their
+     * repository.
+     */
+    prompt_obsolete?: boolean | null;
+    /**
+     * Proxy ReaPack should use, in the form `host:port`. An empty string means no proxy.
+     */
+    proxy?: null | string;
+    /**
+     * Number of seconds after which ReaPack downloads a cached repository index again.
+     */
+    stale_threshold?: number | null;
+    /**
+     * Whether ReaPack should verify SSL certificates.
+     */
+    verify_peer?: boolean | null;
+
+    [property: string]: any;
+}
+
+/**
+ * ReaPack remote (repository) to be registered in `reapack.ini`.
+ */
+export interface RecipeRemote {
+    /**
+     * Whether ReaPack should automatically install new packages from this remote. If not set,
+     * the global ReaPack setting applies.
+     */
+    auto_install?: boolean | null;
+    /**
+     * Whether the remote should be enabled. Defaults to `true`.
+     */
+    enabled?: boolean | null;
+    name: string;
+    /**
+     * URL of the repository index.
+     */
+    url: string;
 
     [property: string]: any;
 }
@@ -201,18 +332,56 @@ function r(name: string) {
 
 const typeMap: any = {
     "Recipe": o([
-        {json: "author", js: "author", typ: u(undefined, u(null, ""))},
         {json: "description", js: "description", typ: u(undefined, u(null, ""))},
         {json: "features", js: "features", typ: u(undefined, u(m(r("Feature")), null))},
+        {json: "includes", js: "includes", typ: u(undefined, u(a(""), null))},
         {json: "name", js: "name", typ: ""},
-        {json: "required_packages", js: "required_packages", typ: u(undefined, u(a(""), null))},
+        {json: "reapack_settings", js: "reapack_settings", typ: u(undefined, u(null, r("ReaPackSettings")))},
+        {json: "remotes", js: "remotes", typ: u(undefined, u(a(r("RecipeRemote")), null))},
+        {json: "required_packages", js: "required_packages", typ: u(undefined, u(a(u(r("ConditionalRecipePackage"), "")), null))},
         {json: "skip_additional_packages", js: "skip_additional_packages", typ: u(undefined, u(true, null))},
+        {json: "sub_title", js: "sub_title", typ: u(undefined, u(null, ""))},
         {json: "website", js: "website", typ: u(undefined, u(null, ""))},
     ], "any"),
     "Feature": o([
+        {json: "conflicts_with", js: "conflicts_with", typ: u(undefined, u(a(""), null))},
         {json: "default", js: "default", typ: u(undefined, u(true, null))},
         {json: "description", js: "description", typ: u(undefined, u(null, ""))},
         {json: "name", js: "name", typ: ""},
-        {json: "packages", js: "packages", typ: u(undefined, u(a(""), null))},
+        {json: "packages", js: "packages", typ: u(undefined, u(a(u(r("ConditionalRecipePackage"), "")), null))},
+        {json: "requires", js: "requires", typ: u(undefined, u(a(""), null))},
+    ], "any"),
+    "ConditionalRecipePackage": o([
+        {json: "max_reaper_version", js: "max_reaper_version", typ: u(undefined, u(null, ""))},
+        {json: "min_reaper_version", js: "min_reaper_version", typ: u(undefined, u(null, ""))},
+        {json: "platforms", js: "platforms", typ: u(undefined, u(a(r("ReaperPlatform")), null))},
+        {json: "portable_only", js: "portable_only", typ: u(undefined, u(true, null))},
+        {json: "url", js: "url", typ: ""},
+    ], "any"),
+    "ReaPackSettings": o([
+        {json: "auto_install", js: "auto_install", typ: u(undefined, u(true, null))},
+        {json: "network_timeout", js: "network_timeout", typ: u(undefined, u(0, null))},
+        {json: "pre_releases", js: "pre_releases", typ: u(undefined, u(true, null))},
+        {json: "prompt_obsolete", js: "prompt_obsolete", typ: u(undefined, u(true, null))},
+        {json: "proxy", js: "proxy", typ: u(undefined, u(null, ""))},
+        {json: "stale_threshold", js: "stale_threshold", typ: u(undefined, u(0, null))},
+        {json: "verify_peer", js: "verify_peer", typ: u(undefined, u(true, null))},
+    ], "any"),
+    "RecipeRemote": o([
+        {json: "auto_install", js: "auto_install", typ: u(undefined, u(true, null))},
+        {json: "enabled", js: "enabled", typ: u(undefined, u(true, null))},
+        {json: "name", js: "name", typ: ""},
+        {json: "url", js: "url", typ: ""},
     ], "any"),
+    "ReaperPlatform": [
+        "linux-aarch64",
+        "linux-armv7l",
+        "linux-i686",
+        "linux-x86_64",
+        "macos-arm64",
+        "macos-i386",
+        "macos-x86_64",
+        "windows-x64",
+        "windows-x86",
+    ],
 };
@@ -4,10 +4,13 @@ import {PackageVersionRef} from "../../reapack/bindings/PackageVersionRef";
 import {PackagePath} from "../../reapack/bindings/PackagePath";
 import {Convert} from "./recipe-parser";
 import {Feature} from "../../core/bindings/Feature";
+import {RecipePackage} from "../../core/bindings/RecipePackage";
 
 
 export type ParsedRecipe = {
     raw: Recipe,
+    // URL from which the recipe has been fetched (used for resolving relative includes).
+    location?: string,
     requiredPackages: PackageUrl[],
     features: Record<string, ParsedFeature>,
 }
@@ -109,7 +112,10 @@ async function fetchRecipeFromUrl(url: URL): Promise<ParsedRecipe> {
         throw new Error("Recipe URL doesn't return text");
     }
     // Parse response text as recipe
-    return parseRecipeFromRawString(text);
+    return {
+        ...parseRecipeFromRawString(text),
+        location: url.toString(),
+    };
 }
 
 function parseRecipeFromRawString(text: string): ParsedRecipe {
@@ -134,7 +140,7 @@ function parseRecipe(recipe: Recipe): ParsedRecipe {
 
     return {
         raw: recipe,
-        requiredPackages: getOrEmptyArray(recipe.required_packages).map(parsePackageUrlFromRawString),
+        requiredPackages: getOrEmptyArray(recipe.required_packages).map(parseRecipePackage),
         features: parsedFeatures,
     };
 }
@@ -142,10 +148,15 @@ function parseRecipe(recipe: Recipe): ParsedRecipe {
 function parseFeature(feature: Feature): ParsedFeature {
     return {
         raw: feature,
-        packages: getOrEmptyArray(feature.packages).map(parsePackageUrlFromRawString),
+        packages: getOrEmptyArray(feature.packages).map(parseRecipePackage),
     };
 }
 
+// Conditions of conditional package entries are evaluated by the ReaBoot backend, not here.
+function parseRecipePackage(entry: RecipePackage): PackageUrl {
+    return parsePackageUrlFromRawString(typeof entry === "string" ? entry : entry.url);
+}
+
 function getOrNull<R>(f: () => R): R | null {
     try {
         return f();
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Determines whether ReaBoot installs packages of remotes that have auto-install enabled.
+ *
+ * Auto-install is enabled for a remote if the remote says so or, if the remote doesn't
+ * specify it, if ReaPack's global auto-install setting is enabled.
+ */
+export type AutoInstallMode = "Off" | "NewRemotes" | "AskForNewRemotes";
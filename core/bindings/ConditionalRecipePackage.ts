@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReaperPlatform } from "./ReaperPlatform";
+
+/**
+ * Package URL together with the conditions that must be met in order to install the package.
+ */
+export type ConditionalRecipePackage = { url: string, 
+/**
+ * Platforms on which the package should be installed. If not set, all platforms qualify.
+ */
+platforms?: Array<ReaperPlatform> | null, 
+/**
+ * Minimum REAPER version (inclusive).
+ */
+min_reaper_version?: string | null, 
+/**
+ * Maximum REAPER version (inclusive).
+ */
+max_reaper_version?: string | null, 
+/**
+ * If `true`, the package is only installed into portable REAPER installations.
+ */
+portable_only?: boolean | null, };
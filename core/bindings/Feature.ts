@@ -1,3 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RecipePackage } from "./RecipePackage";
 
-export type Feature = { name: string, default?: boolean | null, description?: string | null, packages?: Array<string> | null, };
+export type Feature = { name: string, default?: boolean | null, description?: string | null, packages?: Array<RecipePackage> | null, 
+/**
+ * IDs of features that are automatically installed along with this feature.
+ */
+requires?: Array<string> | null, 
+/**
+ * IDs of features that can't be installed together with this feature.
+ */
+conflicts_with?: Array<string> | null, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Information about a package that is currently installed via ReaPack.
+ */
+export type InstalledPackageInfo = { 
+/**
+ * Name of the ReaPack remote from which the package was installed.
+ */
+remote: string, category: string, package: string, version: string, author: string, 
+/**
+ * Package type such as `script` or `extension`.
+ *
+ * Types unknown to ReaBoot are rendered as `unknown (N)`.
+ */
+typ: string, 
+/**
+ * Number of files that belong to this package.
+ */
+file_count: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LockedSource } from "./LockedSource";
+
+/**
+ * A package pinned to an exact version.
+ */
+export type LockedPackage = { repository_url: string, category: string, package_name: string, version: string, sources: Array<LockedSource>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A package file pinned to an exact download URL and hash.
+ */
+export type LockedSource = { 
+/**
+ * Destination path relative to the REAPER resource directory.
+ */
+relative_path: string, url: string, 
+/**
+ * Hex-encoded multihash of the file contents, in the same format as used in ReaPack
+ * indexes.
+ */
+hash: string, };
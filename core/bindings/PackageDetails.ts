@@ -0,0 +1,48 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PackageFileInfo } from "./PackageFileInfo";
+import type { PackageLinkInfo } from "./PackageLinkInfo";
+import type { PackageVersionInfo } from "./PackageVersionInfo";
+import type { ReaperPlatform } from "./ReaperPlatform";
+
+/**
+ * Detailed information about a package in a repository index.
+ */
+export type PackageDetails = { 
+/**
+ * Name of the repository, as declared in its index.
+ */
+repository: string, category: string, package: string, 
+/**
+ * Display name of the package, if the index provides one.
+ */
+description: string | null, 
+/**
+ * Package type such as `script` or `extension`.
+ */
+typ: string, 
+/**
+ * Long description from the package metadata (often in RTF format).
+ */
+about: string | null, 
+/**
+ * Website, donation and screenshot links (and links with other relations).
+ */
+links: Array<PackageLinkInfo>, 
+/**
+ * All versions, the newest one first.
+ */
+versions: Array<PackageVersionInfo>, 
+/**
+ * Version that the package URL refers to.
+ */
+selected_version: string, 
+/**
+ * Platform for which `files` have been determined.
+ */
+platform: ReaperPlatform, 
+/**
+ * Files of the selected version that would be installed on `platform`.
+ *
+ * Empty if the selected version is not available for that platform.
+ */
+files: Array<PackageFileInfo>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PackagePrepStatusInfo } from "./PackagePrepStatusInfo";
+import type { PackageStatusCategory } from "./PackageStatusCategory";
+
+/**
+ * Preparation outcome of a single package.
+ */
+export type PackagePreparationInfo = { remote: string, category: string, package: string, version: string | null, 
+/**
+ * Whether the package is going to be added, replaced or failed.
+ */
+status_category: PackageStatusCategory, status: PackagePrepStatusInfo, 
+/**
+ * Human-readable description of the status, e.g. the error message.
+ */
+message: string, donation_url: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Version of a package in a repository index.
+ */
+export type PackageVersionInfo = { name: string, pre_release: boolean, author: string | null, 
+/**
+ * Release date time in RFC 3339 format (UTC).
+ */
+time: string | null, changelog: string | null, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoInstallationInfo } from "./AutoInstallationInfo";
+import type { InstalledPackageInfo } from "./InstalledPackageInfo";
+import type { PackagePreparationInfo } from "./PackagePreparationInfo";
+import type { ReaPackSettingChangeInfo } from "./ReaPackSettingChangeInfo";
+import type { RemoteRegistrationInfo } from "./RemoteRegistrationInfo";
+import type { ToolingChangeInfo } from "./ToolingChangeInfo";
+
+/**
+ * Machine-readable representation of the preparation report, which summarizes what happened
+ * (or would happen) to each package.
+ */
+export type PreparationReportInfo = { failure_count: number, replacement_count: number, addition_count: number, packages: Array<PackagePreparationInfo>, tooling_changes: Array<ToolingChangeInfo>, 
+/**
+ * Packages that are removed without replacement.
+ */
+package_removals: Array<InstalledPackageInfo>, 
+/**
+ * ReaPack remotes newly registered because the recipe asks for it.
+ */
+remote_registrations: Array<RemoteRegistrationInfo>, 
+/**
+ * Remotes whose packages are installed because auto-install is enabled for them.
+ */
+auto_installations: Array<AutoInstallationInfo>, 
+/**
+ * Settings in `reapack.ini` that are changed by this installation.
+ */
+reapack_setting_changes: Array<ReaPackSettingChangeInfo>, };
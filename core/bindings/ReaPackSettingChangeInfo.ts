@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Change of a single setting in `reapack.ini`.
+ */
+export type ReaPackSettingChangeInfo = { 
+/**
+ * Name of the setting as in [`ReaPackSettings`], e.g. `verify_peer`.
+ */
+name: string, 
+/**
+ * Previous value (`None` if ReaPack's default applied).
+ */
+old_value: string | null, new_value: string | null, };
@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * ReaPack settings stored in `reapack.ini`.
+ *
+ * Settings that are not provided keep their current value (or ReaPack's default).
+ */
+export type ReaPackSettings = { 
+/**
+ * Whether ReaPack should automatically install new packages from enabled remotes.
+ */
+auto_install?: boolean | null, 
+/**
+ * Whether ReaPack should consider pre-releases for all packages, not just for the ones
+ * marked as "bleeding edge".
+ *
+ * ReaBoot honors this as well: If enabled, `latest` resolves to the latest version
+ * including pre-releases.
+ */
+pre_releases?: boolean | null, 
+/**
+ * Whether ReaPack should prompt to uninstall packages that are no longer available in
+ * their repository.
+ */
+prompt_obsolete?: boolean | null, 
+/**
+ * Proxy ReaPack should use, in the form `host:port`. An empty string means no proxy.
+ */
+proxy?: string | null, 
+/**
+ * Whether ReaPack should verify SSL certificates.
+ */
+verify_peer?: boolean | null, 
+/**
+ * Number of seconds after which ReaPack downloads a cached repository index again.
+ */
+stale_threshold?: number | null, 
+/**
+ * Network timeout of ReaPack in seconds.
+ */
+network_timeout?: number | null, };
@@ -1,4 +1,27 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Feature } from "./Feature";
+import type { ReaPackSettings } from "./ReaPackSettings";
+import type { RecipePackage } from "./RecipePackage";
+import type { RecipeRemote } from "./RecipeRemote";
 
-export type Recipe = { name: string, sub_title?: string | null, description?: string | null, website?: string | null, skip_additional_packages?: boolean | null, required_packages?: Array<string> | null, features?: { [key in string]?: Feature } | null, };
+export type Recipe = { name: string, sub_title?: string | null, description?: string | null, website?: string | null, skip_additional_packages?: boolean | null, required_packages?: Array<RecipePackage> | null, features?: { [key in string]?: Feature } | null, 
+/**
+ * Paths or URLs of other recipes whose packages and features should be merged into this one.
+ *
+ * Relative paths are resolved against the location of this recipe. Later includes take
+ * precedence over earlier ones and this recipe takes precedence over all of its includes.
+ */
+includes?: Array<string> | null, 
+/**
+ * ReaPack remotes that should be registered, even if none of their packages is installed.
+ *
+ * Remotes that are registered already (same name or URL) are left untouched.
+ */
+remotes?: Array<RecipeRemote> | null, 
+/**
+ * ReaPack settings to be written to `reapack.ini`, e.g. whether to use pre-releases.
+ *
+ * The network security settings `proxy` and `verify_peer` are ignored unless the user
+ * allows them explicitly.
+ */
+reapack_settings?: ReaPackSettings | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Information about a remote (repository) configured in ReaPack.
+ */
+export type RemoteInfo = { name: string, url: string, enabled: boolean, 
+/**
+ * Whether ReaPack automatically installs new packages from this remote. `None` means that
+ * the global ReaPack setting applies.
+ */
+auto_install: boolean | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * ReaPack remote registered in `reapack.ini`.
+ */
+export type RemoteRegistrationInfo = { name: string, url: string, enabled: boolean, };
@@ -0,0 +1,49 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Limits the combined transfer rate of all downloads that share this limiter.
+#[derive(Debug)]
+pub(crate) struct BandwidthLimiter {
+    bytes_per_second: u64,
+    /// Point in time at which all bytes consumed so far would have been transferred at the
+    /// maximum rate.
+    next_free: Mutex<Instant>,
+}
+
+impl BandwidthLimiter {
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            next_free: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Accounts for the given number of transferred bytes and waits as long as necessary to
+    /// not exceed the maximum rate.
+    pub async fn consume(&self, bytes: u64) {
+        let deadline = {
+            let mut next_free = self.next_free.lock().unwrap();
+            let start = (*next_free).max(Instant::now());
+            *next_free =
+                start + Duration::from_secs_f64(bytes as f64 / self.bytes_per_second as f64);
+            *next_free
+        };
+        tokio::time::sleep_until(deadline).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn consume() {
+        let limiter = BandwidthLimiter::new(100_000);
+        let start = Instant::now();
+        limiter.consume(5_000).await;
+        limiter.consume(15_000).await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
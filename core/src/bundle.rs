@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use serde::{Deserialize, Serialize};
+use tempdir::TempDir;
+use url::Url;
+
+use reaboot_reapack::model::{VersionName, VersionRef};
+
+use crate::api::InstallerConfig;
+use crate::downloader::{Download, Downloader};
+use crate::file_util::create_parent_dirs;
+use crate::hash_util::{build_sha256_source_hash, ReabootHashVerifier};
+use crate::installer::{InstallError, Installer, InstallerListener, InstallerNewArgs};
+use crate::lockfile::Lockfile;
+use crate::reaper_platform::ReaperPlatform;
+use crate::reaper_util;
+
+/// This is the currently supported bundle format version.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Name of the manifest file within the bundle archive.
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Name of the directory within the bundle archive which contains the bundled files.
+const FILES_DIR_NAME: &str = "files";
+
+/// Describes the contents of an offline bundle.
+///
+/// An offline bundle is a zstd-compressed tar archive which contains this manifest and all files
+/// necessary to carry out an installation without network access: Repository indexes, package
+/// files and optionally REAPER installers. Each bundled file is stored under its hash.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle format version.
+    pub version: u32,
+    /// The platforms for which the bundle contains files.
+    pub platforms: Vec<BundledPlatform>,
+    /// All bundled files.
+    pub files: Vec<BundledFile>,
+}
+
+/// Packages and REAPER installer bundled for one particular platform.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BundledPlatform {
+    /// Exact package versions and files for this platform.
+    ///
+    /// Installing from a bundle is always done in locked mode.
+    pub lockfile: Lockfile,
+    #[serde(default)]
+    pub reaper_installer: Option<BundledReaperInstaller>,
+}
+
+/// A bundled REAPER installer.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BundledReaperInstaller {
+    pub version: VersionName,
+    pub url: Url,
+}
+
+/// A file that has been downloaded from the given URL when creating the bundle.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BundledFile {
+    pub url: Url,
+    /// Hex-encoded multihash of the file contents, in the same format as used in ReaPack
+    /// indexes.
+    pub hash: String,
+}
+
+/// An extracted offline bundle from which the downloader can serve files.
+#[derive(Debug)]
+pub struct OfflineBundle {
+    dir: TempDir,
+    manifest: BundleManifest,
+    files_by_url: BTreeMap<Url, String>,
+}
+
+/// Creates offline bundles.
+pub struct BundleWriter {
+    staging_dir: TempDir,
+    recorder: Arc<BundleRecorder>,
+    platforms: Vec<BundledPlatform>,
+}
+
+/// Collects downloaded files while creating an offline bundle.
+#[derive(Debug)]
+pub(crate) struct BundleRecorder {
+    files_dir: PathBuf,
+    files: Mutex<BTreeMap<Url, String>>,
+}
+
+impl OfflineBundle {
+    /// Extracts the given bundle archive into a temporary directory.
+    pub fn open(archive_file: &Path) -> anyhow::Result<Self> {
+        let file = fs::File::open(archive_file)
+            .with_context(|| format!("couldn't open bundle {archive_file:?}"))?;
+        let decoder = zstd::Decoder::new(file).context("couldn't decompress bundle")?;
+        let dir = TempDir::new("reaboot-bundle-")
+            .context("couldn't create temporary directory for bundle")?;
+        tar::Archive::new(decoder)
+            .unpack(dir.path())
+            .context("couldn't extract bundle")?;
+        let json = fs::read_to_string(dir.path().join(MANIFEST_FILE_NAME))
+            .context("bundle doesn't contain a valid manifest")?;
+        let manifest: BundleManifest =
+            serde_json::from_str(&json).context("couldn't parse bundle manifest")?;
+        ensure!(
+            manifest.version <= BUNDLE_VERSION,
+            "Bundle version {} is not supported by this ReaBoot version. Please download the latest ReaBoot version!",
+            manifest.version
+        );
+        let files_by_url = manifest
+            .files
+            .iter()
+            .map(|f| (f.url.clone(), f.hash.clone()))
+            .collect();
+        let bundle = Self {
+            dir,
+            manifest,
+            files_by_url,
+        };
+        Ok(bundle)
+    }
+
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Adjusts the given installer config so that it installs from this bundle for the configured
+    /// platform.
+    ///
+    /// The bundled lockfile pins the versions of the requested packages. It doesn't replace the
+    /// requested packages, so the feature selection and package conditions are still respected.
+    pub(crate) fn apply_to_config(&self, config: &mut InstallerConfig) -> anyhow::Result<()> {
+        ensure!(
+            config.lockfile.is_none(),
+            "Installing from a bundle can't be combined with a custom lockfile"
+        );
+        let platform = config
+            .custom_platform
+            .unwrap_or(ReaperPlatform::from_reaboot_build());
+        let Some(bundled_platform) = self
+            .manifest
+            .platforms
+            .iter()
+            .find(|p| p.lockfile.platform == platform)
+        else {
+            bail!(
+                "The bundle doesn't contain files for platform {platform:?}. It only contains files for: {}",
+                self.manifest
+                    .platforms
+                    .iter()
+                    .map(|p| format!("{:?}", p.lockfile.platform))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        config.lockfile = Some(bundled_platform.lockfile.clone());
+        match &bundled_platform.reaper_installer {
+            None => {
+                // Without network access, we can't install or update REAPER
+                config.install_reaper = Some(false);
+                config.update_reaper = false;
+            }
+            Some(reaper_installer) => {
+                if !matches!(config.reaper_version, Some(VersionRef::Specific(_))) {
+                    config.reaper_version =
+                        Some(VersionRef::Specific(reaper_installer.version.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the bundled file corresponding to the given download to its destination.
+    pub(crate) fn copy_file(&self, download: &Download) -> anyhow::Result<()> {
+        let hash = self
+            .files_by_url
+            .get(&download.url)
+            .with_context(|| format!("File {} is not contained in the bundle", &download.url))?;
+        // The hash ends up as file name, so we better make sure that it's hex only
+        ensure!(
+            hash.chars().all(|c| c.is_ascii_hexdigit()),
+            "Bundle manifest contains invalid hash for file {}",
+            &download.url
+        );
+        let bundled_file = self.dir.path().join(FILES_DIR_NAME).join(hash);
+        let bytes = fs::read(&bundled_file)
+            .with_context(|| format!("Bundled file {} is missing", &download.url))?;
+        ensure!(
+            bytes_match_hash(&bytes, hash),
+            "Bundled file {} is corrupted",
+            &download.url
+        );
+        if let Some(expected_hash) = &download.expected_multihash {
+            ensure!(
+                bytes_match_hash(&bytes, expected_hash),
+                "Bundled file {} has another checksum than expected",
+                &download.url
+            );
+        }
+        create_parent_dirs(&download.file)?;
+        fs::write(&download.file, bytes).context("couldn't copy file from bundle")?;
+        Ok(())
+    }
+}
+
+impl BundleWriter {
+    pub fn new() -> anyhow::Result<Self> {
+        let staging_dir = TempDir::new("reaboot-bundle-")
+            .context("couldn't create temporary directory for bundle")?;
+        let writer = Self {
+            recorder: Arc::new(BundleRecorder::new(staging_dir.path().join(FILES_DIR_NAME))),
+            staging_dir,
+            platforms: vec![],
+        };
+        Ok(writer)
+    }
+
+    /// Resolves and downloads all requested packages for the platform configured in the given
+    /// installer args and optionally also the REAPER installer.
+    ///
+    /// Recipe package conditions are only checked for the platform (see
+    /// [`Installer::new_for_locking`]).
+    pub async fn add_platform<L: InstallerListener>(
+        &mut self,
+        args: InstallerNewArgs<L>,
+        include_reaper: bool,
+    ) -> Result<(), InstallError> {
+        let mut installer = Installer::new_for_locking(args).await?;
+        installer.record_downloads(self.recorder.clone());
+        let resolved_config = installer.resolved_config();
+        let platform = resolved_config.platform;
+        let reaper_version = resolved_config.reaper_version.clone();
+        let num_download_retries = resolved_config.num_download_retries;
+        let http_client = installer.http_client().clone();
+        let max_download_bytes_per_second = resolved_config.max_download_bytes_per_second;
+        if self
+            .platforms
+            .iter()
+            .any(|p| p.lockfile.platform == platform)
+        {
+            return Err(anyhow!("Platform {platform:?} has been added already").into());
+        }
+        let lockfile = installer.lock().await?;
+        let reaper_installer = if include_reaper {
+            let asset = reaper_util::get_latest_reaper_installer_asset(
+                &http_client,
+                platform,
+                &reaper_version,
+            )
+            .await?;
+            let mut downloader = Downloader::new(http_client, num_download_retries)
+                .with_recorder(self.recorder.clone());
+            if let Some(bytes_per_second) = max_download_bytes_per_second {
+                downloader = downloader.with_bandwidth_limit(bytes_per_second);
+            }
+            let file = self.staging_dir.path().join(&asset.file_name);
+            let download = Download::new(
+                format!("REAPER {}", &asset.version),
+                asset.url.clone(),
+                file.clone(),
+                None,
+            );
+            downloader.download(download, |_| {}).await?;
+            fs::remove_file(file).map_err(anyhow::Error::from)?;
+            Some(BundledReaperInstaller {
+                version: asset.version,
+                url: asset.url,
+            })
+        } else {
+            None
+        };
+        self.platforms.push(BundledPlatform {
+            lockfile,
+            reaper_installer,
+        });
+        Ok(())
+    }
+
+    /// Writes the bundle archive.
+    pub fn finish(self, archive_file: &Path) -> anyhow::Result<BundleManifest> {
+        let files = self
+            .recorder
+            .files
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(url, hash)| BundledFile {
+                url: url.clone(),
+                hash: hash.clone(),
+            })
+            .collect();
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            platforms: self.platforms,
+            files,
+        };
+        let json = serde_json::to_string_pretty(&manifest)?;
+        fs::write(self.staging_dir.path().join(MANIFEST_FILE_NAME), json)?;
+        let file = fs::File::create(archive_file)
+            .with_context(|| format!("couldn't create bundle {archive_file:?}"))?;
+        let encoder = zstd::Encoder::new(file, 0)?;
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_path_with_name(
+            self.staging_dir.path().join(MANIFEST_FILE_NAME),
+            MANIFEST_FILE_NAME,
+        )?;
+        fs::create_dir_all(&self.recorder.files_dir)?;
+        builder.append_dir_all(FILES_DIR_NAME, &self.recorder.files_dir)?;
+        builder.into_inner()?.finish()?;
+        Ok(manifest)
+    }
+}
+
+impl BundleRecorder {
+    fn new(files_dir: PathBuf) -> Self {
+        Self {
+            files_dir,
+            files: Default::default(),
+        }
+    }
+
+    /// Copies the downloaded file into the bundle, storing it under its hash.
+    pub fn record(&self, download: &Download) -> anyhow::Result<()> {
+        if self.files.lock().unwrap().contains_key(&download.url) {
+            return Ok(());
+        }
+        let bytes = fs::read(&download.file).context("couldn't read downloaded file")?;
+        let hash = build_sha256_source_hash(&bytes);
+        let bundled_file = self.files_dir.join(&hash);
+        if !bundled_file.exists() {
+            fs::create_dir_all(&self.files_dir)?;
+            fs::write(&bundled_file, bytes).context("couldn't add file to bundle")?;
+        }
+        self.files
+            .lock()
+            .unwrap()
+            .insert(download.url.clone(), hash);
+        Ok(())
+    }
+}
+
+fn bytes_match_hash(bytes: &[u8], multihash: &str) -> bool {
+    let Ok(mut verifier) = ReabootHashVerifier::try_from_hash(multihash) else {
+        return false;
+    };
+    verifier.update(bytes);
+    verifier.verify().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_open() {
+        let dir = TempDir::new("reaboot-test-").unwrap();
+        let writer = BundleWriter::new().unwrap();
+        let url = Url::parse("https://example.com/hello.lua").unwrap();
+        let src_file = dir.path().join("hello.lua");
+        fs::write(&src_file, "hello world").unwrap();
+        writer
+            .recorder
+            .record(&Download::new(
+                "hello".to_string(),
+                url.clone(),
+                src_file,
+                None,
+            ))
+            .unwrap();
+        let archive_file = dir.path().join("bundle.tar.zst");
+        writer.finish(&archive_file).unwrap();
+        let bundle = OfflineBundle::open(&archive_file).unwrap();
+        assert_eq!(bundle.manifest().files.len(), 1);
+        // Bundled file
+        let dest_file = dir.path().join("dest/hello.lua");
+        let hash = build_sha256_source_hash("hello world");
+        bundle
+            .copy_file(&Download::new(
+                "hello".to_string(),
+                url,
+                dest_file.clone(),
+                Some(hash),
+            ))
+            .unwrap();
+        assert_eq!(fs::read_to_string(&dest_file).unwrap(), "hello world");
+        // File not contained in bundle
+        let other_url = Url::parse("https://example.com/other.lua").unwrap();
+        assert!(bundle
+            .copy_file(&Download::new(
+                "other".to_string(),
+                other_url,
+                dest_file,
+                None
+            ))
+            .is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use anyhow::Context;
+use reqwest_middleware::RequestBuilder;
+use serde::Deserialize;
+use url::Url;
+
+/// Environment variable which may contain credential entries in the same JSON format as the
+/// credentials file.
+pub const CREDENTIALS_ENV_VAR: &str = "REABOOT_CREDENTIALS";
+
+/// Environment variable which may contain the path of the credentials file.
+pub const CREDENTIALS_FILE_ENV_VAR: &str = "REABOOT_CREDENTIALS_FILE";
+
+/// Credentials for accessing private repositories, keyed by host or URL prefix.
+///
+/// Credentials are only ever sent as `Authorization` header. They are not part of any URL and
+/// their `Debug` representation is redacted, so they don't end up in progress events, logs or
+/// reports.
+#[derive(Clone, Debug, Default)]
+pub struct CredentialStore {
+    entries: Vec<CredentialEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CredentialEntry {
+    /// Either a host (e.g. `git.example.com`) or a URL prefix
+    /// (e.g. `https://git.example.com/team/`).
+    ///
+    /// A host only matches `https` URLs. In order to send credentials via plain `http`, the
+    /// target must be a URL prefix starting with `http://`.
+    pub target: String,
+    #[serde(flatten)]
+    pub credential: Credential,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Credential {
+    Bearer {
+        token: String,
+    },
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+}
+
+impl Debug for Credential {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Credential::Bearer { .. } => f.write_str("Bearer(<redacted>)"),
+            Credential::Basic { username, .. } => write!(f, "Basic({username}, <redacted>)"),
+        }
+    }
+}
+
+impl Credential {
+    /// Adds the corresponding `Authorization` header to the given request.
+    pub fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Credential::Bearer { token } => request.bearer_auth(token),
+            Credential::Basic { username, password } => {
+                request.basic_auth(username, password.as_ref())
+            }
+        }
+    }
+}
+
+impl CredentialStore {
+    pub fn new(entries: Vec<CredentialEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Loads credentials from the environment variable [`CREDENTIALS_ENV_VAR`] and from the
+    /// credentials file.
+    ///
+    /// If no file is given, ReaBoot looks at [`CREDENTIALS_FILE_ENV_VAR`] and then at
+    /// `credentials.json` in the ReaBoot config directory. Only an explicitly given file must
+    /// exist.
+    pub fn load(file: Option<&Path>) -> anyhow::Result<Self> {
+        let mut entries = vec![];
+        if let Ok(json) = env::var(CREDENTIALS_ENV_VAR) {
+            entries.extend(
+                parse_entries(&json)
+                    .with_context(|| format!("couldn't parse {CREDENTIALS_ENV_VAR}"))?,
+            );
+        }
+        let explicit_file = file
+            .map(Path::to_path_buf)
+            .or_else(|| env::var_os(CREDENTIALS_FILE_ENV_VAR).map(PathBuf::from));
+        let file = match explicit_file {
+            Some(f) => Some(f),
+            None => Self::default_file().filter(|f| f.exists()),
+        };
+        if let Some(file) = file {
+            let json = fs::read_to_string(&file)
+                .with_context(|| format!("couldn't read credentials file {file:?}"))?;
+            entries.extend(
+                parse_entries(&json)
+                    .with_context(|| format!("couldn't parse credentials file {file:?}"))?,
+            );
+        }
+        Ok(Self::new(entries))
+    }
+
+    /// Returns the default location of the credentials file.
+    pub fn default_file() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("reaboot").join("credentials.json"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the credential that should be used for the given URL.
+    ///
+    /// The most specific match wins, with URL prefixes being more specific than hosts. If
+    /// there are several equally specific matches, the first one wins.
+    pub fn find(&self, url: &Url) -> Option<&Credential> {
+        let mut best: Option<((bool, usize), &Credential)> = None;
+        for entry in &self.entries {
+            let Some(specificity) = entry.match_specificity(url) else {
+                continue;
+            };
+            if best.is_none_or(|(s, _)| specificity > s) {
+                best = Some((specificity, &entry.credential));
+            }
+        }
+        best.map(|(_, c)| c)
+    }
+}
+
+impl CredentialEntry {
+    /// Returns whether the target is a URL prefix and how long it is, if it matches.
+    fn match_specificity(&self, url: &Url) -> Option<(bool, usize)> {
+        let is_url_prefix = self.target.contains("://");
+        let matches = if is_url_prefix {
+            Url::parse(&self.target).is_ok_and(|target| url_prefix_matches(&target, url))
+        } else {
+            // Never send credentials in cleartext unless explicitly configured
+            url.scheme() == "https"
+                && url
+                    .host_str()
+                    .is_some_and(|host| host.eq_ignore_ascii_case(&self.target))
+        };
+        matches.then_some((is_url_prefix, self.target.len()))
+    }
+}
+
+/// Returns whether the given URL starts with the given URL prefix.
+///
+/// Scheme, host and port must match exactly and the path prefix must end at a path segment
+/// boundary, so that `https://example.com/team` neither matches `https://example.com.evil.org/`
+/// nor `https://example.com/teammate/`.
+fn url_prefix_matches(prefix: &Url, url: &Url) -> bool {
+    if prefix.scheme() != url.scheme()
+        || prefix.host_str() != url.host_str()
+        || prefix.port_or_known_default() != url.port_or_known_default()
+    {
+        return false;
+    }
+    let prefix_path = prefix.path();
+    let path = url.path();
+    if prefix_path.ends_with('/') {
+        return path.starts_with(prefix_path);
+    }
+    path.strip_prefix(prefix_path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn parse_entries(json: &str) -> anyhow::Result<Vec<CredentialEntry>> {
+    Ok(serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_and_redact() {
+        let json = r#"[
+            {"target": "git.example.com", "type": "bearer", "token": "host-token"},
+            {"target": "https://git.example.com/team/", "type": "basic", "username": "joe", "password": "secret"}
+        ]"#;
+        let store = CredentialStore::new(parse_entries(json).unwrap());
+        let find = |url: &str| store.find(&Url::parse(url).unwrap());
+        assert!(matches!(
+            find("https://git.example.com/other/index.xml"),
+            Some(Credential::Bearer { .. })
+        ));
+        assert!(matches!(
+            find("https://git.example.com/team/index.xml"),
+            Some(Credential::Basic { .. })
+        ));
+        assert!(find("https://example.com/index.xml").is_none());
+        // Lookalike hosts and paths don't match
+        assert!(find("https://git.example.com.evil.org/team/index.xml").is_none());
+        assert!(matches!(
+            find("https://git.example.com/teammate/index.xml"),
+            Some(Credential::Bearer { .. })
+        ));
+        assert!(find("http://git.example.com.evil.org/index.xml").is_none());
+        // Hosts only match https URLs
+        assert!(find("http://git.example.com/other/index.xml").is_none());
+        let http_store = CredentialStore::new(
+            parse_entries(
+                r#"[{"target": "http://git.example.com/", "type": "bearer", "token": "t"}]"#,
+            )
+            .unwrap(),
+        );
+        assert!(http_store
+            .find(&Url::parse("http://git.example.com/other/index.xml").unwrap())
+            .is_some());
+        let prefix_store = CredentialStore::new(
+            parse_entries(
+                r#"[{"target": "https://git.example.com", "type": "bearer", "token": "t"}]"#,
+            )
+            .unwrap(),
+        );
+        let find_prefix = |url: &str| prefix_store.find(&Url::parse(url).unwrap());
+        assert!(find_prefix("https://git.example.com/team/index.xml").is_some());
+        assert!(find_prefix("https://git.example.com:443/index.xml").is_some());
+        assert!(find_prefix("https://git.example.com.evil.org/index.xml").is_none());
+        assert!(find_prefix("https://git.example.com:8443/index.xml").is_none());
+        assert!(find_prefix("http://git.example.com/index.xml").is_none());
+        let debug = format!("{store:?}");
+        assert!(!debug.contains("host-token"));
+        assert!(!debug.contains("secret"));
+    }
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use anyhow::Context;
+
+use crate::file_util::create_parent_dirs;
+use crate::hash_util::ReabootHashVerifier;
+
+/// Default maximum size of the download cache (1 GiB).
+pub const DEFAULT_MAX_DOWNLOAD_CACHE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// A persistent, content-addressed cache for downloaded files.
+///
+/// Each file is stored under its multihash (as used in ReaPack indexes). A cached file is only
+/// reused if its contents still match that hash, so a corrupted cache can't corrupt an
+/// installation.
+#[derive(Clone, Debug)]
+pub struct DownloadCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+/// Statistics about the download cache.
+#[derive(Clone, Debug)]
+pub struct DownloadCacheStats {
+    pub dir: PathBuf,
+    pub file_count: u64,
+    pub total_size: u64,
+    pub max_size: u64,
+}
+
+impl DownloadCache {
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    /// Returns the default cache directory within the OS-specific user cache directory.
+    pub fn default_dir() -> Option<PathBuf> {
+        Some(dirs::cache_dir()?.join("reaboot").join("downloads"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Copies the cached file with the given hash to `dest_file`.
+    ///
+    /// Returns `false` if the cache doesn't contain a matching file. Cached files that don't
+    /// match their hash are removed from the cache.
+    pub fn get(&self, multihash: &str, dest_file: &Path) -> anyhow::Result<bool> {
+        let Some(cached_file) = self.cached_file(multihash) else {
+            return Ok(false);
+        };
+        if !cached_file.exists() {
+            return Ok(false);
+        }
+        if !file_matches_hash(&cached_file, multihash)? {
+            tracing::warn!(
+                msg = "Removing corrupted file from download cache",
+                ?cached_file
+            );
+            fs::remove_file(&cached_file)?;
+            return Ok(false);
+        }
+        create_parent_dirs(dest_file)?;
+        fs::copy(&cached_file, dest_file).context("couldn't copy file from download cache")?;
+        // Mark as recently used, which is relevant for eviction. This is just best-effort, the
+        // file has been copied successfully already (e.g. the cache might be read-only).
+        let touch_result = fs::File::options()
+            .write(true)
+            .open(&cached_file)
+            .and_then(|f| f.set_modified(SystemTime::now()));
+        if let Err(e) = touch_result {
+            tracing::warn!(
+                msg = "Couldn't mark cached file as recently used",
+                ?cached_file,
+                %e
+            );
+        }
+        Ok(true)
+    }
+
+    /// Adds a copy of the given file, which must have the given hash, to the cache.
+    pub fn put(&self, multihash: &str, src_file: &Path) -> anyhow::Result<()> {
+        let cached_file = self
+            .cached_file(multihash)
+            .context("hash is not suitable as cache key")?;
+        if cached_file.exists() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).context("couldn't create download cache directory")?;
+        // Copy to a temporary file first, so that concurrent readers never see incomplete files
+        let temp_file = tempfile_path(&cached_file);
+        fs::copy(src_file, &temp_file).context("couldn't copy file into download cache")?;
+        fs::rename(&temp_file, &cached_file)?;
+        Ok(())
+    }
+
+    /// Removes the least recently used files until the cache doesn't exceed its maximum size
+    /// anymore.
+    pub fn evict(&self) -> anyhow::Result<()> {
+        let mut entries = self.entries()?;
+        let mut total_size: u64 = entries.iter().map(|e| e.size).sum();
+        if total_size <= self.max_size {
+            return Ok(());
+        }
+        entries.sort_by_key(|e| e.modified);
+        for entry in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            fs::remove_file(&entry.path)?;
+            total_size -= entry.size;
+        }
+        Ok(())
+    }
+
+    /// Removes all files from the cache.
+    pub fn clean(&self) -> anyhow::Result<()> {
+        for entry in self.entries()? {
+            fs::remove_file(&entry.path)?;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> anyhow::Result<DownloadCacheStats> {
+        let entries = self.entries()?;
+        let stats = DownloadCacheStats {
+            dir: self.dir.clone(),
+            file_count: entries.len() as u64,
+            total_size: entries.iter().map(|e| e.size).sum(),
+            max_size: self.max_size,
+        };
+        Ok(stats)
+    }
+
+    fn cached_file(&self, multihash: &str) -> Option<PathBuf> {
+        // The hash ends up as file name, so we better make sure that it's hex only
+        if multihash.is_empty() || !multihash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(self.dir.join(multihash.to_ascii_lowercase()))
+    }
+
+    fn entries(&self) -> anyhow::Result<Vec<CacheEntry>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut entries = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            entries.push(CacheEntry {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn file_matches_hash(file: &Path, multihash: &str) -> anyhow::Result<bool> {
+    let Ok(mut verifier) = ReabootHashVerifier::try_from_hash(multihash) else {
+        return Ok(false);
+    };
+    let mut reader = fs::File::open(file)?;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        verifier.update(&buffer[..n]);
+    }
+    Ok(verifier.verify().is_ok())
+}
+
+/// Returns a temporary file path next to the given file that is unique across processes and
+/// across concurrent calls within this process.
+fn tempfile_path(file: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut file_name = file.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{}-{n}.tmp", std::process::id()));
+    file.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_util::build_sha256_source_hash;
+
+    #[test]
+    fn temp_file_paths_are_unique() {
+        let file = Path::new("cache/1220abc");
+        assert_ne!(tempfile_path(file), tempfile_path(file));
+    }
+
+    #[test]
+    fn put_get_evict() {
+        let dir = tempdir::TempDir::new("reaboot-test-").unwrap();
+        let cache = DownloadCache::new(dir.path().join("cache"), 15);
+        let src_file = dir.path().join("src.txt");
+        let dest_file = dir.path().join("dest/dest.txt");
+        let hash = build_sha256_source_hash("hello world");
+        // Miss
+        assert!(!cache.get(&hash, &dest_file).unwrap());
+        // Hit
+        fs::write(&src_file, "hello world").unwrap();
+        cache.put(&hash, &src_file).unwrap();
+        assert!(cache.get(&hash, &dest_file).unwrap());
+        assert_eq!(fs::read_to_string(&dest_file).unwrap(), "hello world");
+        // Corrupted file is not reused
+        fs::write(cache.dir().join(&hash), "hello moon").unwrap();
+        assert!(!cache.get(&hash, &dest_file).unwrap());
+        assert_eq!(cache.stats().unwrap().file_count, 0);
+        // Eviction
+        let other_hash = build_sha256_source_hash("hello universe");
+        fs::write(&src_file, "hello universe").unwrap();
+        cache.put(&other_hash, &src_file).unwrap();
+        fs::write(&src_file, "hello world").unwrap();
+        cache.put(&hash, &src_file).unwrap();
+        assert_eq!(cache.stats().unwrap().total_size, 25);
+        cache.evict().unwrap();
+        assert_eq!(cache.stats().unwrap().file_count, 1);
+    }
+}
@@ -1,18 +1,37 @@
+use crate::bandwidth_limiter::BandwidthLimiter;
+use crate::bundle::{BundleRecorder, OfflineBundle};
+use crate::credentials::CredentialStore;
 use crate::hash_util::ReabootHashVerifier;
-use anyhow::Context;
-use futures::stream::StreamExt;
-use reqwest::header::{HeaderMap, HeaderValue};
+use anyhow::{anyhow, Context};
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT_RANGES, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::StatusCode;
 use reqwest_middleware::ClientWithMiddleware;
-use reqwest_retry::policies::ExponentialBackoff;
-use reqwest_retry::RetryTransientMiddleware;
 use std::fs;
+use std::io::SeekFrom;
 use std::path::PathBuf;
-use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+use std::pin::pin;
+use std::sync::Arc;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use url::Url;
 
 #[derive(Clone)]
 pub struct Downloader {
     client: ClientWithMiddleware,
+    /// If set, files are taken from this offline bundle instead of being downloaded.
+    bundle: Option<Arc<OfflineBundle>>,
+    /// If set, each successfully downloaded file is recorded for creating an offline bundle.
+    recorder: Option<Arc<BundleRecorder>>,
+    /// How often to continue a download that has been interrupted in the middle of the
+    /// transfer.
+    max_resumptions: u32,
+    /// If set, limits the combined transfer rate of this downloader and all its clones.
+    bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
+    /// If set, requests to matching URLs are authenticated with these credentials.
+    credentials: Option<Arc<CredentialStore>>,
 }
 
 #[derive(Clone, Debug)]
@@ -35,85 +54,391 @@ impl Download {
 }
 
 impl Downloader {
-    pub fn new(retries: u32) -> Self {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(retries);
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", HeaderValue::from_static("reaboot"));
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .unwrap();
-        let client = reqwest_middleware::ClientBuilder::new(client)
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
-        Self { client }
+    /// Creates a downloader which uses the given HTTP client.
+    ///
+    /// Interrupted downloads are resumed up to `retries` times.
+    pub fn new(client: ClientWithMiddleware, retries: u32) -> Self {
+        Self {
+            client,
+            bundle: None,
+            recorder: None,
+            max_resumptions: retries,
+            bandwidth_limiter: None,
+            credentials: None,
+        }
+    }
+
+    /// Limits the combined transfer rate of this downloader and all its clones to the given
+    /// number of bytes per second.
+    pub fn with_bandwidth_limit(mut self, bytes_per_second: u64) -> Self {
+        self.bandwidth_limiter = Some(Arc::new(BandwidthLimiter::new(bytes_per_second)));
+        self
+    }
+
+    /// Makes this downloader authenticate requests to private repositories with the given
+    /// credentials.
+    pub fn with_credentials(mut self, credentials: Arc<CredentialStore>) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Makes this downloader serve all files from the given offline bundle, without accessing
+    /// the network.
+    pub fn with_bundle(mut self, bundle: Arc<OfflineBundle>) -> Self {
+        self.bundle = Some(bundle);
+        self
+    }
+
+    /// Makes this downloader record each successful download in the given recorder.
+    pub(crate) fn with_recorder(mut self, recorder: Arc<BundleRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
     }
 
     pub async fn download(
         &self,
         download: Download,
         progress_listener: impl Fn(DownloadProgress),
+    ) -> anyhow::Result<()> {
+        if let Some(bundle) = &self.bundle {
+            bundle.copy_file(&download)?;
+        } else if download.url.scheme() == "file" {
+            self.download_via_file_system(&download, &progress_listener)
+                .await?;
+        } else {
+            self.download_via_http(&download, &progress_listener)
+                .await?;
+        }
+        self.record(&download)?;
+        progress_listener(DownloadProgress::Finished);
+        Ok(())
+    }
+
+    /// Records the given download if this downloader has a recorder.
+    ///
+    /// This must also be called for files that were obtained in other ways, e.g. from the
+    /// download cache.
+    pub(crate) fn record(&self, download: &Download) -> anyhow::Result<()> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(download)?;
+        }
+        Ok(())
+    }
+
+    /// Downloads the file via HTTP.
+    ///
+    /// The retry middleware only takes care of failed requests. If the transfer itself is
+    /// interrupted, we keep the partially downloaded file and resume the download with a range
+    /// request, provided that the server supports it. Otherwise, we start from scratch.
+    async fn download_via_http(
+        &self,
+        download: &Download,
+        progress_listener: impl Fn(DownloadProgress),
     ) -> anyhow::Result<()> {
         progress_listener(DownloadProgress::Connecting);
-        let req = self.client.get(download.url.clone());
-        let res = req.send().await?;
-        progress_listener(DownloadProgress::CreatingDestFile);
+        let mut resume_validator = None;
+        let mut num_resumptions = 0;
+        loop {
+            let outcome = self
+                .download_via_http_once(download, resume_validator.as_ref(), &progress_listener)
+                .await?;
+            match outcome {
+                HttpDownloadOutcome::Finished => return Ok(()),
+                HttpDownloadOutcome::Interrupted { error, validator } => {
+                    if num_resumptions >= self.max_resumptions {
+                        return Err(error.context("download has been interrupted too often"));
+                    }
+                    num_resumptions += 1;
+                    tracing::warn!(
+                        msg = "Download interrupted",
+                        url = %download.url,
+                        resumable = validator.is_some(),
+                        %error
+                    );
+                    resume_validator = validator;
+                }
+            }
+        }
+    }
+
+    async fn download_via_http_once(
+        &self,
+        download: &Download,
+        resume_validator: Option<&HeaderValue>,
+        progress_listener: impl Fn(DownloadProgress),
+    ) -> anyhow::Result<HttpDownloadOutcome> {
+        // Resume only if there's something to resume
+        let resume = resume_validator.and_then(|validator| {
+            let existing_len = fs::metadata(&download.file).ok()?.len();
+            (existing_len > 0).then_some((existing_len, validator))
+        });
+        let mut req = self.client.get(download.url.clone());
+        if let Some(credential) = self
+            .credentials
+            .as_ref()
+            .and_then(|c| c.find(&download.url))
+        {
+            req = credential.apply(req);
+        }
+        if let Some((offset, validator)) = resume {
+            // If the file has changed in the meantime, If-Range makes the server send the
+            // complete new file instead of a range
+            req = req
+                .header(RANGE, format!("bytes={offset}-"))
+                .header(IF_RANGE, validator.clone());
+        }
+        let res = match req.send().await {
+            Ok(res) => res,
+            // A failed resume request is just another interruption
+            Err(error) if resume.is_some() => {
+                return Ok(HttpDownloadOutcome::Interrupted {
+                    error: error.into(),
+                    validator: resume_validator.cloned(),
+                });
+            }
+            Err(error) => return Err(error.into()),
+        };
         res.error_for_status_ref()?;
-        let content_length = get_content_length(res.headers());
-        if let Some(dir) = download.file.parent() {
-            fs::create_dir_all(dir)?;
+        let resume_offset = match resume {
+            Some((offset, _)) if res.status() == StatusCode::PARTIAL_CONTENT => {
+                let range_start = get_content_range_start(res.headers());
+                if range_start != Some(offset) {
+                    // Appending this range would corrupt the file, so start from scratch
+                    return Ok(HttpDownloadOutcome::Interrupted {
+                        error: anyhow!(
+                            "server sent range starting at {range_start:?} instead of {offset}"
+                        ),
+                        validator: None,
+                    });
+                }
+                offset
+            }
+            _ => 0,
+        };
+        progress_listener(DownloadProgress::CreatingDestFile);
+        let validator = get_resume_validator(res.headers());
+        let content_length = get_content_length(res.headers()).map(|l| resume_offset + l);
+        let stream = res.bytes_stream();
+        let outcome = write_to_dest_file(
+            download,
+            resume_offset,
+            content_length,
+            stream,
+            self.bandwidth_limiter.as_deref(),
+            progress_listener,
+        )
+        .await?;
+        let outcome = match outcome {
+            WriteOutcome::Finished => HttpDownloadOutcome::Finished,
+            WriteOutcome::Interrupted(error) => HttpDownloadOutcome::Interrupted {
+                error: error.into(),
+                validator,
+            },
+        };
+        Ok(outcome)
+    }
+
+    /// Copies a file from a `file://` URL, e.g. from a local repository that's not published
+    /// yet.
+    async fn download_via_file_system(
+        &self,
+        download: &Download,
+        progress_listener: impl Fn(DownloadProgress),
+    ) -> anyhow::Result<()> {
+        progress_listener(DownloadProgress::Connecting);
+        let src_path = download
+            .url
+            .to_file_path()
+            .map_err(|_| anyhow!("{} is not a valid file URL", &download.url))?;
+        let src_file = tokio::fs::File::open(&src_path)
+            .await
+            .with_context(|| format!("couldn't open local file {src_path:?}"))?;
+        progress_listener(DownloadProgress::CreatingDestFile);
+        let content_length = src_file.metadata().await?.len();
+        let stream = stream::try_unfold(src_file, |mut src_file| async move {
+            let mut buffer = vec![0; 64 * 1024];
+            let n = src_file.read(&mut buffer).await?;
+            if n == 0 {
+                return Ok(None);
+            }
+            buffer.truncate(n);
+            Ok(Some((buffer, src_file)))
+        });
+        let outcome = write_to_dest_file(
+            download,
+            0,
+            Some(content_length),
+            stream,
+            None,
+            progress_listener,
+        )
+        .await?;
+        match outcome {
+            WriteOutcome::Finished => Ok(()),
+            WriteOutcome::Interrupted(error) => Err(error),
         }
+    }
+}
+
+enum HttpDownloadOutcome {
+    Finished,
+    Interrupted {
+        error: anyhow::Error,
+        /// Set if the server supports resuming the download.
+        validator: Option<HeaderValue>,
+    },
+}
+
+enum WriteOutcome<E> {
+    Finished,
+    /// The stream failed, but everything received so far has been written to the file.
+    Interrupted(E),
+}
+
+/// Writes the given stream of chunks to the destination file of the download, reporting
+/// progress and verifying the expected hash (if any).
+///
+/// If `resume_offset` is greater than zero, the stream is appended to the first `resume_offset`
+/// bytes of the existing file. `content_length` is the total length including that prefix.
+async fn write_to_dest_file<E>(
+    download: &Download,
+    resume_offset: u64,
+    content_length: Option<u64>,
+    stream: impl Stream<Item = Result<impl AsRef<[u8]>, E>>,
+    bandwidth_limiter: Option<&BandwidthLimiter>,
+    progress_listener: impl Fn(DownloadProgress),
+) -> anyhow::Result<WriteOutcome<E>> {
+    if let Some(dir) = download.file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut verifier = if let Some(m) = download.expected_multihash.as_ref() {
+        Some(ReabootHashVerifier::try_from_hash(m).context(
+            "Download came with a checksum but we have no way to verify it. Discarding download.",
+        )?)
+    } else {
+        None
+    };
+    let mut dest_file = if resume_offset > 0 {
         let mut dest_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&download.file)
+            .await?;
+        // Hash verification needs to cover the part that we have already
+        let mut remaining = resume_offset;
+        let mut buffer = vec![0; 64 * 1024];
+        while remaining > 0 {
+            let max_len = buffer.len().min(remaining as usize);
+            let n = dest_file.read(&mut buffer[..max_len]).await?;
+            if n == 0 {
+                break;
+            }
+            if let Some(verifier) = &mut verifier {
+                verifier.update(&buffer[..n]);
+            }
+            remaining -= n as u64;
+        }
+        // Cut off anything beyond the offset, just in case
+        dest_file.set_len(resume_offset).await?;
+        dest_file.seek(SeekFrom::Start(resume_offset)).await?;
+        dest_file
+    } else {
+        OpenOptions::new()
             .create(true)
             .truncate(true)
             .write(true)
             .append(false)
             .open(&download.file)
-            .await?;
-        let mut stream = res.bytes_stream();
-        let mut bytes_already_downloaded = 0;
-        progress_listener(DownloadProgress::Downloading(0.0));
-        let mut verifier = if let Some(m) = download.expected_multihash.as_ref() {
-            Some(
-                ReabootHashVerifier::try_from_hash(m)
-                    .context("Download came with a checksum but we have no way to verify it. Discarding download.")?,
-            )
-        } else {
-            None
-        };
-        while let Some(item) = stream.next().await {
-            let mut chunk = item?;
-            let chunk_size = chunk.len() as u64;
-            if let Some(l) = content_length {
-                if l != 0 {
-                    let progress = bytes_already_downloaded as f64 / l as f64;
-                    progress_listener(DownloadProgress::Downloading(progress));
-                }
+            .await?
+    };
+    let mut bytes_already_downloaded = resume_offset;
+    let initial_progress = match content_length {
+        Some(l) if l != 0 => bytes_already_downloaded as f64 / l as f64,
+        _ => 0.0,
+    };
+    if resume_offset > 0 {
+        progress_listener(DownloadProgress::Resuming(initial_progress));
+    } else {
+        progress_listener(DownloadProgress::Downloading(initial_progress));
+    }
+    let mut stream = pin!(stream);
+    while let Some(item) = stream.next().await {
+        let chunk = match item {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                // Make sure the partial file is complete on disk, so we can resume
+                dest_file.flush().await?;
+                return Ok(WriteOutcome::Interrupted(e));
             }
-            bytes_already_downloaded += chunk_size;
-            dest_file.write_all_buf(&mut chunk).await?;
-            if let Some(verifier) = &mut verifier {
-                verifier.update(&chunk);
+        };
+        let chunk = chunk.as_ref();
+        let chunk_size = chunk.len() as u64;
+        if let Some(l) = content_length {
+            if l != 0 {
+                let progress = bytes_already_downloaded as f64 / l as f64;
+                progress_listener(DownloadProgress::Downloading(progress));
             }
         }
-        if let Some(verifier) = verifier {
-            verifier.verify()
-                .context("Download came with a checksum but downloaded file has another checksum. Discarding download.")?;
+        bytes_already_downloaded += chunk_size;
+        if let Some(verifier) = &mut verifier {
+            verifier.update(chunk);
         }
-        progress_listener(DownloadProgress::Finished);
-        Ok(())
+        dest_file.write_all(chunk).await?;
+        if let Some(limiter) = bandwidth_limiter {
+            limiter.consume(chunk_size).await;
+        }
+    }
+    // Make sure everything is written to disk before anyone goes on reading the file
+    dest_file.flush().await?;
+    if let Some(verifier) = verifier {
+        verifier.verify()
+            .context("Download came with a checksum but downloaded file has another checksum. Discarding download.")?;
     }
+    Ok(WriteOutcome::Finished)
+}
+
+/// Returns the validator to be used in an `If-Range` header when resuming the download, if the
+/// server supports range requests.
+fn get_resume_validator(headers: &HeaderMap) -> Option<HeaderValue> {
+    let accepts_byte_ranges = headers
+        .get(ACCEPT_RANGES)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .any(|unit| unit.trim().eq_ignore_ascii_case("bytes"));
+    if !accepts_byte_ranges {
+        return None;
+    }
+    // If-Range requires a strong validator, so weak ETags are not suitable
+    headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .cloned()
 }
 
 fn get_content_length(headers: &HeaderMap) -> Option<u64> {
     headers.get("Content-Length")?.to_str().ok()?.parse().ok()
 }
 
+/// Returns the start offset of a `Content-Range` header such as `bytes 5-10/11`.
+fn get_content_range_start(headers: &HeaderMap) -> Option<u64> {
+    let range = headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?;
+    let (start, _) = range.trim_start().split_once('-')?;
+    start.parse().ok()
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum DownloadProgress {
     Connecting,
     CreatingDestFile,
     Downloading(f64),
+    /// The download has been interrupted and continues at the given progress.
+    Resuming(f64),
     Finished,
 }
 
@@ -122,8 +447,148 @@ impl DownloadProgress {
         match self {
             DownloadProgress::Connecting => 0.01,
             DownloadProgress::CreatingDestFile => 0.02,
-            DownloadProgress::Downloading(progress) => 0.03 + progress * 0.97,
+            DownloadProgress::Downloading(progress) | DownloadProgress::Resuming(progress) => {
+                0.03 + progress * 0.97
+            }
             DownloadProgress::Finished => 1.0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::HttpConfig;
+    use crate::hash_util::build_sha256_source_hash;
+    use crate::http_client::create_http_client;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    /// Serves "hello world" but breaks the connection in the middle of the first response.
+    async fn serve_interrupted_once(listener: TcpListener) -> Vec<String> {
+        let mut requests = vec![];
+        for i in 0..2 {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 4096];
+            let n = socket.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..n]).to_lowercase();
+            let response = if i == 0 {
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\naccept-ranges: bytes\r\netag: \"v1\"\r\n\r\nhello"
+                    .to_string()
+            } else if request.contains("range: bytes=5-") && request.contains("if-range: \"v1\"") {
+                "HTTP/1.1 206 Partial Content\r\ncontent-length: 6\r\ncontent-range: bytes 5-10/11\r\naccept-ranges: bytes\r\netag: \"v1\"\r\n\r\n world"
+                    .to_string()
+            } else {
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\n\r\nhello world".to_string()
+            };
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+            requests.push(request);
+        }
+        requests
+    }
+
+    /// Serves the given raw responses, one per connection.
+    async fn serve_responses(listener: TcpListener, responses: &[&str]) -> Vec<String> {
+        let mut requests = vec![];
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 4096];
+            let n = socket.read(&mut buffer).await.unwrap();
+            requests.push(String::from_utf8_lossy(&buffer[..n]).to_lowercase());
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+        requests
+    }
+
+    #[tokio::test]
+    async fn restart_download_if_resumed_range_does_not_match() {
+        let dir = tempdir::TempDir::new("reaboot-test-").unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let responses = [
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\naccept-ranges: bytes\r\netag: \"v1\"\r\n\r\nhello",
+                "HTTP/1.1 206 Partial Content\r\ncontent-length: 8\r\ncontent-range: bytes 3-10/11\r\n\r\nlo world",
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\n\r\nhello world",
+            ];
+            serve_responses(listener, &responses).await
+        });
+        let file = dir.path().join("hello.txt");
+        let download = Download::new(
+            "hello".to_string(),
+            Url::parse(&format!("http://127.0.0.1:{port}/hello.txt")).unwrap(),
+            file.clone(),
+            Some(build_sha256_source_hash("hello world")),
+        );
+        let client = create_http_client(&HttpConfig::default(), 0).unwrap();
+        Downloader::new(client, 2)
+            .download(download, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello world");
+        let requests = server.await.unwrap();
+        assert!(requests[1].contains("range: bytes=5-"));
+        assert!(!requests[2].contains("range:"));
+    }
+
+    #[tokio::test]
+    async fn count_failed_resume_request_as_interruption() {
+        let dir = tempdir::TempDir::new("reaboot-test-").unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let responses = [
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\naccept-ranges: bytes\r\netag: \"v1\"\r\n\r\nhello",
+                // Connection closed without any response
+                "",
+                "HTTP/1.1 206 Partial Content\r\ncontent-length: 6\r\ncontent-range: bytes 5-10/11\r\n\r\n world",
+            ];
+            serve_responses(listener, &responses).await
+        });
+        let file = dir.path().join("hello.txt");
+        let download = Download::new(
+            "hello".to_string(),
+            Url::parse(&format!("http://127.0.0.1:{port}/hello.txt")).unwrap(),
+            file.clone(),
+            Some(build_sha256_source_hash("hello world")),
+        );
+        let client = create_http_client(&HttpConfig::default(), 0).unwrap();
+        Downloader::new(client, 2)
+            .download(download, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello world");
+        let requests = server.await.unwrap();
+        assert!(requests[2].contains("range: bytes=5-"));
+    }
+
+    #[tokio::test]
+    async fn resume_interrupted_download() {
+        let dir = tempdir::TempDir::new("reaboot-test-").unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(serve_interrupted_once(listener));
+        let file = dir.path().join("hello.txt");
+        let download = Download::new(
+            "hello".to_string(),
+            Url::parse(&format!("http://127.0.0.1:{port}/hello.txt")).unwrap(),
+            file.clone(),
+            Some(build_sha256_source_hash("hello world")),
+        );
+        let progress_events = Mutex::new(vec![]);
+        let client = create_http_client(&HttpConfig::default(), 0).unwrap();
+        Downloader::new(client, 1)
+            .download(download, |p| progress_events.lock().unwrap().push(p))
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello world");
+        let requests = server.await.unwrap();
+        assert!(requests[1].contains("range: bytes=5-"));
+        assert!(progress_events
+            .into_inner()
+            .unwrap()
+            .contains(&DownloadProgress::Resuming(5.0 / 11.0)));
+    }
+}
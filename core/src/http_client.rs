@@ -0,0 +1,61 @@
+use std::fs;
+
+use anyhow::{bail, Context};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::{Certificate, Identity, NoProxy, Proxy};
+use reqwest_middleware::ClientWithMiddleware;
+
+use crate::api::HttpConfig;
+use crate::retry_middleware::RetryMiddleware;
+
+/// HTTP client with retry behavior, to be shared by all requests.
+pub type HttpClient = ClientWithMiddleware;
+
+/// Creates the HTTP client to be used for all requests.
+///
+/// Failed requests are retried the given number of times.
+pub fn create_http_client(config: &HttpConfig, retries: u32) -> anyhow::Result<HttpClient> {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("reaboot"));
+    for (name, value) in &config.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("invalid HTTP header name {name:?}"))?;
+        let value = HeaderValue::from_str(value)
+            .with_context(|| format!("invalid value for HTTP header {name}"))?;
+        headers.insert(name, value);
+    }
+    let mut builder = reqwest::Client::builder().default_headers(headers);
+    if let Some(proxy_url) = &config.proxy {
+        let proxy = Proxy::all(proxy_url)
+            .context("invalid proxy URL")?
+            .no_proxy(NoProxy::from_string(&config.no_proxy.join(",")));
+        builder = builder.proxy(proxy);
+    }
+    for file in &config.extra_root_certificates {
+        let pem = fs::read(file)
+            .with_context(|| format!("couldn't read root certificate file {file:?}"))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("couldn't parse root certificate file {file:?}"))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    match (&config.client_certificate, &config.client_private_key) {
+        (None, None) => {}
+        (Some(cert_file), Some(key_file)) => {
+            let cert = fs::read(cert_file)
+                .with_context(|| format!("couldn't read client certificate file {cert_file:?}"))?;
+            let key = fs::read(key_file)
+                .with_context(|| format!("couldn't read client private key file {key_file:?}"))?;
+            let identity = Identity::from_pkcs8_pem(&cert, &key)
+                .context("couldn't load client certificate")?;
+            builder = builder.identity(identity);
+        }
+        _ => bail!("A client certificate must be accompanied by a private key and vice versa"),
+    }
+    let client = builder.build().context("couldn't create HTTP client")?;
+    let client = reqwest_middleware::ClientBuilder::new(client)
+        .with(RetryMiddleware::new(retries))
+        .build();
+    Ok(client)
+}
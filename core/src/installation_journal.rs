@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::file_util::{create_parent_dirs, move_file};
+use crate::reaper_resource_dir::ReaperResourceDir;
+
+/// Name of the subdirectory of the backup directory which contains backups of package files
+/// (and other non-configuration files) that have been overwritten or removed.
+pub const FILE_BACKUP_DIR_NAME: &str = "files";
+
+/// Name of the file in the backup directory which contains the persisted journal.
+pub const JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// Records every change that the installer makes to the REAPER resource directory in the final
+/// installation phase, so that these changes can be rolled back if one of the steps fails.
+///
+/// Each overwritten or removed file is moved into the backup directory first. On success, the
+/// journal is persisted in the backup directory, which makes it possible to restore the previous
+/// state later.
+pub(crate) struct InstallationJournal {
+    reaper_resource_dir: ReaperResourceDir,
+    backup_dir: PathBuf,
+    entries: Vec<JournalEntry>,
+}
+
+/// Persisted form of an installation journal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalFile {
+    /// When the changes have been applied.
+    pub timestamp: jiff::Timestamp,
+    /// All changes in the order in which they have been applied.
+    pub entries: Vec<JournalEntry>,
+}
+
+/// A single change to the REAPER resource directory.
+///
+/// Paths are relative to the REAPER resource directory, backup paths are relative to the
+/// backup directory. Both use forward slashes as separator.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// A directory that didn't exist before.
+    CreatedDir { path: String },
+    /// A file that has been placed at `path`.
+    ///
+    /// If `backup` is set, the file overwrote an existing one, which has been moved to `backup`.
+    PlacedFile {
+        path: String,
+        backup: Option<String>,
+    },
+    /// A file that has been removed from `path` by moving it to `backup`.
+    RemovedFile { path: String, backup: String },
+}
+
+impl InstallationJournal {
+    pub fn new(reaper_resource_dir: ReaperResourceDir, backup_dir: PathBuf) -> Self {
+        Self {
+            reaper_resource_dir,
+            backup_dir,
+            entries: vec![],
+        }
+    }
+
+    /// Returns the backup location for a configuration file such as `reapack.ini`.
+    ///
+    /// Configuration files are backed up flat, right into the backup directory.
+    pub fn config_file_backup(&self, file: &Path) -> anyhow::Result<PathBuf> {
+        let file_name = file.file_name().context("file has no name")?;
+        Ok(self.backup_dir.join(file_name))
+    }
+
+    /// Returns the backup location for a package file (or any other file), given its path
+    /// relative to the REAPER resource directory.
+    ///
+    /// The directory structure is mirrored within the backup directory.
+    pub fn file_backup(&self, relative_path: impl AsRef<Path>) -> PathBuf {
+        self.backup_dir
+            .join(FILE_BACKUP_DIR_NAME)
+            .join(relative_path)
+    }
+
+    /// Moves `src_file` to `dest_file`, moving an existing `dest_file` to `backup_file` before.
+    pub fn move_file_overwriting_with_backup(
+        &mut self,
+        src_file: &Path,
+        dest_file: &Path,
+        backup_file: PathBuf,
+    ) -> anyhow::Result<()> {
+        self.back_up_existing_dest_file(dest_file, backup_file)?;
+        move_file(src_file, dest_file, true)
+    }
+
+    /// Copies `src_file` to `dest_file`, moving an existing `dest_file` to `backup_file` before.
+    pub fn copy_file_overwriting_with_backup(
+        &mut self,
+        src_file: &Path,
+        dest_file: &Path,
+        backup_file: PathBuf,
+    ) -> anyhow::Result<()> {
+        self.back_up_existing_dest_file(dest_file, backup_file)?;
+        fs::copy(src_file, dest_file).context("copying file to destination failed")?;
+        Ok(())
+    }
+
+    /// Removes `file` by moving it to `backup_file`.
+    ///
+    /// Files that don't exist anymore are skipped.
+    pub fn remove_file_with_backup(
+        &mut self,
+        file: &Path,
+        backup_file: PathBuf,
+    ) -> anyhow::Result<()> {
+        if file.try_exists().is_ok_and(|exists| !exists) {
+            return Ok(());
+        }
+        let entry = JournalEntry::RemovedFile {
+            path: self.relative_path(file)?,
+            backup: self.relative_backup_path(&backup_file)?,
+        };
+        self.create_parent_dirs(&backup_file)
+            .context("couldn't create backup directory")?;
+        fs::rename(file, &backup_file)
+            .with_context(|| format!("couldn't back up file {file:?}"))?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Persists the journal in the backup directory.
+    ///
+    /// Does nothing if no change has been recorded.
+    pub fn commit(self) -> anyhow::Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        let journal_file = JournalFile {
+            timestamp: jiff::Timestamp::now(),
+            entries: self.entries,
+        };
+        journal_file.save_to_dir(&self.backup_dir)
+    }
+
+    /// Reverts all recorded changes in reverse order.
+    ///
+    /// Continues reverting even if one step fails, in order to restore as much as possible.
+    pub fn rollback(self) -> anyhow::Result<()> {
+        let mut num_failed_entries = 0;
+        for entry in self.entries.into_iter().rev() {
+            if let Err(e) = revert_entry(&self.reaper_resource_dir, &self.backup_dir, &entry) {
+                tracing::warn!(msg = "Couldn't revert journal entry", ?entry, %e);
+                num_failed_entries += 1;
+            }
+        }
+        if num_failed_entries > 0 {
+            bail!(
+                "Couldn't revert {num_failed_entries} change(s). Backups are located in {:?}.",
+                self.backup_dir
+            );
+        }
+        Ok(())
+    }
+
+    fn back_up_existing_dest_file(
+        &mut self,
+        dest_file: &Path,
+        backup_file: PathBuf,
+    ) -> anyhow::Result<()> {
+        let path = self.relative_path(dest_file)?;
+        let backup = if dest_file.exists() {
+            let relative_backup_path = self.relative_backup_path(&backup_file)?;
+            self.create_parent_dirs(&backup_file)
+                .context("couldn't create backup directory")?;
+            fs::rename(dest_file, &backup_file)
+                .with_context(|| format!("couldn't back up file {dest_file:?}"))?;
+            Some(relative_backup_path)
+        } else {
+            self.create_parent_dirs(dest_file)?;
+            None
+        };
+        // Record before placing the file, so that a rollback restores the backup even if
+        // placing fails
+        self.entries.push(JournalEntry::PlacedFile { path, backup });
+        Ok(())
+    }
+
+    /// Creates all parent directories of `path` that don't exist yet and records them.
+    fn create_parent_dirs(&mut self, path: &Path) -> anyhow::Result<()> {
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+        let missing_dirs: Vec<_> = parent
+            .ancestors()
+            .take_while(|dir| dir.try_exists().is_ok_and(|exists| !exists))
+            .map(Path::to_path_buf)
+            .collect();
+        // Create from top to bottom, so that a rollback removes from bottom to top
+        for dir in missing_dirs.into_iter().rev() {
+            let entry = JournalEntry::CreatedDir {
+                path: self.relative_path(&dir)?,
+            };
+            fs::create_dir(&dir).with_context(|| format!("couldn't create directory {dir:?}"))?;
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+
+    fn relative_path(&self, path: &Path) -> anyhow::Result<String> {
+        to_relative_path(self.reaper_resource_dir.get(), path)
+    }
+
+    fn relative_backup_path(&self, path: &Path) -> anyhow::Result<String> {
+        to_relative_path(&self.backup_dir, path)
+    }
+}
+
+impl JournalFile {
+    /// Loads the journal persisted in the given backup directory, if there's one.
+    pub fn load_from_dir(backup_dir: &Path) -> anyhow::Result<Option<Self>> {
+        let file = backup_dir.join(JOURNAL_FILE_NAME);
+        if !file.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&file).context("couldn't read journal")?;
+        let journal = serde_json::from_str(&json).context("couldn't parse journal")?;
+        Ok(Some(journal))
+    }
+
+    fn save_to_dir(&self, backup_dir: &Path) -> anyhow::Result<()> {
+        let file = backup_dir.join(JOURNAL_FILE_NAME);
+        create_parent_dirs(&file)?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(file, json).context("couldn't write journal")?;
+        Ok(())
+    }
+}
+
+fn revert_entry(
+    reaper_resource_dir: &ReaperResourceDir,
+    backup_dir: &Path,
+    entry: &JournalEntry,
+) -> anyhow::Result<()> {
+    match entry {
+        JournalEntry::CreatedDir { path } => {
+            fs::remove_dir(reaper_resource_dir.join(path))?;
+        }
+        JournalEntry::PlacedFile { path, backup } => {
+            let path = reaper_resource_dir.join(path);
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            if let Some(backup) = backup {
+                fs::rename(backup_dir.join(backup), path)?;
+            }
+        }
+        JournalEntry::RemovedFile { path, backup } => {
+            fs::rename(backup_dir.join(backup), reaper_resource_dir.join(path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts the given path to a path relative to `base_dir`, using forward slashes.
+fn to_relative_path(base_dir: &Path, path: &Path) -> anyhow::Result<String> {
+    let relative_path = path
+        .strip_prefix(base_dir)
+        .with_context(|| format!("{path:?} is not located within {base_dir:?}"))?;
+    let segments: Option<Vec<&str>> = relative_path
+        .components()
+        .map(|c| match c {
+            Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect();
+    let segments = segments.with_context(|| format!("{relative_path:?} is not a simple path"))?;
+    Ok(segments.join("/"))
+}
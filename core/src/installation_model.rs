@@ -5,13 +5,16 @@ use crate::reaper_platform::ReaperPlatform;
 use camino::Utf8Path;
 use reaboot_reapack::index::{Category, IndexPackageType, IndexPlatform, Package, Source, Version};
 use reaboot_reapack::model::{
-    InstalledPackage, InstalledPackageType, LightPackageId, LightVersionId, PackageType,
-    PackageUrl, VersionRef,
+    InstalledPackage, InstalledPackageType, InstalledVersionName, LightPackageId, LightVersionId,
+    PackagePath, PackageType, PackageUrl, PackageVersionRef, VersionRef,
 };
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
+use schemars::JsonSchema;
+use serde::Serialize;
 use thiserror::Error;
+use ts_rs::TS;
 use url::Url;
 
 pub struct PreDownloadFailures<'a> {
@@ -41,7 +44,8 @@ pub struct SinglePackageInstallationPlan<'a> {
     pub to_be_removed: Option<&'a InstalledPackage>,
 }
 
-#[derive(Copy, Clone, Error, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Error, Debug, Serialize, TS, JsonSchema)]
+#[ts(export)]
 pub enum PackageDescError {
     #[error("Repository is unavailable")]
     RepositoryIndexUnavailable,
@@ -102,6 +106,104 @@ pub fn make_first_plan<'a>(
     }
 }
 
+/// An installed package that is a candidate for being updated.
+pub struct UpdateCandidate {
+    /// URL of the repository index that belongs to the remote from which the package was
+    /// installed.
+    pub repository_url: Url,
+    pub package: InstalledPackage,
+}
+
+/// Returns package URLs pointing to the latest versions of those update candidates for which the
+/// given indexes contain a newer version than the installed one.
+///
+/// Just like ReaPack, this considers pre-releases only if `include_pre_releases` is `true` (the
+/// global pre-release setting), for packages that are marked as "bleeding edge" or for packages
+/// that are already on a pre-release. Pinned packages are never updated.
+pub fn determine_package_urls_for_update(
+    candidates: &[UpdateCandidate],
+    indexes: &HashMap<Url, DownloadedIndex>,
+    include_pre_releases: bool,
+) -> Vec<PackageUrl> {
+    candidates
+        .iter()
+        .filter_map(|candidate| {
+            let p = &candidate.package;
+            if p.is_pinned() {
+                return None;
+            }
+            let InstalledVersionName::Valid(installed_version) = &p.version else {
+                return None;
+            };
+            let package = indexes
+                .get(&candidate.repository_url)?
+                .index
+                .find_category(&p.category)?
+                .find_package(&p.package)?;
+            let consider_pre_releases =
+                include_pre_releases || p.is_bleeding_edge() || !installed_version.is_stable();
+            let latest_version = if consider_pre_releases {
+                package.latest_version_including_pre_releases()?
+            } else {
+                package.latest_stable_version()?
+            };
+            if &latest_version.name <= installed_version {
+                return None;
+            }
+            let package_url = PackageUrl {
+                repository_url: candidate.repository_url.clone(),
+                package_version_ref: PackageVersionRef {
+                    package_path: PackagePath {
+                        category: p.category.clone(),
+                        package_name: p.package.clone(),
+                    },
+                    version_ref: VersionRef::Specific(latest_version.name.clone()),
+                },
+            };
+            Some(package_url)
+        })
+        .collect()
+}
+
+/// Returns package URLs pointing to the latest versions of all packages in the given index, as
+/// installed by ReaPack's auto-install.
+///
+/// Just like ReaPack, this silently skips packages that have an unknown type or no version that
+/// is installable on the given platform.
+pub fn determine_package_urls_for_auto_install(
+    index: &DownloadedIndex,
+    include_pre_releases: bool,
+    platform: ReaperPlatform,
+) -> Vec<PackageUrl> {
+    index
+        .index
+        .categories()
+        .flat_map(|category| category.packages.iter().map(move |p| (category, p)))
+        .filter_map(|(category, package)| {
+            if !matches!(package.typ, IndexPackageType::Known(_)) {
+                return None;
+            }
+            let latest_version = if include_pre_releases {
+                package.latest_version_including_pre_releases()?
+            } else {
+                package.latest_stable_version()?
+            };
+            get_platform_compatible_sources(latest_version, platform).next()?;
+            let package_url = PackageUrl {
+                repository_url: index.url.clone(),
+                package_version_ref: PackageVersionRef {
+                    package_path: PackagePath {
+                        category: category.name.clone(),
+                        package_name: package.name.clone(),
+                    },
+                    version_ref: VersionRef::Specific(latest_version.name.clone()),
+                },
+            };
+            Some(package_url)
+        })
+        .collect()
+}
+
 pub struct FirstPlan<'a> {
     pub files_to_be_downloaded: Vec<QualifiedSource<'a>>,
     pub pre_download_failures: PreDownloadFailures<'a>,
@@ -216,7 +318,7 @@ fn resolve_and_deduplicate_versions<'a>(
     (qualified_versions.into_values().collect(), failures)
 }
 
-fn lookup_package_version_in_index<'i>(
+pub(crate) fn lookup_package_version_in_index<'i>(
     package_url: &PackageUrl,
     index: &'i DownloadedIndex,
 ) -> Result<QualifiedVersion<'i>, PackageDescError> {
@@ -267,7 +369,7 @@ fn weed_out_packages_with_version_conflicts(
     )
 }
 
-fn resolve_package_sources_weeding_out_platform_incompatible_versions(
+pub(crate) fn resolve_package_sources_weeding_out_platform_incompatible_versions(
     versions: Vec<QualifiedVersion>,
     reaper_target: ReaperPlatform,
 ) -> (Vec<QualifiedSource>, Vec<QualifiedVersion>) {
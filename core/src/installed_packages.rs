@@ -0,0 +1,90 @@
+use anyhow::{bail, Context};
+use std::fs;
+use tempdir::TempDir;
+
+use reaboot_reapack::database::{CompatibilityInfo, Database};
+use reaboot_reapack::model::{InstalledPackage, InstalledPackageType, PackageType};
+
+use crate::api::InstalledPackageInfo;
+use crate::installer::REABOOT_TEMP_DIR_PREFIX;
+use crate::reaper_resource_dir::ReaperResourceDir;
+
+/// Criteria for narrowing down the list of installed packages.
+#[derive(Clone, Debug, Default)]
+pub struct InstalledPackageFilter {
+    /// If not empty, only packages from one of these remotes are included (case-insensitive).
+    pub remotes: Vec<String>,
+    /// If not empty, only packages of one of these types are included.
+    pub types: Vec<PackageType>,
+}
+
+impl InstalledPackageFilter {
+    pub fn matches(&self, package: &InstalledPackage) -> bool {
+        let remote_matches = self.remotes.is_empty()
+            || self
+                .remotes
+                .iter()
+                .any(|r| r.eq_ignore_ascii_case(&package.remote));
+        let type_matches = self.types.is_empty()
+            || matches!(package.typ, InstalledPackageType::Known(t) if self.types.contains(&t));
+        remote_matches && type_matches
+    }
+}
+
+/// Returns all packages that are currently installed in the given REAPER resource directory
+/// according to the ReaPack database, sorted by package ID.
+///
+/// The ReaPack database is opened in read-only mode. If it has an older schema that needs
+/// migration, only a temporary copy of it will be migrated.
+pub async fn list_installed_packages(
+    reaper_resource_dir: &ReaperResourceDir,
+    filter: &InstalledPackageFilter,
+) -> anyhow::Result<Vec<InstalledPackageInfo>> {
+    let db_file = reaper_resource_dir.reapack_registry_db_file();
+    if !db_file.exists() {
+        return Ok(vec![]);
+    }
+    let mut db = Database::open_read_only(&db_file)
+        .await
+        .context("couldn't open ReaPack database")?;
+    let packages = match db.compatibility_info().await? {
+        CompatibilityInfo::DbTooNew => {
+            bail!("This ReaBoot version is too old to read your installed ReaPack database. Please download the latest ReaBoot version!");
+        }
+        CompatibilityInfo::CompatibleButNeedsMigration => {
+            db.close().await?;
+            let temp_dir =
+                TempDir::new(REABOOT_TEMP_DIR_PREFIX).context("couldn't create temp directory")?;
+            let temp_db_file = temp_dir.path().join("registry.db");
+            fs::copy(&db_file, &temp_db_file).context("couldn't copy ReaPack database")?;
+            let mut temp_db = Database::open(&temp_db_file).await?;
+            let packages = temp_db.installed_packages().await?;
+            temp_db.close().await?;
+            packages
+        }
+        CompatibilityInfo::PerfectlyCompatible | CompatibilityInfo::DbNewerButCompatible => {
+            let packages = db.installed_packages().await?;
+            db.close().await?;
+            packages
+        }
+    };
+    let mut infos: Vec<_> = packages
+        .into_iter()
+        .filter(|p| filter.matches(p))
+        .map(|p| installed_package_info(&p))
+        .collect();
+    infos.sort();
+    Ok(infos)
+}
+
+pub(crate) fn installed_package_info(p: &InstalledPackage) -> InstalledPackageInfo {
+    InstalledPackageInfo {
+        remote: p.remote.clone(),
+        category: p.category.clone(),
+        package: p.package.clone(),
+        version: p.version.to_string(),
+        author: p.author.clone(),
+        typ: p.typ.to_string(),
+        file_count: p.files.len(),
+    }
+}
@@ -1,33 +1,44 @@
 use crate::api::{
-    ConfirmationRequest, DownloadInfo, InstallationStage, InstallerConfig, MultiDownloadInfo,
-    PackageInfo, ResolvedInstallerConfig,
+    AutoInstallMode, ConfirmationRequest, DownloadInfo, InstallationStage, InstallerConfig,
+    MultiDownloadInfo, PackageInfo, ResolvedInstallerConfig,
 };
+use crate::bundle::{BundleRecorder, OfflineBundle};
+use crate::download_cache::DownloadCache;
 use crate::downloader::{Download, Downloader};
 use crate::file_util::{
     create_parent_dirs, existing_file_or_dir_is_writable, file_or_dir_is_writable_or_creatable,
-    get_first_existing_parent_dir, move_dir_contents, move_file, move_file_overwriting_with_backup,
+    get_first_existing_parent_dir, move_dir_contents, move_file,
 };
+use crate::http_client::HttpClient;
+use crate::installation_journal::InstallationJournal;
 use crate::installation_model::{
-    make_first_plan, QualifiedSource, SinglePackageInstallationPlan, TempInstallFailure,
+    determine_package_urls_for_auto_install, determine_package_urls_for_update, make_first_plan,
+    QualifiedSource, SinglePackageInstallationPlan, TempInstallFailure, UpdateCandidate,
 };
+use crate::lockfile::Lockfile;
 use crate::multi_downloader::{
     DownloadError, DownloadResult, DownloadWithPayload, MultiDownloader,
 };
-use crate::preparation_report::PreparationReport;
+use crate::preparation_report::{
+    AutoInstallation, PreparationReport, ReaPackSettingChange, RemoteRegistration,
+};
 
+use crate::reaboot_util::ResolvePurpose;
 use crate::reaper_resource_dir::{
     ReaperResourceDir, REAPACK_INI_FILE_PATH, REAPACK_REGISTRY_DB_FILE_PATH,
 };
 use crate::reaper_util::{extract_reaper_to_dir, ReaperInstallerAsset};
+use crate::recipe::RecipeRemote;
 use crate::task_tracker::{TaskSummary, TaskTrackerListener};
+use crate::uninstaller::{dry_remove_package_files, remove_package_files};
 use crate::{reaboot_util, reaper_util, ToolDownload, ToolingChange};
-use anyhow::{bail, ensure, Context, Error};
+use anyhow::{anyhow, ensure, Context, Error};
 use enumset::EnumSet;
 use reaboot_reapack::database::{Database, DatabaseTransaction};
 use reaboot_reapack::index::{Index, IndexSection, NormalIndexSection};
 use reaboot_reapack::model::{
     Config, InstalledFile, InstalledPackage, InstalledPackageType, InstalledVersionName,
-    LightPackageId, LightVersionId, Remote, Section,
+    LightPackageId, LightVersionId, PackageUrl, Remote, Section,
 };
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
@@ -36,17 +47,20 @@ use std::fs;
 use std::io::BufReader;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tempdir::TempDir;
 use thiserror::Error;
 use tokio::sync::broadcast::Receiver;
 use url::Url;
 
-const REABOOT_TEMP_DIR_PREFIX: &str = "reaboot-";
+pub(crate) const REABOOT_TEMP_DIR_PREFIX: &str = "reaboot-";
 
 /// Responsible for orchestrating and carrying out the actual installation.
 pub struct Installer<L> {
     resolved_config: ResolvedInstallerConfig,
     downloader: Downloader,
+    /// HTTP client shared by all requests.
+    http_client: HttpClient,
     multi_downloader: MultiDownloader,
     /// Temporary directory used for most of the downloads.
     ///
@@ -79,15 +93,41 @@ impl<L: InstallerListener> Installer<L> {
     ///
     /// Creates a temporary directly already.
     pub async fn new(args: InstallerNewArgs<L>) -> anyhow::Result<Self> {
-        let resolved_config = reaboot_util::resolve_config(args.config).await?;
+        Self::new_internal(args, ResolvePurpose::Install).await
+    }
+
+    /// Creates a new installer that is only going to be used for [`Self::lock`].
+    ///
+    /// Doesn't check whether the REAPER resource directory is ready for installing packages,
+    /// because nothing is going to be installed. Recipe package conditions are only checked for
+    /// the platform.
+    pub async fn new_for_locking(args: InstallerNewArgs<L>) -> anyhow::Result<Self> {
+        Self::new_internal(args, ResolvePurpose::Lock).await
+    }
+
+    async fn new_internal(
+        args: InstallerNewArgs<L>,
+        purpose: ResolvePurpose,
+    ) -> anyhow::Result<Self> {
+        let mut config = args.config;
+        let http_client = reaboot_util::create_http_client_for_config(&config)?;
+        let http_config = config.http.take().unwrap_or_default();
+        let bundle = config
+            .bundle
+            .take()
+            .map(|file| -> anyhow::Result<_> {
+                let bundle = OfflineBundle::open(&file)?;
+                bundle.apply_to_config(&mut config)?;
+                Ok(Arc::new(bundle))
+            })
+            .transpose()?;
+        let resolved_config =
+            reaboot_util::resolve_config_internal(config, purpose, &http_client).await?;
         // Do some early sanity checks
-        let dest_reapack_db_file = resolved_config
-            .reaper_resource_dir
-            .reapack_registry_db_file();
-        if dest_reapack_db_file.exists() {
-            Database::open(dest_reapack_db_file).await.context("ReaPack database is currently busy. Please close REAPER and/or stop existing ReaBoot processes and try again!")?;
+        if purpose == ResolvePurpose::Install {
+            reaboot_util::complain_if_reapack_db_busy(&resolved_config.reaper_resource_dir).await?;
         }
-        if !resolved_config.dry_run {
+        if purpose == ResolvePurpose::Install && !resolved_config.dry_run {
             let resource_dir = resolved_config.reaper_resource_dir.get();
             ensure!(file_or_dir_is_writable_or_creatable(resource_dir), "REAPER resource directory {resource_dir:?} is read-only. Are you trying to write into a system directory? REAPER resource directories are usually accessible without root/admin privileges.");
         }
@@ -103,12 +143,27 @@ impl<L: InstallerListener> Installer<L> {
         };
         let temp_reaper_resource_dir = temp_dir.join("REAPER");
         fs::create_dir_all(&temp_reaper_resource_dir)?;
+        let mut downloader = reaboot_util::create_downloader(
+            http_client.clone(),
+            &http_config,
+            resolved_config.num_download_retries,
+            resolved_config.max_download_bytes_per_second,
+        )?;
+        if let Some(bundle) = bundle {
+            downloader = downloader.with_bundle(bundle);
+        }
         let installer = Self {
             multi_downloader: MultiDownloader::new(
-                Downloader::new(resolved_config.num_download_retries),
+                downloader.clone(),
                 resolved_config.concurrent_downloads,
+                resolved_config.concurrent_downloads_per_host,
+                resolved_config
+                    .download_cache_dir
+                    .clone()
+                    .map(|dir| DownloadCache::new(dir, resolved_config.max_download_cache_size)),
             ),
-            downloader: Downloader::new(resolved_config.num_download_retries),
+            downloader,
+            http_client,
             temp_dir,
             temp_reaper_resource_dir: ReaperResourceDir::new(temp_reaper_resource_dir)?,
             temp_dir_for_reaper_download: args.temp_dir_for_reaper_download,
@@ -117,6 +172,11 @@ impl<L: InstallerListener> Installer<L> {
             _temp_dir_guard: temp_dir_guard,
             resolved_config,
         };
+        for name in &installer.resolved_config.ignored_recipe_reapack_settings {
+            installer.listener.warn(format!(
+                "Ignoring ReaPack setting {name:?} of the recipe because network settings of recipes need to be allowed explicitly"
+            ));
+        }
         Ok(installer)
     }
 
@@ -124,6 +184,19 @@ impl<L: InstallerListener> Installer<L> {
         &self.resolved_config
     }
 
+    /// Returns the HTTP client that the installer uses for all requests.
+    ///
+    /// Useful for making additional requests with the same network settings.
+    pub fn http_client(&self) -> &HttpClient {
+        &self.http_client
+    }
+
+    /// Records all downloads in the given recorder, for creating an offline bundle.
+    pub(crate) fn record_downloads(&mut self, recorder: Arc<BundleRecorder>) {
+        self.downloader = self.downloader.clone().with_recorder(recorder.clone());
+        self.multi_downloader.record_downloads(recorder);
+    }
+
     pub async fn install(mut self) -> Result<InstallationOutcome, InstallError> {
         let result = self.install_internal().await;
         let final_stage = match &result {
@@ -137,13 +210,104 @@ impl<L: InstallerListener> Installer<L> {
         result
     }
 
+    /// Resolves all packages to exact versions and creates a lockfile, without installing
+    /// anything.
+    ///
+    /// Packages are downloaded nevertheless, in order to make sure that they are installable and
+    /// to compute hashes for files that don't come with a hash in the repository index.
+    pub async fn lock(self) -> Result<Lockfile, InstallError> {
+        let result = self.lock_internal().await;
+        let final_stage = match &result {
+            Ok(_) => InstallationStage::Finished,
+            Err(e) => InstallationStage::Failed {
+                display_msg: format!("{e:#}"),
+            },
+        };
+        self.listener.installation_stage_changed(final_stage);
+        self.clean_up();
+        result
+    }
+
+    async fn lock_internal(&self) -> Result<Lockfile, InstallError> {
+        let repository_urls = self
+            .resolved_config
+            .package_urls
+            .iter()
+            .map(|purl| purl.repository_url().clone())
+            .collect();
+        let downloaded_indexes = self.download_repository_indexes(repository_urls).await?;
+        // Locally installed packages are irrelevant for the lockfile
+        let first_plan = make_first_plan(
+            &self.resolved_config.package_urls,
+            &downloaded_indexes,
+            &[],
+            self.resolved_config.platform,
+        );
+        let package_download_results = self
+            .download_packages(first_plan.files_to_be_downloaded)
+            .await;
+        let (successful_downloads, download_errors) =
+            weed_out_download_errors(package_download_results);
+        let preparation_report = PreparationReport::new(
+            vec![],
+            first_plan.pre_download_failures,
+            download_errors,
+            vec![],
+            &[],
+            &[],
+        );
+        if preparation_report.summary().failures > 0 {
+            return Err(InstallError::SomePackagesFailed(preparation_report));
+        }
+        let lockfile =
+            Lockfile::from_downloads(self.resolved_config.platform, &successful_downloads)?;
+        Ok(lockfile)
+    }
+
     async fn install_internal(&mut self) -> Result<InstallationOutcome, InstallError> {
         // Download and extract REAPER if necessary
         let reaper_preparation_outcome = self.download_and_prepare_reaper_if_necessary().await?;
         // Prepare temporary directory
         self.prepare_temp_dir()?;
-        // Download repository indexes
-        let downloaded_indexes = self.download_repository_indexes().await?;
+        // Gather installed packages that might need an update
+        let update_candidates = if self.resolved_config.update_installed_packages {
+            self.gather_update_candidates().await?
+        } else {
+            vec![]
+        };
+        // Load ReaPack config as it is before the installation
+        let existing_reapack_config = self.load_temp_reapack_config()?;
+        // Download repository indexes (including the ones of recipe remotes whose packages
+        // should be installed automatically)
+        let auto_install_recipe_remote_urls: Vec<_> = self
+            .recipe_remotes()
+            .iter()
+            .filter(|r| {
+                self.is_new_auto_install_remote(existing_reapack_config.as_ref(), &r.name, &r.url)
+            })
+            .map(|r| r.url.clone())
+            .collect();
+        let repository_urls = self
+            .resolved_config
+            .package_urls
+            .iter()
+            .map(|purl| purl.repository_url())
+            .chain(update_candidates.iter().map(|c| &c.repository_url))
+            .cloned()
+            .chain(auto_install_recipe_remote_urls)
+            .collect();
+        let downloaded_indexes = self.download_repository_indexes(repository_urls).await?;
+        // Add package URLs for installed packages that have a newer version
+        if !update_candidates.is_empty() {
+            self.add_package_urls_for_update(&update_candidates, &downloaded_indexes);
+        }
+        // Add package URLs for all packages of new remotes that have auto-install enabled
+        let auto_installations = self
+            .add_package_urls_for_auto_install(
+                existing_reapack_config.as_ref(),
+                &downloaded_indexes,
+            )
+            .await;
         // Check which packages are installed already
         let package_status_quo = self
             .gather_already_installed_packages(&downloaded_indexes)
@@ -200,7 +364,7 @@ impl<L: InstallerListener> Installer<L> {
         {
             tooling_changes.push(ToolingChange::new("REAPER".to_string(), download.clone()));
         }
-        let preparation_report = PreparationReport::new(
+        let mut preparation_report = PreparationReport::new(
             tooling_changes,
             first_plan.pre_download_failures,
             download_errors,
@@ -208,6 +372,17 @@ impl<L: InstallerListener> Installer<L> {
             &first_plan.installed_packages_to_be_removed,
             &package_installation_plans,
         );
+        let existing_reapack_config = existing_reapack_config.unwrap_or_default();
+        preparation_report.remote_registrations = self
+            .plan_recipe_remote_registrations(&existing_reapack_config, &downloaded_indexes)
+            .into_iter()
+            .map(|(_, registration)| registration)
+            .collect();
+        preparation_report.reapack_setting_changes = ReaPackSettingChange::determine(
+            &self.resolved_config.reapack_settings,
+            &existing_reapack_config,
+        );
+        preparation_report.auto_installations = auto_installations;
         if self.resolved_config.dry_run {
             let outcome = InstallationOutcome {
                 preparation_report,
@@ -226,18 +401,36 @@ impl<L: InstallerListener> Installer<L> {
         } else {
             None
         };
-        // Apply ReaPack state
-        // We do that *before* applying the packages. If something fails when
-        // copying/moving the package files, the real ReaPack can still install the
-        // packages via its synchronization feature.
-        self.apply_reapack_state(&downloaded_indexes)
-            .context("applying ReaPack state failed")?;
-        // Apply packages
-        self.install_packages(
+        // Apply ReaPack state and packages, rolling back all changes if something fails
+        let mut journal = InstallationJournal::new(
+            self.resolved_config.reaper_resource_dir.clone(),
+            self.resolved_config.backup_dir.clone(),
+        );
+        let apply_result = self.apply_changes(
+            &mut journal,
+            &downloaded_indexes,
             &first_plan.installed_packages_to_be_removed,
             package_installation_plans,
-        )
-        .context("moving packages failed")?;
+        );
+        if let Err(error) = apply_result {
+            self.listener
+                .warn("Installation failed. Rolling back all changes...");
+            return match journal.rollback() {
+                Ok(_) => Err(error
+                    .context("installation failed but all changes have been rolled back")
+                    .into()),
+                Err(rollback_error) => Err(error
+                    .context(format!(
+                        "installation failed and rolling back failed as well: {rollback_error:#}"
+                    ))
+                    .into()),
+            };
+        }
+        // Persist journal, which makes it possible to restore the previous state later
+        if let Err(e) = journal.commit() {
+            self.listener
+                .warn(format!("Couldn't persist installation journal: {e:#}"));
+        }
         // Build final outcome
         let outcome = InstallationOutcome {
             preparation_report,
@@ -340,6 +533,192 @@ impl<L: InstallerListener> Installer<L> {
         Ok(quo)
     }
 
+    /// Returns all installed packages whose remote is configured and enabled, together with the
+    /// repository URL of that remote.
+    async fn gather_update_candidates(&self) -> anyhow::Result<Vec<UpdateCandidate>> {
+        let reapack_db_file = self.temp_reaper_resource_dir.reapack_registry_db_file();
+        if !reapack_db_file.exists() {
+            return Ok(vec![]);
+        }
+        let config = self.load_temp_reapack_config()?.unwrap_or_default();
+        let mut db = Database::open(reapack_db_file).await?;
+        // Migration is okay, we are working on a copy of the original DB file
+        let installed_packages = db.installed_packages().await?;
+        db.close().await?;
+        let candidates = installed_packages
+            .into_iter()
+            .filter_map(|package| {
+                let Some(remote) = config.remote_by_name.get(&package.remote) else {
+                    self.listener.warn(format!(
+                        "Can't update package {} because remote {:?} is not configured",
+                        package.package_id(),
+                        &package.remote
+                    ));
+                    return None;
+                };
+                if !remote.enabled {
+                    return None;
+                }
+                let candidate = UpdateCandidate {
+                    repository_url: remote.url.clone(),
+                    package,
+                };
+                Some(candidate)
+            })
+            .collect();
+        Ok(candidates)
+    }
+
+    /// Adds package URLs for all update candidates with newer versions available, unless the
+    /// package in question is explicitly requested already.
+    fn add_package_urls_for_update(
+        &mut self,
+        update_candidates: &[UpdateCandidate],
+        downloaded_indexes: &HashMap<Url, DownloadedIndex>,
+    ) {
+        let update_urls = determine_package_urls_for_update(
+            update_candidates,
+            downloaded_indexes,
+            self.resolved_config.include_pre_releases,
+        );
+        let package_urls = &mut self.resolved_config.package_urls;
+        for update_url in update_urls {
+            if !contains_same_package(package_urls, &update_url) {
+                package_urls.push(update_url);
+            }
+        }
+    }
+
+    /// Adds package URLs for all packages of remotes that are added or enabled by this
+    /// installation and have auto-install enabled, unless the package in question is
+    /// requested already.
+    ///
+    /// With [`AutoInstallMode::AskForNewRemotes`], asks the user for confirmation first.
+    async fn add_package_urls_for_auto_install(
+        &mut self,
+        existing_reapack_config: Option<&Config>,
+        downloaded_indexes: &HashMap<Url, DownloadedIndex>,
+    ) -> Vec<AutoInstallation> {
+        let mut interactions = self.interactions.resubscribe();
+        let mut auto_installations = vec![];
+        let mut auto_install_urls = vec![];
+        for index in downloaded_indexes.values() {
+            if !self.is_new_auto_install_remote(existing_reapack_config, &index.name, &index.url) {
+                continue;
+            }
+            let urls: Vec<_> = determine_package_urls_for_auto_install(
+                index,
+                self.resolved_config.include_pre_releases,
+                self.resolved_config.platform,
+            )
+            .into_iter()
+            .filter(|url| !contains_same_package(&self.resolved_config.package_urls, url))
+            .collect();
+            auto_installations.push(AutoInstallation {
+                remote: index.name.clone(),
+                url: index.url.clone(),
+                package_count: urls.len(),
+            });
+            auto_install_urls.extend(urls);
+        }
+        auto_installations.sort_by(|a, b| a.remote.cmp(&b.remote));
+        if self.resolved_config.auto_install_mode == AutoInstallMode::AskForNewRemotes
+            && !auto_install_urls.is_empty()
+            && !self
+                .confirm_auto_installations(&auto_installations, &mut interactions)
+                .await
+        {
+            self.listener
+                .info("Not installing packages of new remotes automatically");
+            return vec![];
+        }
+        self.resolved_config.package_urls.extend(auto_install_urls);
+        auto_installations
+    }
+
+    /// Asks the user whether all packages of the given remotes should be installed.
+    async fn confirm_auto_installations(
+        &self,
+        auto_installations: &[AutoInstallation],
+        interactions: &mut Receiver<bool>,
+    ) -> bool {
+        let mut message = "The following new remotes have auto-install enabled. Should ReaBoot install all of their packages now?\n".to_string();
+        for a in auto_installations.iter().filter(|a| a.package_count > 0) {
+            message += &format!("\n- {} ({} packages)", a.remote, a.package_count);
+        }
+        let confirmation_request = ConfirmationRequest {
+            message,
+            yes_label: "Install all packages".to_string(),
+            no_label: Some("Skip".to_string()),
+        };
+        self.listener.confirm(confirmation_request);
+        interactions.recv().await.unwrap_or(false)
+    }
+
+    /// Returns whether the given remote is added or enabled by this installation and has
+    /// auto-install enabled, in which case all of its packages should be installed (depending on
+    /// the auto-install mode).
+    ///
+    /// If the recipe registers the remote, its settings take precedence.
+    fn is_new_auto_install_remote(
+        &self,
+        existing_reapack_config: Option<&Config>,
+        name: &str,
+        url: &Url,
+    ) -> bool {
+        let config = &self.resolved_config;
+        let auto_install_enabled = matches!(
+            config.auto_install_mode,
+            AutoInstallMode::NewRemotes | AutoInstallMode::AskForNewRemotes
+        );
+        if !auto_install_enabled || config.lockfile.is_some() {
+            return false;
+        }
+        let existing_remote = existing_reapack_config.and_then(|c| {
+            c.remote_by_name
+                .values()
+                .find(|r| r.name == name || &r.url == url)
+        });
+        if let Some(r) = existing_remote {
+            // Existing remotes are only enabled by this installation if packages from them are
+            // requested
+            let enabled_by_installation = !r.enabled
+                && config
+                    .package_urls
+                    .iter()
+                    .any(|purl| purl.repository_url() == url);
+            if !enabled_by_installation {
+                return false;
+            }
+        }
+        let recipe_remote = self
+            .recipe_remotes()
+            .iter()
+            .find(|r| r.name == name || &r.url == url);
+        if recipe_remote.is_some_and(|r| r.enabled == Some(false)) {
+            return false;
+        }
+        let global_auto_install = config
+            .reapack_settings
+            .auto_install
+            .or(existing_reapack_config.and_then(|c| c.install.auto_install))
+            .unwrap_or(false);
+        recipe_remote
+            .and_then(|r| r.auto_install)
+            .unwrap_or(global_auto_install)
+    }
+
+    /// Loads the ReaPack config from the temporary directory, which reflects the state before
+    /// the installation.
+    fn load_temp_reapack_config(&self) -> anyhow::Result<Option<Config>> {
+        let reapack_ini_file = self.temp_reaper_resource_dir.reapack_ini_file();
+        if !reapack_ini_file.exists() {
+            return Ok(None);
+        }
+        let config = Config::load_from_ini_file(&reapack_ini_file)?;
+        Ok(Some(config))
+    }
+
     fn prepare_temp_dir(&self) -> anyhow::Result<()> {
         self.listener
             .installation_stage_changed(InstallationStage::PreparingTempDirectory);
@@ -376,7 +755,7 @@ impl<L: InstallerListener> Installer<L> {
         successful_downloads: Vec<DownloadWithPayload<QualifiedSource<'a>>>,
         installed_packages_to_be_replaced: &'a [InstalledPackage],
         installed_packages_to_be_removed: &'a [InstalledPackage],
-    ) -> anyhow::Result<TempInstallOutcome> {
+    ) -> anyhow::Result<TempInstallOutcome<'a>> {
         // Create/migrate ReaPack database
         let reapack_db_file = self.temp_reaper_resource_dir.reapack_registry_db_file();
         if reapack_db_file.exists() {
@@ -420,6 +799,7 @@ impl<L: InstallerListener> Installer<L> {
         config: &mut Config,
         downloaded_indexes: &HashMap<Url, DownloadedIndex>,
     ) {
+        let registrations = self.plan_recipe_remote_registrations(config, downloaded_indexes);
         for index in downloaded_indexes.values() {
             let remote = Remote {
                 name: index.name.to_string(),
@@ -429,6 +809,67 @@ impl<L: InstallerListener> Installer<L> {
             };
             config.add_remote(remote);
         }
+        // Recipe remotes are registered even if none of their packages is installed
+        for (recipe_remote, registration) in registrations {
+            let remote = config
+                .remote_by_name
+                .entry(registration.name.clone())
+                .or_insert_with(|| Remote {
+                    name: registration.name,
+                    url: registration.url,
+                    enabled: registration.enabled,
+                    auto_install: None,
+                });
+            remote.auto_install = recipe_remote.auto_install;
+        }
+        self.resolved_config.reapack_settings.apply_to(config);
+    }
+
+    /// Determines which remotes of the recipe are going to be registered in the given ReaPack
+    /// config.
+    ///
+    /// Remotes that exist already (same name or URL) are left untouched. A remote whose
+    /// repository index is downloaded anyway is registered with the name from the index.
+    fn plan_recipe_remote_registrations(
+        &self,
+        config: &Config,
+        downloaded_indexes: &HashMap<Url, DownloadedIndex>,
+    ) -> Vec<(&RecipeRemote, RemoteRegistration)> {
+        let mut registrations: Vec<(&RecipeRemote, RemoteRegistration)> = vec![];
+        for recipe_remote in self.recipe_remotes() {
+            let exists = config
+                .remote_by_name
+                .values()
+                .any(|r| r.name == recipe_remote.name || r.url == recipe_remote.url);
+            let planned = registrations
+                .iter()
+                .any(|(_, r)| r.name == recipe_remote.name || r.url == recipe_remote.url);
+            if exists || planned {
+                continue;
+            }
+            let registration = match downloaded_indexes.get(&recipe_remote.url) {
+                None => RemoteRegistration {
+                    name: recipe_remote.name.clone(),
+                    url: recipe_remote.url.clone(),
+                    enabled: recipe_remote.enabled.unwrap_or(true),
+                },
+                Some(index) => RemoteRegistration {
+                    name: index.name.clone(),
+                    url: index.url.clone(),
+                    enabled: true,
+                },
+            };
+            registrations.push((recipe_remote, registration));
+        }
+        registrations
+    }
+
+    fn recipe_remotes(&self) -> &[RecipeRemote] {
+        self.resolved_config
+            .recipe
+            .as_ref()
+            .and_then(|r| r.remotes.as_deref())
+            .unwrap_or_default()
     }
 
     async fn update_reapack_db<'a>(
@@ -436,7 +877,7 @@ impl<L: InstallerListener> Installer<L> {
         downloads: Vec<DownloadWithPayload<QualifiedSource<'a>>>,
         installed_packages_to_be_replaced: &'a [InstalledPackage],
         installed_packages_to_be_removed: &'a [InstalledPackage],
-    ) -> anyhow::Result<TempInstallOutcome> {
+    ) -> anyhow::Result<TempInstallOutcome<'a>> {
         // Make some transformations
         let mut replace_package_by_id: HashMap<_, _> = installed_packages_to_be_replaced
             .iter()
@@ -516,14 +957,7 @@ impl<L: InstallerListener> Installer<L> {
         p: &InstalledPackage,
     ) -> anyhow::Result<()> {
         // Dry remove installed package files
-        for file in &p.files {
-            let absolute_path = self
-                .resolved_config
-                .reaper_resource_dir
-                .get()
-                .join(&file.path);
-            dry_remove_file(&absolute_path)?;
-        }
+        dry_remove_package_files(&self.resolved_config.reaper_resource_dir, p)?;
         // Remove package from DB
         self.listener.info(format!("Remove package {p} from DB..."));
         transaction.remove_package(p.package_id()).await?;
@@ -564,6 +998,8 @@ impl<L: InstallerListener> Installer<L> {
             // are in the nice position to have a valid version name at this point.
             version: InstalledVersionName::Valid(first_version.version.name.clone()),
             author: first_version.version.author.clone().unwrap_or_default(),
+            // Keep flags such as "pinned" or "bleeding edge" of the package that we replace
+            flags: replace_package.map(|p| p.flags).unwrap_or_default(),
             files: downloads
                 .iter()
                 .map(|download| InstalledFile {
@@ -614,27 +1050,54 @@ impl<L: InstallerListener> Installer<L> {
         Ok(())
     }
 
+    /// Applies all changes to the REAPER resource directory, recording each of them in the
+    /// given journal.
+    fn apply_changes(
+        &self,
+        journal: &mut InstallationJournal,
+        downloaded_indexes: &HashMap<Url, DownloadedIndex>,
+        packages_to_be_removed: &[InstalledPackage],
+        plans: Vec<SinglePackageInstallationPlan<'_>>,
+    ) -> anyhow::Result<()> {
+        // Apply ReaPack state
+        // We do that *before* applying the packages. If something fails when
+        // copying/moving the package files, the real ReaPack can still install the
+        // packages via its synchronization feature.
+        self.apply_reapack_state(journal, downloaded_indexes)
+            .context("applying ReaPack state failed")?;
+        // Apply packages
+        self.install_packages(journal, packages_to_be_removed, plans)
+            .context("moving packages failed")?;
+        Ok(())
+    }
+
     fn apply_reapack_state(
         &self,
+        journal: &mut InstallationJournal,
         downloaded_indexes: &HashMap<Url, DownloadedIndex>,
     ) -> anyhow::Result<()> {
         tracing::debug!("Applying ReaPack state");
         self.listener
             .installation_stage_changed(InstallationStage::ApplyingReaPackState);
-        move_file_overwriting_with_backup(
-            self.temp_reaper_resource_dir.reapack_ini_file(),
-            self.resolved_config.reaper_resource_dir.reapack_ini_file(),
-            &self.resolved_config.backup_dir,
-        )
-        .context("moving ReaPack INI file failed")?;
-        move_file_overwriting_with_backup(
-            self.temp_reaper_resource_dir.reapack_registry_db_file(),
-            self.resolved_config
-                .reaper_resource_dir
-                .reapack_registry_db_file(),
-            &self.resolved_config.backup_dir,
-        )
-        .context("moving ReaPack registry DB file failed")?;
+        let dest_ini_file = self.resolved_config.reaper_resource_dir.reapack_ini_file();
+        journal
+            .move_file_overwriting_with_backup(
+                &self.temp_reaper_resource_dir.reapack_ini_file(),
+                &dest_ini_file,
+                journal.config_file_backup(&dest_ini_file)?,
+            )
+            .context("moving ReaPack INI file failed")?;
+        let dest_db_file = self
+            .resolved_config
+            .reaper_resource_dir
+            .reapack_registry_db_file();
+        journal
+            .move_file_overwriting_with_backup(
+                &self.temp_reaper_resource_dir.reapack_registry_db_file(),
+                &dest_db_file,
+                journal.config_file_backup(&dest_db_file)?,
+            )
+            .context("moving ReaPack registry DB file failed")?;
         let dest_cache_dir = self.resolved_config.reaper_resource_dir.reapack_cache_dir();
         // It can happen that we downloaded one repository index by 2 different URLs. This would
         // result in only one XML file on disk, but the hash map refers to it with 2 different
@@ -648,31 +1111,37 @@ impl<L: InstallerListener> Installer<L> {
                 .file_name()
                 .context("ReaPack index file should have a name at this point")?;
             let dest_index_file = dest_cache_dir.join(src_index_file_name);
-            move_file_overwriting_with_backup(
-                &downloaded_index_file,
-                dest_index_file,
-                &self.resolved_config.backup_dir,
-            )
-            .context("moving cached ReaPack repository index failed")?;
+            journal
+                .move_file_overwriting_with_backup(
+                    &downloaded_index_file,
+                    &dest_index_file,
+                    journal.config_file_backup(&dest_index_file)?,
+                )
+                .context("moving cached ReaPack repository index failed")?;
         }
         Ok(())
     }
 
     fn install_packages(
         &self,
+        journal: &mut InstallationJournal,
         packages_to_be_removed: &[InstalledPackage],
         plans: Vec<SinglePackageInstallationPlan>,
     ) -> anyhow::Result<()> {
         for p in packages_to_be_removed {
-            self.uninstall_package(p)?;
+            self.uninstall_package(journal, p)?;
         }
         for plan in plans {
-            self.install_package(plan)?;
+            self.install_package(journal, plan)?;
         }
         Ok(())
     }
 
-    fn install_package(&self, plan: SinglePackageInstallationPlan) -> anyhow::Result<()> {
+    fn install_package(
+        &self,
+        journal: &mut InstallationJournal,
+        plan: SinglePackageInstallationPlan<'_>,
+    ) -> anyhow::Result<()> {
         self.listener
             .installation_stage_changed(InstallationStage::InstallingPackage {
                 package: PackageInfo {
@@ -680,7 +1149,7 @@ impl<L: InstallerListener> Installer<L> {
                 },
             });
         if let Some(p) = plan.to_be_removed {
-            self.uninstall_package(p)?;
+            self.uninstall_package(journal, p)?;
         }
         // Copy/move
         self.listener.info(format!(
@@ -688,35 +1157,33 @@ impl<L: InstallerListener> Installer<L> {
             &plan.version.id()
         ));
         for download in plan.to_be_moved.into_iter() {
-            let src_file = download.download.file;
+            let relative_path = download.payload.relative_path;
             let dest_file = self
                 .resolved_config
                 .reaper_resource_dir
-                .get()
-                .join(download.payload.relative_path);
+                .join(&relative_path);
             // Overwriting the destination file is important! We take exclusive ownership
             // of any previously unmanaged file and overwrite it.
             // See https://github.com/helgoboss/reaboot/issues/2
-            move_file(&src_file, &dest_file, true)?;
+            journal.move_file_overwriting_with_backup(
+                &download.download.file,
+                &dest_file,
+                journal.file_backup(&relative_path),
+            )?;
         }
         Ok(())
     }
 
-    fn uninstall_package(&self, p: &InstalledPackage) -> Result<(), Error> {
+    fn uninstall_package(
+        &self,
+        journal: &mut InstallationJournal,
+        p: &InstalledPackage,
+    ) -> Result<(), Error> {
         // Remove files
         self.listener
             .info(format!("Deleting files of existing package {p}"));
-        for file in &p.files {
-            let path = self
-                .resolved_config
-                .reaper_resource_dir
-                .get()
-                .join(&file.path);
-            if path.try_exists().is_ok_and(|exists| !exists) {
-                continue;
-            }
-            fs::remove_file(path).context("couldn't remove file of package to be replaced")?;
-        }
+        remove_package_files(journal, &self.resolved_config.reaper_resource_dir, p)
+            .context("couldn't remove file of package to be replaced")?;
         Ok(())
     }
 
@@ -771,6 +1238,7 @@ impl<L: InstallerListener> Installer<L> {
         self.listener
             .installation_stage_changed(InstallationStage::CheckingLatestReaperVersion);
         let reaper_installer_asset = reaper_util::get_latest_reaper_installer_asset(
+            &self.http_client,
             self.resolved_config.platform,
             &self.resolved_config.reaper_version,
         )
@@ -865,19 +1333,16 @@ impl<L: InstallerListener> Installer<L> {
         }
     }
 
-    async fn download_repository_indexes(&self) -> anyhow::Result<HashMap<Url, DownloadedIndex>> {
+    async fn download_repository_indexes(
+        &self,
+        repository_urls: HashSet<Url>,
+    ) -> anyhow::Result<HashMap<Url, DownloadedIndex>> {
         let temp_cache_dir = self.temp_reaper_resource_dir.reapack_cache_dir();
-        let repository_urls: HashSet<_> = self
-            .resolved_config
-            .package_urls
-            .iter()
-            .map(|purl| purl.repository_url())
-            .collect();
         let downloads = repository_urls.into_iter().enumerate().map(|(i, url)| {
             DownloadWithPayload::new(
                 Download::new(
                     url.to_string(),
-                    url.clone(),
+                    url,
                     temp_cache_dir.join(i.to_string()),
                     None,
                 ),
@@ -937,24 +1402,50 @@ impl<L: InstallerListener> Installer<L> {
         &'a self,
         sources: Vec<QualifiedSource<'a>>,
     ) -> Vec<DownloadResult<QualifiedSource<'a>>> {
-        let downloads = sources.into_iter().map(|source| DownloadWithPayload {
-            download: Download {
-                label: source.simple_file_name().to_string(),
-                url: source.source.content.clone(),
-                file: self
-                    .temp_reaper_resource_dir
-                    .get()
-                    .join(&source.relative_path),
-                expected_multihash: source.source.hash.clone(),
-            },
-            payload: source,
-        });
+        let locked_sources = self
+            .resolved_config
+            .lockfile
+            .as_ref()
+            .map(|l| l.sources_by_relative_path());
+        let mut lock_errors = vec![];
+        let downloads: Vec<_> = sources
+            .into_iter()
+            .filter_map(|source| {
+                let mut download = Download {
+                    label: source.simple_file_name().to_string(),
+                    url: source.source.content.clone(),
+                    file: self
+                        .temp_reaper_resource_dir
+                        .get()
+                        .join(&source.relative_path),
+                    expected_multihash: source.source.hash.clone(),
+                };
+                if let Some(locked_sources) = &locked_sources {
+                    // In locked mode, the hash in the lockfile is the single source of truth
+                    let locked_source = locked_sources
+                        .get(source.relative_path.as_str())
+                        .filter(|s| s.url == download.url);
+                    let Some(locked_source) = locked_source else {
+                        lock_errors.push(DownloadError {
+                            download: DownloadWithPayload::new(download, source),
+                            error: anyhow!("File is not contained in the lockfile"),
+                        });
+                        return None;
+                    };
+                    download.expected_multihash = Some(locked_source.hash.clone());
+                }
+                Some(DownloadWithPayload::new(download, source))
+            })
+            .collect();
         let multi_download_listener = MultiDownloadListener::new(self, |info| {
             InstallationStage::DownloadingPackageFiles { download: info }
         });
-        self.multi_downloader
+        let mut results = self
+            .multi_downloader
             .download_multiple(downloads, multi_download_listener)
-            .await
+            .await;
+        results.extend(lock_errors.into_iter().map(Err));
+        results
     }
 
     fn copy_file_from_final_to_temp_dir_if_exists(
@@ -1012,6 +1503,16 @@ pub struct DownloadedIndex {
     pub index: Index,
 }
 
+/// Returns whether the given package URLs contain one that refers to the same package as the
+/// given package URL (regardless of the version).
+fn contains_same_package(package_urls: &[PackageUrl], package_url: &PackageUrl) -> bool {
+    package_urls.iter().any(|purl| {
+        purl.repository_url() == package_url.repository_url()
+            && purl.package_version_ref().package_path()
+                == package_url.package_version_ref().package_path()
+    })
+}
+
 fn weed_out_download_errors(
     package_download_results: Vec<DownloadResult<QualifiedSource>>,
 ) -> (
@@ -1088,18 +1589,6 @@ struct PackageStatusQuo {
     installed_packages_to_keep: Vec<InstalledPackage>,
 }
 
-fn dry_remove_file(path: &Path) -> anyhow::Result<()> {
-    if path.exists() && !existing_file_or_dir_is_writable(path) {
-        let suffix = if cfg!(windows) {
-            " Is REAPER running already? If yes, exit REAPER and try again."
-        } else {
-            ""
-        };
-        bail!("Removing the file would not work.{suffix}");
-    }
-    Ok(())
-}
-
 fn dry_move_file(src: &Path, dest: PathBuf) -> anyhow::Result<()> {
     ensure!(src.exists(), "Source file {src:?} doesn't exist");
     // It's okay if the destination file exists already, not managed by ReaPack! See
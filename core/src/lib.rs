@@ -1,17 +1,32 @@
 pub mod api;
+mod bandwidth_limiter;
+pub mod bundle;
+pub mod credentials;
 mod display_util;
+pub mod download_cache;
 pub mod downloader;
 mod file_util;
 pub mod hash_util;
+pub mod http_client;
+pub mod installation_journal;
 pub mod installation_model;
+pub mod installed_packages;
 pub mod installer;
+pub mod lockfile;
 pub mod multi_downloader;
+pub mod package_details;
 mod preparation_report;
 pub mod reaboot_util;
 pub mod reaper_platform;
 pub mod reaper_resource_dir;
 pub mod reaper_util;
 pub mod recipe;
+pub mod recipe_check;
+pub mod remotes;
+pub mod restorer;
+mod retry_middleware;
+pub mod search;
 pub mod task_tracker;
+pub mod uninstaller;
 
 pub use preparation_report::*;
@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use url::Url;
+
+use reaboot_reapack::model::{PackagePath, PackageUrl, PackageVersionRef, VersionName, VersionRef};
+
+use crate::hash_util::build_sha256_source_hash;
+use crate::installation_model::QualifiedSource;
+use crate::multi_downloader::DownloadWithPayload;
+use crate::reaper_platform::ReaperPlatform;
+
+/// This is the currently supported lockfile format version.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// A lockfile pins each package to an exact version and each package file to an exact download
+/// URL and hash.
+///
+/// Installing from a lockfile makes sure that each machine ends up with byte-identical files.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Lockfile {
+    /// Lockfile format version.
+    pub version: u32,
+    /// Platform for which the packages have been resolved.
+    ///
+    /// Sources are platform-specific, so a lockfile can only be used for installations
+    /// targeting the same platform.
+    pub platform: ReaperPlatform,
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A package pinned to an exact version.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LockedPackage {
+    pub repository_url: Url,
+    pub category: String,
+    pub package_name: String,
+    pub version: VersionName,
+    pub sources: Vec<LockedSource>,
+}
+
+/// A package file pinned to an exact download URL and hash.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LockedSource {
+    /// Destination path relative to the REAPER resource directory.
+    pub relative_path: String,
+    pub url: Url,
+    /// Hex-encoded multihash of the file contents, in the same format as used in ReaPack
+    /// indexes.
+    pub hash: String,
+}
+
+impl Lockfile {
+    pub fn load_from_file(path: &Path) -> anyhow::Result<Self> {
+        let json = fs::read_to_string(path).context("couldn't read lockfile")?;
+        let lockfile: Self = serde_json::from_str(&json).context("couldn't parse lockfile")?;
+        ensure!(
+            lockfile.version <= LOCKFILE_VERSION,
+            "Lockfile version {} is not supported by this ReaBoot version. Please download the latest ReaBoot version!",
+            lockfile.version
+        );
+        Ok(lockfile)
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).context("couldn't write lockfile")?;
+        Ok(())
+    }
+
+    /// Creates a lockfile from the given successful package downloads.
+    ///
+    /// Sources that don't come with a hash in the repository index will be hashed from the
+    /// downloaded file.
+    pub(crate) fn from_downloads(
+        platform: ReaperPlatform,
+        downloads: &[DownloadWithPayload<QualifiedSource>],
+    ) -> anyhow::Result<Self> {
+        let mut packages: BTreeMap<(String, String, String), LockedPackage> = BTreeMap::new();
+        for download in downloads {
+            let version = download.payload.version;
+            let repository_url = &version.package.index.url;
+            let hash = match &download.payload.source.hash {
+                None => {
+                    let bytes = fs::read(&download.download.file)
+                        .context("couldn't read downloaded file for hashing")?;
+                    build_sha256_source_hash(bytes)
+                }
+                Some(h) => h.clone(),
+            };
+            let key = (
+                repository_url.to_string(),
+                version.package.category.name.clone(),
+                version.package.package.name.clone(),
+            );
+            let package = packages.entry(key).or_insert_with(|| LockedPackage {
+                repository_url: repository_url.clone(),
+                category: version.package.category.name.clone(),
+                package_name: version.package.package.name.clone(),
+                version: version.version.name.clone(),
+                sources: vec![],
+            });
+            package.sources.push(LockedSource {
+                relative_path: download.payload.relative_path.clone(),
+                url: download.download.url.clone(),
+                hash,
+            });
+        }
+        let mut packages: Vec<_> = packages.into_values().collect();
+        for p in &mut packages {
+            p.sources
+                .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        }
+        let lockfile = Self {
+            version: LOCKFILE_VERSION,
+            platform,
+            packages,
+        };
+        Ok(lockfile)
+    }
+
+    /// Pins each of the given package URLs to the locked version of its package.
+    ///
+    /// Fails if one of the given package URLs refers to a package that is not contained in the
+    /// lockfile or to a specific version that doesn't match the locked version. That also
+    /// means that `latest` and `latest-pre` are never resolved in locked mode. Locked packages
+    /// that are not referred to are left out.
+    pub fn lock_package_urls<'a>(
+        &self,
+        package_urls: impl IntoIterator<Item = &'a PackageUrl>,
+    ) -> anyhow::Result<Vec<PackageUrl>> {
+        package_urls
+            .into_iter()
+            .map(|purl| {
+            let Some(locked) = self.find_package(purl.repository_url(), purl.package_path()) else {
+                bail!(
+                    "Package {} from repository {} is not contained in the lockfile. Refusing to resolve version \"{}\" in locked mode.",
+                    purl.package_name(),
+                    purl.repository_url(),
+                    purl.version_ref()
+                );
+            };
+            if let VersionRef::Specific(v) = purl.version_ref() {
+                ensure!(
+                    v == &locked.version,
+                    "Package {} is requested in version {v} but locked to version {}",
+                    purl.package_name(),
+                    &locked.version
+                );
+            }
+            Ok(locked.package_url())
+        })
+        .collect()
+    }
+
+    pub fn find_package(
+        &self,
+        repository_url: &Url,
+        package_path: &PackagePath,
+    ) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| {
+            &p.repository_url == repository_url
+                && p.category == package_path.category
+                && p.package_name == package_path.package_name
+        })
+    }
+
+    /// Returns all locked sources, keyed by their destination path relative to the REAPER
+    /// resource directory.
+    pub fn sources_by_relative_path(&self) -> HashMap<&str, &LockedSource> {
+        self.packages
+            .iter()
+            .flat_map(|p| &p.sources)
+            .map(|s| (s.relative_path.as_str(), s))
+            .collect()
+    }
+}
+
+impl LockedPackage {
+    /// Returns a package URL pointing to exactly the locked version.
+    pub fn package_url(&self) -> PackageUrl {
+        PackageUrl {
+            repository_url: self.repository_url.clone(),
+            package_version_ref: PackageVersionRef {
+                package_path: PackagePath {
+                    category: self.category.clone(),
+                    package_name: self.package_name.clone(),
+                },
+                version_ref: VersionRef::Specific(self.version.clone()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_package_urls() {
+        let lockfile = Lockfile {
+            version: LOCKFILE_VERSION,
+            platform: ReaperPlatform::LinuxX86_64,
+            packages: vec![
+                LockedPackage {
+                    repository_url: Url::parse("https://example.com/index.xml").unwrap(),
+                    category: "Example".to_string(),
+                    package_name: "Hello World.lua".to_string(),
+                    version: "1.0.1".parse().unwrap(),
+                    sources: vec![],
+                },
+                LockedPackage {
+                    repository_url: Url::parse("https://example.com/index.xml").unwrap(),
+                    category: "Example".to_string(),
+                    package_name: "Unrequested.lua".to_string(),
+                    version: "2.0".parse().unwrap(),
+                    sources: vec![],
+                },
+            ],
+        };
+        let parse = |url: &str| PackageUrl::parse(url).unwrap();
+        // Latest resolves to the locked version and unrequested packages are left out
+        let locked = lockfile
+            .lock_package_urls(&[parse(
+                "https://example.com/index.xml#p=Example/Hello%20World.lua&v=latest",
+            )])
+            .unwrap();
+        assert_eq!(
+            locked,
+            vec![parse(
+                "https://example.com/index.xml#p=Example/Hello%20World.lua&v=1.0.1"
+            )]
+        );
+        // Specific version mismatch
+        assert!(lockfile
+            .lock_package_urls(&[parse(
+                "https://example.com/index.xml#p=Example/Hello%20World.lua&v=1.0"
+            )])
+            .is_err());
+        // Package not contained in lockfile
+        assert!(lockfile
+            .lock_package_urls(&[parse(
+                "https://example.com/index.xml#p=Example/Other.lua&v=latest"
+            )])
+            .is_err());
+    }
+}
@@ -1,11 +1,20 @@
+use crate::bundle::BundleRecorder;
+use crate::download_cache::DownloadCache;
 use crate::downloader::{Download, Downloader};
 
 use crate::task_tracker::{track_tasks, TaskTrackerListener};
-use futures::{stream, StreamExt};
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 pub struct MultiDownloader {
     downloader: Downloader,
     concurrent_downloads: u32,
+    /// If set, limits the number of concurrent downloads from the same host.
+    concurrent_downloads_per_host: Option<u32>,
+    host_semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+    cache: Option<DownloadCache>,
 }
 
 #[derive(Clone)]
@@ -28,30 +37,65 @@ pub struct DownloadError<P> {
 pub type DownloadResult<P> = Result<DownloadWithPayload<P>, DownloadError<P>>;
 
 impl MultiDownloader {
-    pub fn new(downloader: Downloader, concurrent_downloads: u32) -> Self {
+    pub fn new(
+        downloader: Downloader,
+        concurrent_downloads: u32,
+        concurrent_downloads_per_host: Option<u32>,
+        cache: Option<DownloadCache>,
+    ) -> Self {
         Self {
             downloader,
             concurrent_downloads,
+            concurrent_downloads_per_host,
+            host_semaphores: Default::default(),
+            cache,
         }
     }
 
+    /// Makes this multi-downloader record each successful download in the given recorder.
+    pub(crate) fn record_downloads(&mut self, recorder: Arc<BundleRecorder>) {
+        self.downloader = self.downloader.clone().with_recorder(recorder);
+    }
+
     pub async fn download_multiple<P>(
         &self,
         downloads: impl IntoIterator<Item = DownloadWithPayload<P>>,
         listener: impl TaskTrackerListener<Payload = DownloadWithPayload<P>>,
     ) -> Vec<DownloadResult<P>> {
         let tasks = track_tasks(downloads, listener);
-        let download_results: Vec<_> = stream::iter(tasks)
-            .enumerate()
-            .map(|(_i, task)| async move {
+        // Not using `buffer_unordered` here because the global limit must only be applied after
+        // acquiring the host permit. Otherwise, downloads waiting for a busy host would occupy
+        // global slots and block downloads from other hosts.
+        let global_semaphore = Semaphore::new(self.concurrent_downloads.max(1) as usize);
+        let futures = tasks.into_iter().map(|task| {
+            let global_semaphore = &global_semaphore;
+            async move {
+                let download = &task.payload.download;
+                if self.get_from_cache(download) {
+                    task.start();
+                    if let Err(e) = self.downloader.record(download) {
+                        task.fail();
+                        return Err(DownloadError {
+                            download: task.payload,
+                            error: e,
+                        });
+                    }
+                    task.finish();
+                    return Ok(task.payload);
+                }
+                let _host_permit = self.acquire_host_permit(download).await;
+                let _global_permit = global_semaphore.acquire().await.ok();
                 task.start();
                 let download_result = self
                     .downloader
-                    .download(task.payload.download.clone(), |progress| {
+                    .download(download.clone(), |progress| {
                         let p = progress.to_simple_progress();
                         task.set_progress(p);
                     })
                     .await;
+                if download_result.is_ok() {
+                    self.put_into_cache(download);
+                }
                 match download_result {
                     Ok(_) => {
                         task.finish();
@@ -66,10 +110,57 @@ impl MultiDownloader {
                         Err(download_error)
                     }
                 }
-            })
-            .buffer_unordered(self.concurrent_downloads as usize)
-            .collect()
-            .await;
+            }
+        });
+        let download_results = join_all(futures).await;
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.evict() {
+                tracing::warn!(msg = "Couldn't evict files from download cache", %e);
+            }
+        }
         download_results
     }
+
+    /// Waits until the per-host limit allows another download from the host of the given
+    /// download.
+    ///
+    /// Returns `None` if there's no per-host limit.
+    async fn acquire_host_permit(&self, download: &Download) -> Option<OwnedSemaphorePermit> {
+        let limit = self.concurrent_downloads_per_host?;
+        let host = download.url.host_str().unwrap_or_default().to_string();
+        let semaphore = self
+            .host_semaphores
+            .lock()
+            .unwrap()
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1) as usize)))
+            .clone();
+        semaphore.acquire_owned().await.ok()
+    }
+
+    /// Attempts to satisfy the download from the cache.
+    ///
+    /// Only downloads with an expected hash are eligible.
+    fn get_from_cache(&self, download: &Download) -> bool {
+        let (Some(cache), Some(hash)) = (&self.cache, &download.expected_multihash) else {
+            return false;
+        };
+        match cache.get(hash, &download.file) {
+            Ok(hit) => hit,
+            Err(e) => {
+                tracing::warn!(msg = "Couldn't get file from download cache", %e);
+                false
+            }
+        }
+    }
+
+    /// Adds the successfully downloaded (and therefore verified) file to the cache.
+    fn put_into_cache(&self, download: &Download) {
+        let (Some(cache), Some(hash)) = (&self.cache, &download.expected_multihash) else {
+            return;
+        };
+        if let Err(e) = cache.put(hash, &download.file) {
+            tracing::warn!(msg = "Couldn't put file into download cache", %e);
+        }
+    }
 }
@@ -0,0 +1,199 @@
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::Context;
+
+use reaboot_reapack::index::{Index, IndexPackageType};
+use reaboot_reapack::model::PackageUrl;
+
+use crate::api::{PackageDetails, PackageFileInfo, PackageLinkInfo, PackageVersionInfo};
+use crate::downloader::{Download, Downloader};
+use crate::installation_model::{
+    lookup_package_version_in_index,
+    resolve_package_sources_weeding_out_platform_incompatible_versions, PackageDescError,
+};
+use crate::installer::DownloadedIndex;
+use crate::reaper_platform::ReaperPlatform;
+
+/// Downloads the repository index of the given package URL into `temp_dir` and returns
+/// everything it knows about the referenced package.
+///
+/// The files are determined for the version that the package URL refers to, as they would be
+/// installed on the given platform.
+pub async fn get_package_details(
+    package_url: &PackageUrl,
+    downloader: &Downloader,
+    temp_dir: &Path,
+    platform: ReaperPlatform,
+) -> anyhow::Result<PackageDetails> {
+    let url = package_url.repository_url();
+    let file = temp_dir.join("index.xml");
+    let download = Download::new(url.to_string(), url.clone(), file.clone(), None);
+    downloader
+        .download(download, |_| {})
+        .await
+        .with_context(|| format!("couldn't download repository index {url}"))?;
+    let index = Index::parse(BufReader::new(fs::File::open(&file)?))
+        .with_context(|| format!("couldn't parse repository index {url}"))?;
+    let downloaded_index = DownloadedIndex {
+        url: url.clone(),
+        temp_download_file: file,
+        name: index.name.clone().unwrap_or_default(),
+        index,
+    };
+    let info = describe_package(package_url, &downloaded_index, platform)
+        .with_context(|| format!("couldn't resolve package in repository index {url}"))?;
+    Ok(info)
+}
+
+fn describe_package(
+    package_url: &PackageUrl,
+    index: &DownloadedIndex,
+    platform: ReaperPlatform,
+) -> Result<PackageDetails, PackageDescError> {
+    let selected = lookup_package_version_in_index(package_url, index)?;
+    let package = selected.package.package;
+    let metadata = package.metadata();
+    let links = metadata
+        .into_iter()
+        .flat_map(|m| m.links())
+        .map(|l| PackageLinkInfo {
+            rel: l.rel.to_string(),
+            url: l.url().to_string(),
+            name: l.display_name().map(|n| n.to_string()),
+        })
+        .collect();
+    let mut versions: Vec<_> = package.all_versions().collect();
+    versions.sort_by(|a, b| b.name.cmp(&a.name));
+    let versions = versions
+        .into_iter()
+        .map(|v| PackageVersionInfo {
+            name: v.name.to_string(),
+            pre_release: !v.name.is_stable(),
+            author: v.author.clone(),
+            time: v.time.and_then(|t| {
+                let timestamp = jiff::Timestamp::from_second(t.unix_timestamp()).ok()?;
+                Some(timestamp.to_string())
+            }),
+            changelog: v.changelog().map(|c| c.content.clone()),
+        })
+        .collect();
+    let typ = match &package.typ {
+        IndexPackageType::Known(t) => t.to_string(),
+        IndexPackageType::Unknown(t) => t.clone(),
+    };
+    let info = PackageDetails {
+        repository: index.name.clone(),
+        category: selected.package.category.name.clone(),
+        package: package.name.clone(),
+        description: package.desc.clone(),
+        typ,
+        about: metadata
+            .and_then(|m| m.description())
+            .map(|d| d.to_string()),
+        links,
+        versions,
+        selected_version: selected.version.name.to_string(),
+        platform,
+        files: vec![],
+    };
+    let (sources, _) = resolve_package_sources_weeding_out_platform_incompatible_versions(
+        vec![selected],
+        platform,
+    );
+    let files = sources
+        .into_iter()
+        .map(|s| PackageFileInfo {
+            relative_path: s.relative_path,
+            url: s.source.content.to_string(),
+            typ: s.typ.map(|t| t.to_string()),
+        })
+        .collect();
+    Ok(PackageDetails { files, ..info })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_package_for_platform() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<index version="1" name="Helgoboss">
+  <category name="Extensions">
+    <package name="Helgobox" type="extension" desc="Helgobox">
+      <version name="1.0" author="helgoboss" time="2024-03-01T10:00:00Z">
+        <changelog><![CDATA[Initial release]]></changelog>
+        <source platform="win64" file="reaper_helgobox-x64.dll">https://example.org/1.0/win.dll</source>
+        <source platform="linux64" file="reaper_helgobox-x86_64.so">https://example.org/1.0/linux.so</source>
+        <source platform="all" type="data" file="Helgobox/presets.json">https://example.org/1.0/presets.json</source>
+      </version>
+      <version name="1.1-pre1" author="helgoboss">
+        <source platform="win64" file="reaper_helgobox-x64.dll">https://example.org/1.1/win.dll</source>
+      </version>
+      <metadata>
+        <description><![CDATA[Swiss army knife]]></description>
+        <link rel="website">https://www.helgoboss.org/</link>
+        <link rel="donation" href="https://paypal.me/helgoboss">Donate</link>
+        <link rel="screenshot">https://example.org/screenshot.png</link>
+      </metadata>
+    </package>
+  </category>
+</index>"#;
+        let index = DownloadedIndex {
+            url: "https://example.org/index.xml".parse().unwrap(),
+            temp_download_file: Default::default(),
+            name: "Helgoboss".to_string(),
+            index: Index::parse(xml.as_bytes()).unwrap(),
+        };
+        let package_url =
+            PackageUrl::parse("https://example.org/index.xml#p=Extensions/Helgobox").unwrap();
+        let info = describe_package(&package_url, &index, ReaperPlatform::LinuxX86_64).unwrap();
+        assert_eq!(info.selected_version, "1.0");
+        assert_eq!(info.about.as_deref(), Some("Swiss army knife"));
+        let versions: Vec<_> = info.versions.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(versions, ["1.1-pre1", "1.0"]);
+        assert!(info.versions[0].pre_release);
+        assert_eq!(
+            info.versions[1].time.as_deref(),
+            Some("2024-03-01T10:00:00Z")
+        );
+        assert_eq!(
+            info.versions[1].changelog.as_deref(),
+            Some("Initial release")
+        );
+        let links: Vec<_> = info
+            .links
+            .iter()
+            .map(|l| (l.rel.as_str(), l.url.as_str(), l.name.as_deref()))
+            .collect();
+        assert_eq!(
+            links,
+            [
+                ("website", "https://www.helgoboss.org/", None),
+                ("donation", "https://paypal.me/helgoboss", Some("Donate")),
+                ("screenshot", "https://example.org/screenshot.png", None),
+            ]
+        );
+        let files: Vec<_> = info
+            .files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f.typ.as_deref()))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("UserPlugins/reaper_helgobox-x86_64.so", None),
+                ("Data/Helgobox/presets.json", Some("data")),
+            ]
+        );
+        // Selected version not available for platform
+        let package_url =
+            PackageUrl::parse("https://example.org/index.xml#p=Extensions/Helgobox&v=latest-pre")
+                .unwrap();
+        let info = describe_package(&package_url, &index, ReaperPlatform::LinuxX86_64).unwrap();
+        assert_eq!(info.selected_version, "1.1-pre1");
+        assert!(info.files.is_empty());
+    }
+}
@@ -1,15 +1,25 @@
 use std::fmt::{Display, Formatter, Write};
 
 use reaboot_reapack::model::{
-    InstalledPackage, LightPackageId, PackageId, VersionName, VersionRef,
+    Config, InstalledPackage, LightPackageId, PackageId, VersionName, VersionRef,
 };
 
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+use url::Url;
+
+use crate::api::{
+    AutoInstallationInfo, PackagePrepStatusInfo, PackagePreparationInfo, PreparationReportInfo,
+    ReaPackSettingChangeInfo, ReaPackSettings, RemoteRegistrationInfo, ToolingChangeInfo,
+};
 use crate::display_util::Separated;
 use crate::downloader::Download;
 use crate::installation_model::{
     PackageDescError, PreDownloadFailures, QualifiedSource, SinglePackageInstallationPlan,
     TempInstallFailure,
 };
+use crate::installed_packages::installed_package_info;
 use crate::multi_downloader::DownloadError;
 
 #[derive(Debug)]
@@ -18,6 +28,77 @@ pub struct PreparationReport {
     pub tooling_changes: Vec<ToolingChange>,
     /// This contains only removals without replacements
     pub package_removals: Vec<InstalledPackage>,
+    /// Remotes newly registered in `reapack.ini` because the recipe asks for it.
+    ///
+    /// Doesn't contain remotes that existed already, because they are left untouched.
+    pub remote_registrations: Vec<RemoteRegistration>,
+    /// Remotes whose packages are installed automatically (see [`AutoInstallMode`]).
+    ///
+    /// [`AutoInstallMode`]: crate::api::AutoInstallMode
+    pub auto_installations: Vec<AutoInstallation>,
+    /// Settings in `reapack.ini` that are changed by this installation.
+    pub reapack_setting_changes: Vec<ReaPackSettingChange>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AutoInstallation {
+    pub remote: String,
+    pub url: Url,
+    pub package_count: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct RemoteRegistration {
+    pub name: String,
+    pub url: Url,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReaPackSettingChange {
+    pub name: &'static str,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl ReaPackSettingChange {
+    /// Determines which settings change when applying the given settings to the given
+    /// ReaPack config.
+    pub fn determine(settings: &ReaPackSettings, config: &Config) -> Vec<Self> {
+        let mut new_config = config.clone();
+        settings.apply_to(&mut new_config);
+        reapack_setting_values(config)
+            .into_iter()
+            .zip(reapack_setting_values(&new_config))
+            .filter(|((_, old_value), (_, new_value))| old_value != new_value)
+            .map(|((name, old_value), (_, new_value))| Self {
+                name,
+                old_value,
+                new_value,
+            })
+            .collect()
+    }
+}
+
+/// Returns the values of all settings that can be changed via [`ReaPackSettings`].
+fn reapack_setting_values(config: &Config) -> [(&'static str, Option<String>); 7] {
+    let install = &config.install;
+    let network = &config.network;
+    [
+        ("auto_install", install.auto_install.map(|v| v.to_string())),
+        ("pre_releases", install.pre_releases.map(|v| v.to_string())),
+        (
+            "prompt_obsolete",
+            install.prompt_obsolete.map(|v| v.to_string()),
+        ),
+        ("proxy", network.proxy.clone()),
+        ("verify_peer", network.verify_peer.map(|v| v.to_string())),
+        (
+            "stale_threshold",
+            network.stale_threshold.map(|v| v.to_string()),
+        ),
+        ("network_timeout", network.timeout.map(|v| v.to_string())),
+    ]
 }
 
 #[derive(Clone, Debug)]
@@ -214,6 +295,73 @@ impl PreparationReport {
             package_preparation_outcomes,
             tooling_changes,
             package_removals: packages_to_be_removed.to_vec(),
+            remote_registrations: vec![],
+            auto_installations: vec![],
+            reapack_setting_changes: vec![],
+        }
+    }
+
+    /// Converts this report into a serializable representation.
+    pub fn to_info(&self) -> PreparationReportInfo {
+        let summary = self.summary();
+        PreparationReportInfo {
+            failure_count: summary.failures as u32,
+            replacement_count: summary.replacements as u32,
+            addition_count: summary.additions as u32,
+            packages: self
+                .package_preparation_outcomes
+                .iter()
+                .map(|o| PackagePreparationInfo {
+                    remote: o.package_id.remote.clone(),
+                    category: o.package_id.category.clone(),
+                    package: o.package_id.package.clone(),
+                    version: o.version.as_ref().map(|v| v.to_string()),
+                    status_category: o.status.category(),
+                    status: o.status.to_info(),
+                    message: o.status.to_string(),
+                    donation_url: o.donation_url.clone(),
+                })
+                .collect(),
+            tooling_changes: self
+                .tooling_changes
+                .iter()
+                .map(|c| ToolingChangeInfo {
+                    name: c.name.clone(),
+                    version: c.download.version.clone(),
+                })
+                .collect(),
+            package_removals: self
+                .package_removals
+                .iter()
+                .map(installed_package_info)
+                .collect(),
+            remote_registrations: self
+                .remote_registrations
+                .iter()
+                .map(|r| RemoteRegistrationInfo {
+                    name: r.name.clone(),
+                    url: r.url.to_string(),
+                    enabled: r.enabled,
+                })
+                .collect(),
+            auto_installations: self
+                .auto_installations
+                .iter()
+                .map(|a| AutoInstallationInfo {
+                    remote: a.remote.clone(),
+                    url: a.url.to_string(),
+                    package_count: a.package_count as u32,
+                })
+                .collect(),
+            reapack_setting_changes: self
+                .reapack_setting_changes
+                .iter()
+                .map(|c| ReaPackSettingChangeInfo {
+                    name: c.name.to_string(),
+                    old_value: c.old_value.clone(),
+                    new_value: c.new_value.clone(),
+                })
+                .collect(),
         }
     }
 
@@ -244,7 +392,8 @@ pub struct PreparationReportSummary {
     pub additions: usize,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, TS, JsonSchema)]
+#[ts(export)]
 pub enum PackageStatusCategory {
     Failure,
     Replacement,
@@ -252,6 +401,45 @@ pub enum PackageStatusCategory {
 }
 
 impl PackagePrepStatus {
+    /// Converts this status into a serializable representation.
+    pub fn to_info(&self) -> PackagePrepStatusInfo {
+        match self {
+            PackagePrepStatus::NotFoundInRepo(e) => {
+                PackagePrepStatusInfo::NotFoundInRepo { error: *e }
+            }
+            PackagePrepStatus::VersionConflict(versions) => {
+                PackagePrepStatusInfo::VersionConflict {
+                    versions: versions.iter().map(|v| v.to_string()).collect(),
+                }
+            }
+            PackagePrepStatus::Incompatible => PackagePrepStatusInfo::Incompatible,
+            PackagePrepStatus::ConflictWithOtherPackagesToBeInstalled { relative_path } => {
+                PackagePrepStatusInfo::ConflictWithOtherPackagesToBeInstalled {
+                    relative_path: relative_path.clone(),
+                }
+            }
+            PackagePrepStatus::ConflictWithAlreadyInstalledFiles {
+                relative_path,
+                installed_package_id,
+            } => PackagePrepStatusInfo::ConflictWithAlreadyInstalledFiles {
+                relative_path: relative_path.clone(),
+                installed_package_id: installed_package_id.to_string(),
+            },
+            PackagePrepStatus::DownloadFailed(e) => PackagePrepStatusInfo::DownloadFailed {
+                message: format!("{e:#}"),
+            },
+            PackagePrepStatus::TempInstallFailed(e) => PackagePrepStatusInfo::TempInstallFailed {
+                message: format!("{e:#}"),
+            },
+            PackagePrepStatus::ToBeAdded => PackagePrepStatusInfo::ToBeAdded,
+            PackagePrepStatus::ToBeReplaced { old_version } => {
+                PackagePrepStatusInfo::ToBeReplaced {
+                    old_version: old_version.clone(),
+                }
+            }
+        }
+    }
+
     pub fn category(&self) -> PackageStatusCategory {
         use PackageStatusCategory::*;
         match self {
@@ -442,6 +630,50 @@ impl<'a> Display for PreparationReportAsMarkdown<'a> {
                 writeln!(f, "- {version_markdown}")?;
             }
         }
+        if !self.report.remote_registrations.is_empty() {
+            heading_count += 1;
+            self.write_heading(
+                f,
+                "remote registration",
+                self.report.remote_registrations.len(),
+                skipped_suffix,
+            )?;
+            for r in &self.report.remote_registrations {
+                let disabled_suffix = if r.enabled { "" } else { " (disabled)" };
+                writeln!(f, "- **{}**: {}{disabled_suffix}", r.name, r.url)?;
+            }
+        }
+        if !self.report.auto_installations.is_empty() {
+            heading_count += 1;
+            self.write_heading(
+                f,
+                "remote auto-installation",
+                self.report.auto_installations.len(),
+                skipped_suffix,
+            )?;
+            for a in &self.report.auto_installations {
+                let plural_suffix = if a.package_count == 1 { "" } else { "s" };
+                writeln!(
+                    f,
+                    "- **{}**: {} ({} package{plural_suffix})",
+                    a.remote, a.url, a.package_count
+                )?;
+            }
+        }
+        if !self.report.reapack_setting_changes.is_empty() {
+            heading_count += 1;
+            self.write_heading(
+                f,
+                "ReaPack setting change",
+                self.report.reapack_setting_changes.len(),
+                skipped_suffix,
+            )?;
+            for c in &self.report.reapack_setting_changes {
+                let old_value = c.old_value.as_deref().unwrap_or("default");
+                let new_value = c.new_value.as_deref().unwrap_or("default");
+                writeln!(f, "- **{}**: {old_value} → {new_value}", c.name)?;
+            }
+        }
         if summary.replacements > 0 {
             heading_count += 1;
             self.write_heading(
@@ -525,3 +757,45 @@ fn write_name_and_version(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+    use std::fs;
+
+    #[test]
+    fn determine_reapack_setting_changes() {
+        let mut config = Config::default();
+        config.install.pre_releases = Some(true);
+        config.network.verify_peer = Some(true);
+        let settings = ReaPackSettings {
+            pre_releases: Some(true),
+            verify_peer: Some(false),
+            proxy: Some("proxy.example.org:8080".to_string()),
+            ..Default::default()
+        };
+        let changes: Vec<_> = ReaPackSettingChange::determine(&settings, &config)
+            .into_iter()
+            .map(|c| (c.name, c.old_value, c.new_value))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("proxy", None, Some("proxy.example.org:8080".to_string())),
+                (
+                    "verify_peer",
+                    Some("true".to_string()),
+                    Some("false".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_json_schema() {
+        let schema = schema_for!(PreparationReportInfo);
+        let text = serde_json::to_string_pretty(&schema).unwrap();
+        fs::write("bindings/preparation-report.schema.json", text).unwrap();
+    }
+}
@@ -1,20 +1,29 @@
 use std::collections::HashSet;
 use std::env;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use anyhow::{bail, Context};
+use anyhow::{bail, ensure, Context};
 use url::Url;
 
 use reaboot_reapack::database::{CompatibilityInfo, Database};
 use reaboot_reapack::model::{
-    PackagePath, PackageUrl, PackageVersionRef, ParsePackageUrlError, VersionRef,
+    Config, PackagePath, PackageUrl, PackageVersionRef, ParsePackageUrlError, VersionName,
+    VersionRef,
 };
 
-use crate::api::{InstallationStage, InstallerConfig, ReabootBackendInfo, ResolvedInstallerConfig};
+use crate::api::{
+    HttpConfig, InstallationStage, InstallerConfig, ReabootBackendInfo, ResolvedInstallerConfig,
+};
+use crate::credentials::CredentialStore;
+use crate::download_cache::{DownloadCache, DEFAULT_MAX_DOWNLOAD_CACHE_SIZE};
+use crate::downloader::Downloader;
 use crate::file_util::file_or_dir_is_writable_or_creatable;
+use crate::http_client::{create_http_client, HttpClient};
 use crate::reaper_platform::ReaperPlatform;
 use crate::reaper_resource_dir::ReaperResourceDir;
 use crate::reaper_util;
+use crate::recipe::RecipeEnvironment;
 
 pub fn collect_backend_info() -> ReabootBackendInfo {
     let (main_reaper_resource_dir, main_reaper_ini_exists) =
@@ -40,16 +49,80 @@ pub fn collect_backend_info() -> ReabootBackendInfo {
 const DEFAULT_CONCURRENT_DOWNLOADS: u32 = 6;
 const DEFAULT_NUM_DOWNLOAD_RETRIES: u32 = 3;
 
+/// Creates an HTTP client according to the HTTP settings of the given config.
+///
+/// Useful for requests that happen outside of an installation, e.g. fetching the REAPER EULA.
+pub fn create_http_client_for_config(config: &InstallerConfig) -> anyhow::Result<HttpClient> {
+    let retries = config
+        .num_download_retries
+        .unwrap_or(DEFAULT_NUM_DOWNLOAD_RETRIES);
+    create_http_client(&config.http.clone().unwrap_or_default(), retries)
+}
+
+/// Reason for resolving an installer config.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ResolvePurpose {
+    /// Packages are going to be installed into the resolved REAPER resource directory.
+    Install,
+    /// Packages are just resolved and downloaded in order to create a lockfile or bundle.
+    ///
+    /// The REAPER installation that will eventually receive the packages is not known, so
+    /// recipe package conditions are only checked for the platform.
+    Lock,
+}
+
+/// Creates a downloader according to the HTTP settings of the given config.
+///
+/// The downloader authenticates against private repositories using the configured credentials
+/// and respects the configured bandwidth limit. Useful for downloads that happen outside of an
+/// installation, e.g. fetching repository indexes for a search.
+pub fn create_downloader_for_config(config: &InstallerConfig) -> anyhow::Result<Downloader> {
+    let retries = config
+        .num_download_retries
+        .unwrap_or(DEFAULT_NUM_DOWNLOAD_RETRIES);
+    let http_config = config.http.clone().unwrap_or_default();
+    let client = create_http_client(&http_config, retries)?;
+    create_downloader(
+        client,
+        &http_config,
+        retries,
+        config.max_download_bytes_per_second,
+    )
+}
+
+pub(crate) fn create_downloader(
+    client: HttpClient,
+    http_config: &HttpConfig,
+    num_download_retries: u32,
+    max_download_bytes_per_second: Option<u64>,
+) -> anyhow::Result<Downloader> {
+    let mut downloader = Downloader::new(client, num_download_retries);
+    let credentials = CredentialStore::load(http_config.credentials_file.as_deref())?;
+    if !credentials.is_empty() {
+        downloader = downloader.with_credentials(Arc::new(credentials));
+    }
+    if let Some(bytes_per_second) = max_download_bytes_per_second {
+        downloader = downloader.with_bandwidth_limit(bytes_per_second);
+    }
+    Ok(downloader)
+}
+
 pub async fn resolve_config(config: InstallerConfig) -> anyhow::Result<ResolvedInstallerConfig> {
-    // Check if this is the main REAPER resource directory
-    let main_reaper_resource_dir = reaper_util::get_default_main_reaper_resource_dir()?;
-    let (reaper_resource_dir, portable) = if let Some(d) = config.custom_reaper_resource_dir {
-        let d = ReaperResourceDir::new(d)?;
-        let portable = d != main_reaper_resource_dir;
-        (d, portable)
-    } else {
-        (main_reaper_resource_dir, false)
-    };
+    let client = create_http_client_for_config(&config)?;
+    resolve_config_internal(config, ResolvePurpose::Install, &client).await
+}
+
+/// Resolves the given config.
+///
+/// The given HTTP client is used for loading included recipes and should be created from the
+/// same config (see [`create_http_client_for_config`]).
+pub(crate) async fn resolve_config_internal(
+    config: InstallerConfig,
+    purpose: ResolvePurpose,
+    client: &HttpClient,
+) -> anyhow::Result<ResolvedInstallerConfig> {
+    let (reaper_resource_dir, portable) =
+        resolve_reaper_resource_dir(config.custom_reaper_resource_dir)?;
     // Determine platform
     let reaper_platform = config
         .custom_platform
@@ -89,19 +162,112 @@ pub async fn resolve_config(config: InstallerConfig) -> anyhow::Result<ResolvedI
         .context("couldn't parse user-provided package URLs")?
         .into_iter()
         .collect();
-    // Add ReaPack package (this is good to have for updates within REAPER and also necessary
-    // for scripts being registered at runtime)
-    let install_reapack = config.install_reapack.unwrap_or(true);
-    if install_reapack {
-        package_urls.insert(create_reapack_package_url());
-    }
+    // Merge included recipes (usually already done when loading the recipe)
+    let recipe = match config.recipe {
+        Some(r) if r.includes.is_some() => Some(
+            r.resolve_includes(config.recipe_location.as_deref(), client)
+                .await?,
+        ),
+        r => r,
+    };
     // Add recipe package URLs
-    if let Some(r) = config.recipe.as_ref() {
-        let all_recipe_packages = r.resolve_all_packages(&config.selected_features);
+    if let Some(r) = recipe.as_ref() {
+        let all_recipe_packages = match purpose {
+            ResolvePurpose::Install => {
+                // Only determine the REAPER version if it matters, because it might not be
+                // readable
+                let reaper_version = if r.all_packages().any(|p| p.depends_on_reaper_version()) {
+                    resolve_future_reaper_version(
+                        &reaper_resource_dir,
+                        reaper_exe.exists(),
+                        config.install_reaper.unwrap_or(true),
+                        config.update_reaper,
+                        config.reaper_version.as_ref(),
+                    )?
+                } else {
+                    None
+                };
+                let environment = RecipeEnvironment {
+                    platform: reaper_platform,
+                    reaper_version,
+                    portable,
+                };
+                r.resolve_all_packages(&config.selected_features, &environment)?
+            }
+            ResolvePurpose::Lock => {
+                r.resolve_lockable_packages(&config.selected_features, reaper_platform)?
+            }
+        };
         let recipe_package_urls = parse_package_urls(all_recipe_packages)
             .context("couldn't parse recipe package URls")?;
         package_urls.extend(recipe_package_urls);
     }
+    // Determine ReaPack settings and whether "latest" should include pre-releases
+    let mut recipe_reapack_settings = recipe
+        .as_ref()
+        .and_then(|r| r.reapack_settings.clone())
+        .unwrap_or_default();
+    let mut ignored_recipe_reapack_settings = vec![];
+    if !config.allow_recipe_network_settings.unwrap_or(false) {
+        if recipe_reapack_settings.proxy.take().is_some() {
+            ignored_recipe_reapack_settings.push("proxy".to_string());
+        }
+        if recipe_reapack_settings.verify_peer.take().is_some() {
+            ignored_recipe_reapack_settings.push("verify_peer".to_string());
+        }
+    }
+    let reapack_settings = config
+        .reapack_settings
+        .unwrap_or_default()
+        .or(recipe_reapack_settings);
+    let include_pre_releases = reapack_settings
+        .pre_releases
+        .or_else(|| read_reapack_pre_release_setting(&reaper_resource_dir))
+        .unwrap_or(false);
+    if include_pre_releases {
+        package_urls = package_urls
+            .into_iter()
+            .map(include_pre_releases_in_latest)
+            .collect();
+    }
+    let install_reapack = config.install_reapack.unwrap_or(true);
+    if let Some(lockfile) = config.lockfile.as_ref() {
+        ensure!(
+            lockfile.platform == reaper_platform,
+            "The lockfile has been created for platform {:?} but we are installing for platform {reaper_platform:?}",
+            lockfile.platform
+        );
+        let nothing_requested = package_urls.is_empty()
+            && recipe
+                .as_ref()
+                .is_none_or(|r| r.all_packages().next().is_none());
+        package_urls = if nothing_requested {
+            // Nothing requested explicitly, so we install everything that has been locked
+            lockfile.packages.iter().map(|p| p.package_url()).collect()
+        } else {
+            // Pin each requested package to its locked version
+            let mut locked_package_urls: HashSet<_> = lockfile
+                .lock_package_urls(&package_urls)?
+                .into_iter()
+                .collect();
+            // ReaPack is only installed if it has been locked along with the other packages
+            let reapack_package_url = create_reapack_package_url();
+            let reapack_is_locked = lockfile
+                .find_package(
+                    reapack_package_url.repository_url(),
+                    reapack_package_url.package_path(),
+                )
+                .is_some();
+            if install_reapack && reapack_is_locked {
+                locked_package_urls.extend(lockfile.lock_package_urls([&reapack_package_url])?);
+            }
+            locked_package_urls
+        };
+    } else if install_reapack {
+        // Add ReaPack package (this is good to have for updates within REAPER and also necessary
+        // for scripts being registered at runtime)
+        package_urls.insert(create_reapack_package_url());
+    }
     // Create config value
     let installation_id = config
         .installation_id
@@ -128,27 +294,147 @@ pub async fn resolve_config(config: InstallerConfig) -> anyhow::Result<ResolvedI
         concurrent_downloads: config
             .concurrent_downloads
             .unwrap_or(DEFAULT_CONCURRENT_DOWNLOADS),
+        concurrent_downloads_per_host: config.concurrent_downloads_per_host,
+        max_download_bytes_per_second: config.max_download_bytes_per_second,
         dry_run: config.dry_run,
         reaper_version: config.reaper_version.unwrap_or_default(),
         install_reaper: config.install_reaper.unwrap_or(true),
         update_reaper: config.update_reaper,
         skip_failed_packages: config.skip_failed_packages,
-        recipe: config.recipe,
+        recipe,
         install_reapack,
+        update_installed_packages: config.update_installed_packages.unwrap_or(false),
+        reapack_settings,
+        ignored_recipe_reapack_settings,
+        include_pre_releases,
+        auto_install_mode: config.auto_install_mode.unwrap_or_default(),
+        lockfile: config.lockfile,
+        download_cache_dir: if config.use_download_cache.unwrap_or(true) {
+            config
+                .download_cache_dir
+                .or_else(DownloadCache::default_dir)
+        } else {
+            None
+        },
+        max_download_cache_size: config
+            .max_download_cache_size
+            .unwrap_or(DEFAULT_MAX_DOWNLOAD_CACHE_SIZE),
+    };
+    Ok(resolved)
+}
+
+/// Reads the pre-release setting from the existing `reapack.ini` file, if any.
+fn read_reapack_pre_release_setting(reaper_resource_dir: &ReaperResourceDir) -> Option<bool> {
+    let reapack_ini_file = reaper_resource_dir.reapack_ini_file();
+    if !reapack_ini_file.exists() {
+        return None;
+    }
+    Config::load_from_ini_file(&reapack_ini_file)
+        .ok()?
+        .install
+        .pre_releases
+}
+
+/// Makes the given package URL resolve to the latest version including pre-releases if it
+/// refers to the latest stable version.
+fn include_pre_releases_in_latest(mut package_url: PackageUrl) -> PackageUrl {
+    let version_ref = &mut package_url.package_version_ref.version_ref;
+    if *version_ref == VersionRef::Latest {
+        *version_ref = VersionRef::LatestPre;
+    }
+    package_url
+}
+
+/// Resolves the REAPER resource directory to work with and returns whether it belongs to a
+/// portable REAPER installation.
+///
+/// If no custom directory is given, this returns the main resource directory.
+pub fn resolve_reaper_resource_dir(
+    custom_reaper_resource_dir: Option<PathBuf>,
+) -> anyhow::Result<(ReaperResourceDir, bool)> {
+    // Check if this is the main REAPER resource directory
+    let main_reaper_resource_dir = reaper_util::get_default_main_reaper_resource_dir()?;
+    let resolved = if let Some(d) = custom_reaper_resource_dir {
+        let d = ReaperResourceDir::new(d)?;
+        let portable = d != main_reaper_resource_dir;
+        (d, portable)
+    } else {
+        (main_reaper_resource_dir, false)
     };
     Ok(resolved)
 }
 
-fn create_default_installation_id() -> String {
+pub(crate) fn create_default_installation_id() -> String {
     jiff::Timestamp::now()
         .strftime("%Y-%m-%d_%H-%M-%S")
         .to_string()
 }
 
+/// Determines the REAPER version that will be present after the installation.
+///
+/// Returns `None` if it's going to be the latest available version, which is not known in advance.
+///
+/// Fails if REAPER is installed already but its version can't be determined although it's
+/// needed. Treating it as latest version would silently skip packages that have a maximum
+/// REAPER version.
+fn resolve_future_reaper_version(
+    reaper_resource_dir: &ReaperResourceDir,
+    reaper_exe_exists: bool,
+    install_reaper: bool,
+    update_reaper: bool,
+    requested_version: Option<&VersionRef>,
+) -> anyhow::Result<Option<VersionName>> {
+    let reaper_will_be_installed = if reaper_exe_exists {
+        update_reaper
+    } else {
+        install_reaper
+    };
+    let requested_specific_version = match requested_version {
+        Some(VersionRef::Specific(v)) if reaper_will_be_installed => Some(v),
+        _ => None,
+    };
+    if reaper_will_be_installed && requested_specific_version.is_none() {
+        return Ok(None);
+    }
+    let installed_version = if reaper_exe_exists {
+        let version = reaper_resource_dir.read_installed_version().with_context(|| {
+            format!(
+                "Couldn't determine the version of the installed REAPER (via {:?}), which is necessary to evaluate the REAPER version conditions of the recipe packages",
+                reaper_resource_dir.reaper_install_rev_file()
+            )
+        })?;
+        Some(version)
+    } else {
+        None
+    };
+    // An existing REAPER installation is never downgraded
+    Ok(installed_version.max(requested_specific_version.cloned()))
+}
+
 fn parse_package_urls(
     urls: impl IntoIterator<Item = impl AsRef<str>>,
 ) -> Result<Vec<PackageUrl>, ParsePackageUrlError> {
-    urls.into_iter().map(PackageUrl::parse).collect()
+    urls.into_iter()
+        .map(|url| PackageUrl::parse(url).map(resolve_local_repository_dir))
+        .collect()
+}
+
+/// If the repository URL is a `file://` URL pointing to a local directory, makes it point to the
+/// `index.xml` file within that directory.
+fn resolve_local_repository_dir(mut package_url: PackageUrl) -> PackageUrl {
+    if package_url.repository_url.scheme() != "file" {
+        return package_url;
+    }
+    let Ok(path) = package_url.repository_url.to_file_path() else {
+        return package_url;
+    };
+    if !path.is_dir() {
+        return package_url;
+    }
+    if let Ok(index_url) = Url::from_file_path(path.join("index.xml")) {
+        package_url.repository_url = index_url;
+    }
+    package_url
 }
 
 pub async fn complain_if_reapack_db_too_new(
@@ -169,6 +455,18 @@ pub async fn complain_if_reapack_db_too_new(
     Ok(())
 }
 
+/// Fails if the ReaPack database in the given REAPER resource directory is currently in use,
+/// e.g. by a running REAPER instance.
+pub async fn complain_if_reapack_db_busy(
+    reaper_resource_dir: &ReaperResourceDir,
+) -> anyhow::Result<()> {
+    let reapack_db_file = reaper_resource_dir.reapack_registry_db_file();
+    if reapack_db_file.exists() {
+        Database::open(reapack_db_file).await.context("ReaPack database is currently busy. Please close REAPER and/or stop existing ReaBoot processes and try again!")?;
+    }
+    Ok(())
+}
+
 pub async fn determine_initial_installation_stage(
     resolved_config: &ResolvedInstallerConfig,
 ) -> anyhow::Result<InstallationStage> {
@@ -177,7 +475,7 @@ pub async fn determine_initial_installation_stage(
         return Ok(InstallationStage::NothingInstalled);
     };
     // At this point, we can be sure that REAPER is installed
-    if !resolved_config.package_urls.is_empty() {
+    if !resolved_config.package_urls.is_empty() || resolved_config.update_installed_packages {
         return Ok(InstallationStage::InstalledReaper);
     }
     // No packages to be installed
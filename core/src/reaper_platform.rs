@@ -1,10 +1,25 @@
 use reaboot_reapack::model::Platform;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use strum::{EnumIter, IntoEnumIterator};
 use ts_rs::TS;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS)]
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Serialize,
+    Deserialize,
+    TS,
+    JsonSchema,
+    EnumIter,
+)]
 #[ts(export)]
 pub enum ReaperPlatform {
     /// - REAPER about: macOS-arm64
@@ -71,6 +86,11 @@ pub enum ReaperOs {
 }
 
 impl ReaperPlatform {
+    /// Returns all platforms supported by ReaBoot.
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::iter()
+    }
+
     pub const fn from_reaboot_build() -> Self {
         if let Some(p) = Self::from_reaboot_build_checked() {
             p
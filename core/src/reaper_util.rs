@@ -1,4 +1,5 @@
 use crate::file_util::move_dir_contents;
+use crate::http_client::HttpClient;
 use crate::reaper_platform::ReaperPlatform;
 use anyhow::{bail, ensure, Context};
 
@@ -28,10 +29,11 @@ pub struct ReaperInstallerAsset {
 }
 
 pub async fn get_latest_reaper_installer_asset(
+    client: &HttpClient,
     reaper_target: ReaperPlatform,
     version_ref: &VersionRef,
 ) -> anyhow::Result<ReaperInstallerAsset> {
-    let version = resolve_reaper_version(version_ref).await?;
+    let version = resolve_reaper_version(client, version_ref).await?;
     let major_version = version.major();
     let file_name = get_os_specific_reaper_installer_file_name(reaper_target, &version);
     let url = if version.is_stable() {
@@ -190,19 +192,23 @@ fn extract_reaper_for_linux_to_dir(
 }
 
 /// REAPER versions seem to be similar to ReaPack versions in nature.
-async fn resolve_reaper_version(version_ref: &VersionRef) -> anyhow::Result<VersionName> {
+async fn resolve_reaper_version(
+    client: &HttpClient,
+    version_ref: &VersionRef,
+) -> anyhow::Result<VersionName> {
     let include_pre_releases = match version_ref {
         VersionRef::Latest => false,
         VersionRef::LatestPre => true,
         VersionRef::Specific(v) => return Ok(v.clone()),
     };
     let stable_version =
-        get_latest_reaper_version_from_url(LATEST_STABLE_VERSION_URL, |line| Ok(line)).await?;
+        get_latest_reaper_version_from_url(client, LATEST_STABLE_VERSION_URL, |line| Ok(line))
+            .await?;
     if !include_pre_releases {
         return Ok(stable_version);
     }
     let unstable_version =
-        get_latest_reaper_version_from_url(LATEST_UNSTABLE_VERSION_URL, |line| {
+        get_latest_reaper_version_from_url(client, LATEST_UNSTABLE_VERSION_URL, |line| {
             let (version, _) = line
                 .strip_prefix('v')
                 .context("whatsnew.txt should return version starting with letter v")?
@@ -220,10 +226,11 @@ async fn resolve_reaper_version(version_ref: &VersionRef) -> anyhow::Result<Vers
 }
 
 async fn get_latest_reaper_version_from_url(
+    client: &HttpClient,
     url: &str,
     narrow_down_version_line: impl FnOnce(&str) -> anyhow::Result<&str>,
 ) -> anyhow::Result<VersionName> {
-    let response = reqwest::get(url).await?;
+    let response = client.get(url).send().await?;
     let body = response.text().await?;
     let version_line = body.lines().next().with_context(|| {
         format!("{url} should return version number somewhere contained in first line")
@@ -235,8 +242,8 @@ async fn get_latest_reaper_version_from_url(
     Ok(version)
 }
 
-pub async fn get_reaper_eula() -> anyhow::Result<String> {
-    let response = reqwest::get(EULA_URL).await?;
+pub async fn get_reaper_eula(client: &HttpClient) -> anyhow::Result<String> {
+    let response = client.get(EULA_URL).send().await?;
     let body = response.text().await?;
     Ok(body)
 }
@@ -1,8 +1,19 @@
+use crate::api::ReaPackSettings;
+use crate::http_client::HttpClient;
+use crate::reaper_platform::ReaperPlatform;
+use anyhow::{bail, Context};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use reaboot_reapack::model::VersionName;
 use reqwest::StatusCode;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
 use ts_rs::TS;
+use url::Url;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
@@ -17,26 +28,253 @@ pub struct Recipe {
     #[ts(optional = nullable)]
     pub skip_additional_packages: Option<bool>,
     #[ts(optional = nullable)]
-    pub required_packages: Option<Vec<String>>,
+    pub required_packages: Option<Vec<RecipePackage>>,
     #[ts(optional = nullable)]
     pub features: Option<BTreeMap<String, Feature>>,
+    /// Paths or URLs of other recipes whose packages and features should be merged into this one.
+    ///
+    /// Relative paths are resolved against the location of this recipe. Later includes take
+    /// precedence over earlier ones and this recipe takes precedence over all of its includes.
+    #[ts(optional = nullable)]
+    pub includes: Option<Vec<String>>,
+    /// ReaPack remotes that should be registered, even if none of their packages is installed.
+    ///
+    /// Remotes that are registered already (same name or URL) are left untouched.
+    #[ts(optional = nullable)]
+    pub remotes: Option<Vec<RecipeRemote>>,
+    /// ReaPack settings to be written to `reapack.ini`, e.g. whether to use pre-releases.
+    ///
+    /// The network security settings `proxy` and `verify_peer` are ignored unless the user
+    /// allows them explicitly.
+    #[ts(optional = nullable)]
+    pub reapack_settings: Option<ReaPackSettings>,
 }
 
 impl Recipe {
-    pub fn resolve_all_packages<'a>(
+    /// Returns the IDs of all features.
+    pub fn feature_ids(&self) -> impl Iterator<Item = &String> {
+        self.features.iter().flatten().map(|(id, _)| id)
+    }
+
+    /// Returns the IDs of all features that are marked as default.
+    pub fn default_feature_ids(&self) -> impl Iterator<Item = &String> {
+        self.features
+            .iter()
+            .flatten()
+            .filter(|(_, feature)| feature.default == Some(true))
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the required packages and the packages of all features, without duplicates and
+    /// regardless of their conditions.
+    pub fn all_packages(&self) -> impl Iterator<Item = &RecipePackage> {
+        let mut seen = HashSet::new();
+        self.required_packages
+            .iter()
+            .flatten()
+            .chain(
+                self.features
+                    .iter()
+                    .flatten()
+                    .flat_map(|(_, feature)| feature.packages.iter().flatten()),
+            )
+            .filter(move |p| seen.insert(p.url()))
+    }
+
+    /// Expands the given feature selection by all transitively required features.
+    ///
+    /// Fails if a feature doesn't exist or if the expanded selection contains conflicting features.
+    pub fn resolve_features<'a>(
         &'a self,
-        selected_features: &'a HashSet<String>,
-    ) -> impl Iterator<Item = &String> {
-        self.required_packages.iter().flatten().chain(
-            self.features
+        selected_features: &HashSet<String>,
+    ) -> Result<BTreeSet<&'a str>, FeatureSelectionError> {
+        let features = self.features.as_ref();
+        let get_feature = |id: &str| {
+            features
+                .and_then(|f| f.get_key_value(id))
+                .map(|(id, feature)| (id.as_str(), feature))
+        };
+        let mut resolved = BTreeSet::new();
+        let mut pending: Vec<(&str, Option<&str>)> = selected_features
+            .iter()
+            .map(|id| (id.as_str(), None))
+            .collect();
+        while let Some((id, required_by)) = pending.pop() {
+            let Some((id, feature)) = get_feature(id) else {
+                let error = match required_by {
+                    None => FeatureSelectionError::UnknownFeature(id.to_string()),
+                    Some(feature) => FeatureSelectionError::UnknownRequiredFeature {
+                        feature: feature.to_string(),
+                        required: id.to_string(),
+                    },
+                };
+                return Err(error);
+            };
+            if resolved.insert(id) {
+                pending.extend(
+                    feature
+                        .requires
+                        .iter()
+                        .flatten()
+                        .map(|r| (r.as_str(), Some(id))),
+                );
+            }
+        }
+        for id in &resolved {
+            let (_, feature) = get_feature(id).expect("feature resolved before");
+            let conflicting_feature = feature
+                .conflicts_with
                 .iter()
                 .flatten()
-                .filter(|(id, _)| selected_features.contains(*id))
-                .flat_map(|(_, feature)| feature.packages.iter().flatten()),
+                .find(|other| resolved.contains(other.as_str()));
+            if let Some(other) = conflicting_feature {
+                return Err(FeatureSelectionError::Conflict {
+                    feature: id.to_string(),
+                    other: other.clone(),
+                });
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Returns the URLs of the required packages and the packages of the given features, including
+    /// the features they require (without duplicates).
+    ///
+    /// Packages whose conditions are not met in the given environment are left out.
+    pub fn resolve_all_packages(
+        &self,
+        selected_features: &HashSet<String>,
+        environment: &RecipeEnvironment,
+    ) -> Result<Vec<&str>, FeatureSelectionError> {
+        self.resolve_packages(selected_features, |p| p.is_applicable(environment))
+    }
+
+    /// Like [`Self::resolve_all_packages`] but only checks the platform conditions.
+    ///
+    /// This is used when creating lockfiles and bundles. At that point, the REAPER installation
+    /// that will receive the packages is not known, so the remaining conditions can only be
+    /// evaluated at installation time.
+    pub fn resolve_lockable_packages(
+        &self,
+        selected_features: &HashSet<String>,
+        platform: ReaperPlatform,
+    ) -> Result<Vec<&str>, FeatureSelectionError> {
+        self.resolve_packages(selected_features, |p| p.supports_platform(platform))
+    }
+
+    fn resolve_packages(
+        &self,
+        selected_features: &HashSet<String>,
+        is_included: impl Fn(&RecipePackage) -> bool,
+    ) -> Result<Vec<&str>, FeatureSelectionError> {
+        let resolved_features = self.resolve_features(selected_features)?;
+        let mut seen = HashSet::new();
+        let packages = self
+            .required_packages
+            .iter()
+            .flatten()
+            .chain(
+                self.features
+                    .iter()
+                    .flatten()
+                    .filter(|(id, _)| resolved_features.contains(id.as_str()))
+                    .flat_map(|(_, feature)| feature.packages.iter().flatten()),
+            )
+            .filter(|p| is_included(p))
+            .map(|p| p.url())
+            .filter(|url| seen.insert(*url))
+            .collect();
+        Ok(packages)
+    }
+
+    /// Loads all included recipes recursively and merges them into this recipe.
+    ///
+    /// `location` is the path or URL of this recipe and is used to resolve relative includes.
+    /// The returned recipe doesn't have any includes anymore.
+    pub async fn resolve_includes(
+        self,
+        location: Option<&str>,
+        client: &HttpClient,
+    ) -> anyhow::Result<Recipe> {
+        self.resolve_includes_inspecting(location, client, |_, _| Ok(()))
+            .await
+    }
+
+    /// Like [`Self::resolve_includes`] but passes the location and raw JSON of each included
+    /// recipe to the given function before parsing it, e.g. for validating it.
+    ///
+    /// Fails as soon as the given function fails.
+    pub async fn resolve_includes_inspecting(
+        self,
+        location: Option<&str>,
+        client: &HttpClient,
+        mut inspect: impl FnMut(&str, &str) -> anyhow::Result<()> + Send,
+    ) -> anyhow::Result<Recipe> {
+        let mut include_chain = location
+            .map(|l| vec![normalize_location(l)])
+            .unwrap_or_default();
+        resolve_includes_internal(
+            self,
+            location.map(|l| l.to_string()),
+            client,
+            &mut include_chain,
+            &mut inspect,
         )
+        .await
+    }
+
+    /// Fills everything that this recipe doesn't define itself from the given recipe.
+    fn inherit_from(&mut self, base: Recipe) {
+        self.sub_title = self.sub_title.take().or(base.sub_title);
+        self.description = self.description.take().or(base.description);
+        self.website = self.website.take().or(base.website);
+        self.skip_additional_packages = self
+            .skip_additional_packages
+            .take()
+            .or(base.skip_additional_packages);
+        if let Some(base_packages) = base.required_packages {
+            let own_packages = self.required_packages.take().unwrap_or_default();
+            let mut seen = HashSet::new();
+            let merged = base_packages
+                .into_iter()
+                .chain(own_packages)
+                .filter(|p| seen.insert(p.url().to_string()))
+                .collect();
+            self.required_packages = Some(merged);
+        }
+        if let Some(base_remotes) = base.remotes {
+            let own_remotes = self.remotes.take().unwrap_or_default();
+            let mut merged: Vec<_> = base_remotes
+                .into_iter()
+                .filter(|r| own_remotes.iter().all(|own| own.name != r.name))
+                .collect();
+            merged.extend(own_remotes);
+            self.remotes = Some(merged);
+        }
+        if let Some(base_settings) = base.reapack_settings {
+            let own_settings = self.reapack_settings.take().unwrap_or_default();
+            self.reapack_settings = Some(own_settings.or(base_settings));
+        }
+        if let Some(mut features) = base.features {
+            features.extend(self.features.take().unwrap_or_default());
+            self.features = Some(features);
+        }
     }
 }
 
+/// Error which occurs if a feature selection can't be satisfied.
+#[derive(Clone, Eq, PartialEq, Debug, Error)]
+pub enum FeatureSelectionError {
+    #[error("Feature {0:?} doesn't exist")]
+    UnknownFeature(String),
+    #[error("Feature {feature:?} requires feature {required:?}, which doesn't exist")]
+    UnknownRequiredFeature { feature: String, required: String },
+    #[error(
+        "Feature {feature:?} conflicts with feature {other:?}, so they can't be installed together"
+    )]
+    Conflict { feature: String, other: String },
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct Feature {
@@ -46,16 +284,243 @@ pub struct Feature {
     #[ts(optional = nullable)]
     pub description: Option<String>,
     #[ts(optional = nullable)]
-    pub packages: Option<Vec<String>>,
+    pub packages: Option<Vec<RecipePackage>>,
+    /// IDs of features that are automatically installed along with this feature.
+    #[ts(optional = nullable)]
+    pub requires: Option<Vec<String>>,
+    /// IDs of features that can't be installed together with this feature.
+    #[ts(optional = nullable)]
+    pub conflicts_with: Option<Vec<String>>,
+}
+
+/// ReaPack remote (repository) to be registered in `reapack.ini`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct RecipeRemote {
+    pub name: String,
+    /// URL of the repository index.
+    #[schemars(with = "String")]
+    pub url: Url,
+    /// Whether the remote should be enabled. Defaults to `true`.
+    #[ts(optional = nullable)]
+    pub enabled: Option<bool>,
+    /// Whether ReaPack should automatically install new packages from this remote. If not set,
+    /// the global ReaPack setting applies.
+    #[ts(optional = nullable)]
+    pub auto_install: Option<bool>,
+}
+
+/// Entry in a list of recipe packages.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum RecipePackage {
+    /// Package URL of a package that should be installed unconditionally.
+    Url(String),
+    /// Package URL of a package that should only be installed if certain conditions are met.
+    Conditional(Box<ConditionalRecipePackage>),
+}
+
+/// Package URL together with the conditions that must be met in order to install the package.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct ConditionalRecipePackage {
+    pub url: String,
+    /// Platforms on which the package should be installed. If not set, all platforms qualify.
+    #[ts(optional = nullable)]
+    pub platforms: Option<Vec<ReaperPlatform>>,
+    /// Minimum REAPER version (inclusive).
+    #[ts(optional = nullable)]
+    #[schemars(with = "Option<String>")]
+    pub min_reaper_version: Option<VersionName>,
+    /// Maximum REAPER version (inclusive).
+    #[ts(optional = nullable)]
+    #[schemars(with = "Option<String>")]
+    pub max_reaper_version: Option<VersionName>,
+    /// If `true`, the package is only installed into portable REAPER installations.
+    #[ts(optional = nullable)]
+    pub portable_only: Option<bool>,
+}
+
+/// Properties of the installation against which package conditions are evaluated.
+#[derive(Clone, Debug)]
+pub struct RecipeEnvironment {
+    pub platform: ReaperPlatform,
+    /// REAPER version that will be present after the installation.
+    ///
+    /// `None` means that the latest REAPER version is going to be installed. In that case,
+    /// minimum versions are considered as met and maximum versions as not met.
+    pub reaper_version: Option<VersionName>,
+    pub portable: bool,
+}
+
+impl RecipePackage {
+    pub fn url(&self) -> &str {
+        match self {
+            RecipePackage::Url(url) => url,
+            RecipePackage::Conditional(p) => &p.url,
+        }
+    }
+
+    /// Returns whether the package should be installed on the given platform, not taking other
+    /// conditions into account.
+    pub fn supports_platform(&self, platform: ReaperPlatform) -> bool {
+        match self {
+            RecipePackage::Url(_) => true,
+            RecipePackage::Conditional(p) => p
+                .platforms
+                .as_ref()
+                .is_none_or(|platforms| platforms.contains(&platform)),
+        }
+    }
+
+    /// Returns whether this package should only be installed for certain REAPER versions.
+    pub fn depends_on_reaper_version(&self) -> bool {
+        match self {
+            RecipePackage::Url(_) => false,
+            RecipePackage::Conditional(p) => {
+                p.min_reaper_version.is_some() || p.max_reaper_version.is_some()
+            }
+        }
+    }
+
+    /// Returns whether all conditions of this package are met in the given environment.
+    pub fn is_applicable(&self, environment: &RecipeEnvironment) -> bool {
+        let RecipePackage::Conditional(p) = self else {
+            return true;
+        };
+        if !self.supports_platform(environment.platform) {
+            return false;
+        }
+        if p.portable_only == Some(true) && !environment.portable {
+            return false;
+        }
+        if let (Some(min), Some(actual)) = (&p.min_reaper_version, &environment.reaper_version) {
+            if actual < min {
+                return false;
+            }
+        }
+        if let Some(max) = &p.max_reaper_version {
+            match &environment.reaper_version {
+                Some(actual) if actual <= max => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Loads a recipe from the given path or URL and resolves its includes.
+pub async fn load_recipe(location: &str, client: &HttpClient) -> anyhow::Result<Recipe> {
+    let json = load_recipe_json(location, client).await?;
+    let recipe: Recipe = serde_json::from_str(&json)
+        .with_context(|| format!("couldn't parse recipe at {location}"))?;
+    recipe.resolve_includes(Some(location), client).await
+}
+
+/// Loads the raw recipe JSON either from a local file or, if an HTTP(S) URL is given, from the
+/// web.
+pub async fn load_recipe_json(location: &str, client: &HttpClient) -> anyhow::Result<String> {
+    if let Some(url) = parse_web_url(location) {
+        return fetch_recipe_json(client, url.as_str())
+            .await
+            .with_context(|| format!("couldn't fetch recipe from {url}"))?
+            .with_context(|| format!("there's no recipe at {url}"));
+    }
+    fs::read_to_string(Path::new(location))
+        .with_context(|| format!("couldn't read recipe file {location}"))
 }
 
-pub async fn fetch_and_parse_recipe(url: &str) -> Option<Recipe> {
-    let json = fetch_recipe_json(url).await.ok()??;
+fn resolve_includes_internal<'a>(
+    mut recipe: Recipe,
+    location: Option<String>,
+    client: &'a HttpClient,
+    include_chain: &'a mut Vec<String>,
+    inspect: &'a mut (dyn FnMut(&str, &str) -> anyhow::Result<()> + Send),
+) -> BoxFuture<'a, anyhow::Result<Recipe>> {
+    async move {
+        let includes = recipe.includes.take().unwrap_or_default();
+        let mut included_recipes = vec![];
+        for include in includes {
+            let include_location = resolve_include_location(location.as_deref(), &include)?;
+            let normalized_include_location = normalize_location(&include_location);
+            if include_chain.contains(&normalized_include_location) {
+                bail!(
+                    "Recipe includes form a cycle: {} -> {normalized_include_location}",
+                    include_chain.join(" -> ")
+                );
+            }
+            let json = load_recipe_json(&include_location, client).await?;
+            inspect(&include_location, &json)?;
+            let included_recipe: Recipe = serde_json::from_str(&json)
+                .with_context(|| format!("couldn't parse included recipe {include_location}"))?;
+            include_chain.push(normalized_include_location);
+            let included_recipe = resolve_includes_internal(
+                included_recipe,
+                Some(include_location),
+                client,
+                include_chain,
+                inspect,
+            )
+            .await?;
+            include_chain.pop();
+            included_recipes.push(included_recipe);
+        }
+        // Later includes take precedence over earlier ones
+        for included_recipe in included_recipes.into_iter().rev() {
+            recipe.inherit_from(included_recipe);
+        }
+        Ok(recipe)
+    }
+    .boxed()
+}
+
+fn resolve_include_location(base: Option<&str>, include: &str) -> anyhow::Result<String> {
+    if parse_web_url(include).is_some() || Path::new(include).is_absolute() {
+        return Ok(include.to_string());
+    }
+    let Some(base) = base else {
+        bail!("Can't resolve relative include {include:?} because the location of the including recipe is unknown");
+    };
+    if let Some(base_url) = parse_web_url(base) {
+        let url = base_url
+            .join(include)
+            .with_context(|| format!("couldn't resolve include {include:?}"))?;
+        return Ok(url.to_string());
+    }
+    let base_dir = Path::new(base).parent().unwrap_or(Path::new(""));
+    Ok(base_dir.join(include).to_string_lossy().to_string())
+}
+
+/// Returns a normalized form of the given recipe location, so that different ways of referring
+/// to the same recipe can be recognized as such.
+///
+/// File paths are canonicalized (if the file exists) and URLs are normalized by parsing them.
+fn normalize_location(location: &str) -> String {
+    if let Some(url) = parse_web_url(location) {
+        return url.to_string();
+    }
+    fs::canonicalize(location)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| location.to_string())
+}
+
+fn parse_web_url(text: &str) -> Option<Url> {
+    Url::parse(text)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+pub async fn fetch_and_parse_recipe(client: &HttpClient, url: &str) -> Option<Recipe> {
+    let json = fetch_recipe_json(client, url).await.ok()??;
     serde_json::from_str(&json).ok()
 }
 
-async fn fetch_recipe_json(url: &str) -> anyhow::Result<Option<String>> {
-    let response = reqwest::get(url).await?;
+/// Fetches the raw recipe JSON from the given URL.
+///
+/// Returns `None` if there's no recipe at that URL.
+pub async fn fetch_recipe_json(client: &HttpClient, url: &str) -> anyhow::Result<Option<String>> {
+    let response = client.get(url).send().await?;
     if response.status() == StatusCode::NOT_FOUND {
         return Ok(None);
     }
@@ -75,4 +540,180 @@ mod tests {
         let text = serde_json::to_string_pretty(&schema).unwrap();
         fs::write("bindings/recipe.schema.json", text).unwrap();
     }
+
+    #[test]
+    fn default_feature_ids() {
+        let recipe: Recipe = serde_json::from_str(
+            r#"{
+                "name": "Test",
+                "features": {
+                    "a": {"name": "A", "default": true},
+                    "b": {"name": "B", "default": false},
+                    "c": {"name": "C"}
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(recipe.feature_ids().count(), 3);
+        assert_eq!(recipe.default_feature_ids().collect::<Vec<_>>(), ["a"]);
+    }
+
+    #[test]
+    fn resolve_all_packages_with_feature_dependencies() {
+        let recipe: Recipe = serde_json::from_str(
+            r#"{
+                "name": "Test",
+                "required_packages": ["r"],
+                "features": {
+                    "a": {"name": "A", "packages": ["a"], "requires": ["b"]},
+                    "b": {"name": "B", "packages": ["b", "r"], "requires": ["c"]},
+                    "c": {"name": "C", "packages": ["c"]},
+                    "d": {"name": "D", "packages": ["d"], "conflicts_with": ["c"]},
+                    "e": {"name": "E", "requires": ["x"]}
+                }
+            }"#,
+        )
+        .unwrap();
+        let select = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+        let env = RecipeEnvironment {
+            platform: ReaperPlatform::WindowsX64,
+            reaper_version: None,
+            portable: false,
+        };
+        assert_eq!(
+            recipe.resolve_all_packages(&select(&["a"]), &env).unwrap(),
+            ["r", "a", "b", "c"]
+        );
+        assert_eq!(
+            recipe.resolve_all_packages(&select(&["a", "d"]), &env),
+            Err(FeatureSelectionError::Conflict {
+                feature: "d".to_string(),
+                other: "c".to_string()
+            })
+        );
+        assert_eq!(
+            recipe.resolve_all_packages(&select(&["e"]), &env),
+            Err(FeatureSelectionError::UnknownRequiredFeature {
+                feature: "e".to_string(),
+                required: "x".to_string()
+            })
+        );
+        assert_eq!(
+            recipe.resolve_all_packages(&select(&["y"]), &env),
+            Err(FeatureSelectionError::UnknownFeature("y".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_includes() {
+        let dir = tempdir::TempDir::new("reaboot-recipe-test").unwrap();
+        let write = |name: &str, json: &str| fs::write(dir.path().join(name), json).unwrap();
+        write(
+            "base.json",
+            r#"{
+                "name": "Base",
+                "website": "https://example.org",
+                "required_packages": ["base", "shared"],
+                "features": {
+                    "a": {"name": "Base A"},
+                    "b": {"name": "Base B"}
+                }
+            }"#,
+        );
+        write(
+            "main.json",
+            r#"{
+                "name": "Main",
+                "includes": ["base.json"],
+                "required_packages": ["shared", "main"],
+                "features": {
+                    "b": {"name": "Main B"}
+                }
+            }"#,
+        );
+        write(
+            "cycle.json",
+            r#"{"name": "Cycle", "includes": ["main.json", "cycle.json"]}"#,
+        );
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        write(
+            "sub/indirect-cycle.json",
+            r#"{"name": "Indirect cycle", "includes": ["../sub/indirect-cycle.json"]}"#,
+        );
+        let client = crate::http_client::create_http_client(&Default::default(), 0).unwrap();
+        let location = dir.path().join("main.json");
+        let recipe = load_recipe(location.to_str().unwrap(), &client)
+            .await
+            .unwrap();
+        assert_eq!(recipe.name, "Main");
+        assert_eq!(recipe.includes, None);
+        assert_eq!(recipe.website.as_deref(), Some("https://example.org"));
+        assert_eq!(
+            recipe.all_packages().map(|p| p.url()).collect::<Vec<_>>(),
+            ["base", "shared", "main"]
+        );
+        let features = recipe.features.unwrap();
+        assert_eq!(features["a"].name, "Base A");
+        assert_eq!(features["b"].name, "Main B");
+        let location = dir.path().join("cycle.json");
+        let error = load_recipe(location.to_str().unwrap(), &client)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("form a cycle"));
+        // Cycles are detected even if the same file is referred to via a different path
+        let location = dir.path().join("sub/indirect-cycle.json");
+        let error = load_recipe(location.to_str().unwrap(), &client)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("form a cycle"));
+        // Relative includes can't be resolved without knowing the location of the recipe
+        let recipe: Recipe =
+            serde_json::from_str(r#"{"name": "Unknown", "includes": ["base.json"]}"#).unwrap();
+        assert!(recipe.resolve_includes(None, &client).await.is_err());
+    }
+
+    #[test]
+    fn resolve_conditional_packages() {
+        let recipe: Recipe = serde_json::from_str(
+            r#"{
+                "name": "Test",
+                "required_packages": [
+                    "all",
+                    {"url": "windows", "platforms": ["windows-x64", "windows-x86"]},
+                    {"url": "reaper7", "min_reaper_version": "7.0"},
+                    {"url": "reaper6", "max_reaper_version": "6.99"},
+                    {"url": "portable", "portable_only": true}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let resolve = |platform, reaper_version: Option<&str>, portable| {
+            let env = RecipeEnvironment {
+                platform,
+                reaper_version: reaper_version.map(|v| v.parse().unwrap()),
+                portable,
+            };
+            recipe.resolve_all_packages(&HashSet::new(), &env).unwrap()
+        };
+        assert_eq!(
+            resolve(ReaperPlatform::WindowsX64, Some("7.22"), false),
+            ["all", "windows", "reaper7"]
+        );
+        assert_eq!(
+            resolve(ReaperPlatform::LinuxX86_64, Some("6.83"), true),
+            ["all", "reaper6", "portable"]
+        );
+        assert_eq!(
+            resolve(ReaperPlatform::LinuxX86_64, None, false),
+            ["all", "reaper7"]
+        );
+        // When locking, only the platform is known, so the other conditions are evaluated at
+        // installation time
+        assert_eq!(
+            recipe
+                .resolve_lockable_packages(&HashSet::new(), ReaperPlatform::LinuxX86_64)
+                .unwrap(),
+            ["all", "reaper7", "reaper6", "portable"]
+        );
+    }
 }
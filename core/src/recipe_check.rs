@@ -0,0 +1,244 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+use futures::future::join_all;
+use schemars::schema_for;
+use url::Url;
+
+use reaboot_reapack::index::Index;
+use reaboot_reapack::model::{PackageUrl, VersionRef};
+
+use crate::downloader::{Download, Downloader};
+use crate::installation_model::{make_first_plan, PackageDescError, PreDownloadFailures};
+use crate::installer::DownloadedIndex;
+use crate::reaper_platform::ReaperPlatform;
+use crate::recipe::Recipe;
+
+/// A problem found while checking a recipe.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct RecipeProblem {
+    /// Platform on which the problem occurs or `None` if it occurs on all checked platforms.
+    pub platform: Option<ReaperPlatform>,
+    pub message: String,
+}
+
+/// Validates the given recipe JSON against the recipe JSON schema.
+///
+/// Returns one message per schema violation.
+pub fn validate_recipe_json(json: &str) -> anyhow::Result<Vec<String>> {
+    let instance: serde_json::Value = serde_json::from_str(json)?;
+    let schema = serde_json::to_value(schema_for!(Recipe))?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| anyhow::anyhow!("recipe schema is invalid: {e}"))?;
+    let violations = validator
+        .iter_errors(&instance)
+        .map(|e| format!("Schema violation at `{}`: {e}", e.instance_path))
+        .collect();
+    Ok(violations)
+}
+
+/// Checks that all features of the recipe can be selected and that all packages of the recipe
+/// (including all features) exist in their repositories and can be installed together on each of
+/// the given platforms.
+///
+/// Repository indexes are downloaded into `temp_dir`.
+pub async fn check_recipe(
+    recipe: &Recipe,
+    downloader: &Downloader,
+    temp_dir: &Path,
+    platforms: &[ReaperPlatform],
+) -> Vec<RecipeProblem> {
+    let mut common_problems = BTreeSet::new();
+    // Parse package URLs
+    let features = recipe.features.iter().flatten();
+    let raw_package_urls = recipe
+        .required_packages
+        .iter()
+        .flatten()
+        .map(|p| (p, None))
+        .chain(features.flat_map(|(id, feature)| {
+            feature
+                .packages
+                .iter()
+                .flatten()
+                .map(move |p| (p, Some(id)))
+        }));
+    let mut package_urls = vec![];
+    for (package, feature_id) in raw_package_urls {
+        let raw = package.url();
+        match PackageUrl::parse(raw) {
+            Ok(url) => package_urls.push((url, package)),
+            Err(e) => {
+                let location = feature_id
+                    .map(|id| format!(" in feature `{id}`"))
+                    .unwrap_or_default();
+                common_problems.insert(format!("Invalid package URL `{raw}`{location}: {e}"));
+            }
+        }
+    }
+    // Check feature dependencies
+    for id in recipe.feature_ids() {
+        if let Err(e) = recipe.resolve_features(&HashSet::from([id.clone()])) {
+            common_problems.insert(format!("Feature `{id}` can't be installed: {e}"));
+        }
+    }
+    // Download indexes
+    let repository_urls: HashSet<&Url> = package_urls
+        .iter()
+        .map(|(u, _)| u.repository_url())
+        .collect();
+    let (indexes, index_problems) = download_indexes(repository_urls, downloader, temp_dir).await;
+    common_problems.extend(index_problems);
+    // Check each platform
+    let problems_per_platform: Vec<_> = platforms
+        .iter()
+        .map(|platform| {
+            // Version and portability conditions are not taken into account
+            let platform_package_urls: Vec<_> = package_urls
+                .iter()
+                .filter(|(_, p)| p.supports_platform(*platform))
+                .map(|(u, _)| u.clone())
+                .collect();
+            let plan = make_first_plan(&platform_package_urls, &indexes, &[], *platform);
+            (*platform, describe_failures(plan.pre_download_failures))
+        })
+        .collect();
+    // Problems that occur on all platforms are not platform-specific
+    let problems_on_all_platforms: BTreeSet<String> = problems_per_platform
+        .iter()
+        .map(|(_, problems)| problems.clone())
+        .reduce(|a, b| a.intersection(&b).cloned().collect())
+        .unwrap_or_default();
+    let platform_specific_problems: Vec<_> = problems_per_platform
+        .into_iter()
+        .flat_map(|(platform, problems)| {
+            problems
+                .into_iter()
+                .filter(|p| !problems_on_all_platforms.contains(p))
+                .map(move |message| RecipeProblem {
+                    platform: Some(platform),
+                    message,
+                })
+        })
+        .collect();
+    common_problems
+        .into_iter()
+        .chain(problems_on_all_platforms)
+        .map(|message| RecipeProblem {
+            platform: None,
+            message,
+        })
+        .chain(platform_specific_problems)
+        .collect()
+}
+
+async fn download_indexes(
+    repository_urls: HashSet<&Url>,
+    downloader: &Downloader,
+    temp_dir: &Path,
+) -> (HashMap<Url, DownloadedIndex>, Vec<String>) {
+    let futures = repository_urls
+        .into_iter()
+        .enumerate()
+        .map(|(i, url)| async move {
+            let file = temp_dir.join(format!("{i}.xml"));
+            let download = Download::new(url.to_string(), url.clone(), file.clone(), None);
+            let result = async {
+                downloader.download(download, |_| {}).await?;
+                let index = Index::parse(BufReader::new(fs::File::open(&file)?))?;
+                anyhow::Ok(index)
+            }
+            .await;
+            (url, file, result)
+        });
+    let mut indexes = HashMap::new();
+    let mut problems = vec![];
+    for (url, file, result) in join_all(futures).await {
+        match result {
+            Ok(index) => {
+                let downloaded_index = DownloadedIndex {
+                    url: url.clone(),
+                    temp_download_file: file,
+                    name: index.name.clone().unwrap_or_default(),
+                    index,
+                };
+                indexes.insert(url.clone(), downloaded_index);
+            }
+            Err(e) => problems.push(format!("Repository index {url} is unavailable: {e:#}")),
+        }
+    }
+    (indexes, problems)
+}
+
+fn describe_failures(failures: PreDownloadFailures) -> BTreeSet<String> {
+    let mut problems = BTreeSet::new();
+    for failure in failures.package_descriptors_with_failures {
+        // Unavailable indexes have already been reported
+        if matches!(failure.error, PackageDescError::RepositoryIndexUnavailable) {
+            continue;
+        }
+        let url = failure.package_url;
+        let version = match url.version_ref() {
+            VersionRef::Specific(v) => format!(" {v}"),
+            _ => String::new(),
+        };
+        problems.insert(format!(
+            "Package `{}/{}`{version} from {}: {}",
+            url.category(),
+            url.package_name(),
+            url.repository_url(),
+            failure.error
+        ));
+    }
+    for conflict in failures.version_conflicts {
+        let versions: Vec<_> = conflict
+            .conflicting_versions
+            .iter()
+            .map(|v| v.version.name.to_string())
+            .collect();
+        problems.insert(format!(
+            "Package `{}` is required in competing versions: {}",
+            conflict.package_id,
+            versions.join(", ")
+        ));
+    }
+    for version in failures.incompatible_versions {
+        problems.insert(format!(
+            "Package `{}` is not available for this platform",
+            version.id()
+        ));
+    }
+    for conflict in failures.recipe_file_conflicts {
+        let packages: BTreeSet<_> = conflict
+            .conflicting_files
+            .iter()
+            .map(|f| f.version.id().to_string())
+            .collect();
+        problems.insert(format!(
+            "Packages {} would install the same file `{}`",
+            packages
+                .iter()
+                .map(|p| format!("`{p}`"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            conflict.relative_path
+        ));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_recipe_json_reports_violations() {
+        let valid = r#"{ "name": "Test", "required_packages": ["https://example.org/index.xml#p=Foo/bar.lua"] }"#;
+        assert!(validate_recipe_json(valid).unwrap().is_empty());
+        let invalid = r#"{ "required_packages": "not-a-list" }"#;
+        assert_eq!(validate_recipe_json(invalid).unwrap().len(), 2);
+        assert!(validate_recipe_json("no json").is_err());
+    }
+}
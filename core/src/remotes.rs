@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context};
+use tempdir::TempDir;
+use url::Url;
+
+use reaboot_reapack::model::{Config, Remote};
+
+use crate::api::RemoteInfo;
+use crate::installation_journal::InstallationJournal;
+use crate::installer::REABOOT_TEMP_DIR_PREFIX;
+use crate::reaboot_util;
+use crate::reaper_resource_dir::ReaperResourceDir;
+
+/// A change to the remotes configured in `reapack.ini`.
+#[derive(Clone, Debug)]
+pub enum RemoteChange {
+    /// Adds a new remote.
+    ///
+    /// If a remote with the same name and URL exists already, its settings are updated.
+    Add {
+        name: String,
+        url: Url,
+        enabled: bool,
+        /// `None` means that the global ReaPack setting applies.
+        auto_install: Option<bool>,
+    },
+    /// Removes the remote with the given name.
+    ///
+    /// Packages installed from this remote are left untouched.
+    Remove { name: String },
+    /// Enables or disables the remote with the given name.
+    SetEnabled { name: String, enabled: bool },
+}
+
+/// Returns the remotes configured in `reapack.ini`.
+///
+/// If `reapack.ini` doesn't exist yet, returns the remotes that ReaPack would create by default.
+pub fn list_remotes(reaper_resource_dir: &ReaperResourceDir) -> anyhow::Result<Vec<RemoteInfo>> {
+    let config = load_config(&reaper_resource_dir.reapack_ini_file())?;
+    let remotes = config
+        .remote_by_name
+        .values()
+        .map(|r| RemoteInfo {
+            name: r.name.clone(),
+            url: r.url.to_string(),
+            enabled: r.enabled,
+            auto_install: r.auto_install,
+        })
+        .collect();
+    Ok(remotes)
+}
+
+/// Applies the given change to the remotes in `reapack.ini` and returns the backup directory.
+///
+/// Other settings in `reapack.ini` and its encoding are preserved. Just like the installer, this
+/// works on a temporary copy of `reapack.ini` and only replaces the original (with backup) if
+/// everything went fine. The change can be reverted using the installation ID.
+pub async fn change_remotes(
+    reaper_resource_dir: &ReaperResourceDir,
+    change: RemoteChange,
+    installation_id: Option<String>,
+) -> anyhow::Result<PathBuf> {
+    // ReaPack writes reapack.ini when REAPER exits, which would undo our change
+    reaboot_util::complain_if_reapack_db_busy(reaper_resource_dir).await?;
+    let ini_file = reaper_resource_dir.reapack_ini_file();
+    let mut config = load_config(&ini_file)?;
+    apply_change(&mut config, change)?;
+    // Work on a copy of the INI file
+    let temp_parent_dir = reaper_resource_dir.temp_reaboot_dir();
+    fs::create_dir_all(&temp_parent_dir)?;
+    let temp_dir = TempDir::new_in(&temp_parent_dir, REABOOT_TEMP_DIR_PREFIX)
+        .context("couldn't create temp directory")?;
+    let temp_ini_file = temp_dir.path().join("reapack.ini");
+    if ini_file.exists() {
+        fs::copy(&ini_file, &temp_ini_file).context("couldn't copy reapack.ini file")?;
+    }
+    config.apply_to_ini_file(&temp_ini_file)?;
+    // Replace original file
+    let backup_dir = match installation_id {
+        Some(id) => {
+            let dir = reaper_resource_dir.backup_parent_dir().join(id);
+            ensure!(!dir.exists(), "Backup directory {dir:?} exists already");
+            dir
+        }
+        None => unique_default_backup_dir(reaper_resource_dir),
+    };
+    let mut journal = InstallationJournal::new(reaper_resource_dir.clone(), backup_dir.clone());
+    let backup_file = journal.config_file_backup(&ini_file)?;
+    if let Err(error) =
+        journal.move_file_overwriting_with_backup(&temp_ini_file, &ini_file, backup_file)
+    {
+        return match journal.rollback() {
+            Ok(_) => {
+                Err(error.context("changing remotes failed but the change has been rolled back"))
+            }
+            Err(rollback_error) => Err(error.context(format!(
+                "changing remotes failed and rolling back failed as well: {rollback_error:#}"
+            ))),
+        };
+    }
+    // Persist journal, which makes it possible to restore the previous state later
+    if let Err(e) = journal.commit() {
+        tracing::warn!(msg = "Couldn't persist remote change journal", %e);
+    }
+    Ok(backup_dir)
+}
+
+/// Returns a timestamp-based backup directory, adding a counter if changes are made within the
+/// same second.
+fn unique_default_backup_dir(reaper_resource_dir: &ReaperResourceDir) -> PathBuf {
+    let id = reaboot_util::create_default_installation_id();
+    let backup_parent_dir = reaper_resource_dir.backup_parent_dir();
+    (1..)
+        .map(|i| {
+            let dir_name = if i == 1 {
+                id.clone()
+            } else {
+                format!("{id}-{i}")
+            };
+            backup_parent_dir.join(dir_name)
+        })
+        .find(|dir| !dir.exists())
+        .expect("infinite iterator")
+}
+
+pub(crate) fn load_config(ini_file: &Path) -> anyhow::Result<Config> {
+    if !ini_file.exists() {
+        return Ok(Config::default());
+    }
+    let mut config = Config::load_from_ini_file(ini_file)?;
+    config.migrate();
+    Ok(config)
+}
+
+fn apply_change(config: &mut Config, change: RemoteChange) -> anyhow::Result<()> {
+    match change {
+        RemoteChange::Add {
+            name,
+            url,
+            enabled,
+            auto_install,
+        } => {
+            if let Some(existing) = config.remote_by_name.get(&name) {
+                if existing.url != url {
+                    bail!(
+                        "A remote named {name:?} exists already with a different URL ({})",
+                        existing.url
+                    );
+                }
+            }
+            config.add_remote(Remote {
+                name,
+                url,
+                enabled,
+                auto_install,
+            });
+        }
+        RemoteChange::Remove { name } => {
+            if config.remote_by_name.shift_remove(&name).is_none() {
+                bail!("There's no remote named {name:?}");
+            }
+        }
+        RemoteChange::SetEnabled { name, enabled } => {
+            let Some(remote) = config.remote_by_name.get_mut(&name) else {
+                bail!("There's no remote named {name:?}");
+            };
+            remote.enabled = enabled;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_changes() {
+        let mut config = Config::default();
+        let url: Url = "https://example.org/index.xml".parse().unwrap();
+        let add = |enabled| RemoteChange::Add {
+            name: "Example".to_string(),
+            url: url.clone(),
+            enabled,
+            auto_install: Some(true),
+        };
+        apply_change(&mut config, add(true)).unwrap();
+        apply_change(&mut config, add(false)).unwrap();
+        assert!(!config.remote_by_name["Example"].enabled);
+        let conflicting_add = RemoteChange::Add {
+            name: "Example".to_string(),
+            url: "https://example.org/other.xml".parse().unwrap(),
+            enabled: true,
+            auto_install: None,
+        };
+        assert!(apply_change(&mut config, conflicting_add).is_err());
+        let enable = RemoteChange::SetEnabled {
+            name: "Example".to_string(),
+            enabled: true,
+        };
+        apply_change(&mut config, enable).unwrap();
+        assert!(config.remote_by_name["Example"].enabled);
+        let remove = || RemoteChange::Remove {
+            name: "Example".to_string(),
+        };
+        apply_change(&mut config, remove()).unwrap();
+        assert!(!config.remote_by_name.contains_key("Example"));
+        assert!(apply_change(&mut config, remove()).is_err());
+    }
+}
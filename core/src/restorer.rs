@@ -0,0 +1,425 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Context};
+
+use crate::installation_journal::{
+    InstallationJournal, JournalEntry, JournalFile, FILE_BACKUP_DIR_NAME, JOURNAL_FILE_NAME,
+};
+use crate::reaboot_util;
+use crate::reaper_resource_dir::{
+    ReaperResourceDir, REAPACK_CACHE_DIR_PATH, REAPACK_INI_FILE_PATH, REAPACK_REGISTRY_DB_FILE_PATH,
+};
+
+/// Configuration of a restoration.
+#[derive(Clone, Debug, Default)]
+pub struct RestorerConfig {
+    /// Custom REAPER resource directory (by default the main resource directory).
+    pub custom_reaper_resource_dir: Option<PathBuf>,
+    /// ID of the past installation whose changes should be reverted.
+    pub installation_id: String,
+    /// Name of the backup directory for the files replaced by the restoration itself (by default
+    /// a timestamp).
+    ///
+    /// Because the restoration gets a backup directory as well, it can be reverted, too.
+    pub restoration_id: Option<String>,
+    /// If `true`, checks everything, but doesn't touch the REAPER resource directory.
+    pub dry_run: bool,
+}
+
+/// A past installation, uninstallation or restoration whose backup directory still exists.
+#[derive(Clone, Debug)]
+pub struct PastInstallation {
+    /// Installation ID, which is the name of the backup directory.
+    pub id: String,
+    pub backup_dir: PathBuf,
+    /// When the changes have been applied.
+    ///
+    /// If the backup directory doesn't contain a journal, this is the modification time of the
+    /// backup directory.
+    pub timestamp: Option<jiff::Timestamp>,
+    /// Changes made to the REAPER resource directory.
+    ///
+    /// If the backup directory doesn't contain a journal (created by older ReaBoot versions),
+    /// these changes are derived from the backed-up files. In this case, files that have been
+    /// newly added are unknown.
+    pub changes: Vec<JournalEntry>,
+    /// Whether the backup directory contains a journal.
+    pub has_journal: bool,
+}
+
+/// What happened (or would happen, in dry mode) during a restoration.
+#[derive(Debug)]
+pub struct RestorationReport {
+    /// ID of the past installation that has been reverted.
+    pub installation_id: String,
+    /// Files that have been restored from the backup (relative to the REAPER resource directory).
+    pub restored_files: Vec<String>,
+    /// Files that have been added by the past installation and therefore have been removed
+    /// (relative to the REAPER resource directory).
+    pub removed_files: Vec<String>,
+    /// Directory which contains the backup of all files replaced or removed by the restoration.
+    pub backup_dir: PathBuf,
+    /// Whether this was just a dry run.
+    pub dry_run: bool,
+}
+
+/// Returns all past installations whose backup directories still exist, oldest first.
+///
+/// Installations whose journal can't be loaded are skipped with a warning.
+pub fn list_past_installations(
+    reaper_resource_dir: &ReaperResourceDir,
+) -> anyhow::Result<Vec<PastInstallation>> {
+    let backup_parent_dir = reaper_resource_dir.backup_parent_dir();
+    if !backup_parent_dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut installations = vec![];
+    for entry in fs::read_dir(&backup_parent_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let Some(id) = entry.file_name().to_str().map(|n| n.to_string()) else {
+            continue;
+        };
+        match load_past_installation(reaper_resource_dir, id) {
+            Ok(installation) => installations.push(installation),
+            Err(e) => {
+                tracing::warn!(msg = "Skipping past installation", %e);
+            }
+        }
+    }
+    installations.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+    Ok(installations)
+}
+
+/// Loads the past installation with the given ID.
+pub fn load_past_installation(
+    reaper_resource_dir: &ReaperResourceDir,
+    id: String,
+) -> anyhow::Result<PastInstallation> {
+    let backup_dir = reaper_resource_dir.backup_parent_dir().join(&id);
+    ensure!(
+        backup_dir.is_dir(),
+        "There's no backup for installation {id:?}"
+    );
+    let installation = match JournalFile::load_from_dir(&backup_dir)
+        .with_context(|| format!("couldn't load journal of installation {id:?}"))?
+    {
+        None => {
+            let timestamp = fs::metadata(&backup_dir)
+                .and_then(|md| md.modified())
+                .ok()
+                .and_then(|t| jiff::Timestamp::try_from(t).ok());
+            PastInstallation {
+                id,
+                changes: derive_changes_from_backup_files(&backup_dir)?,
+                backup_dir,
+                timestamp,
+                has_journal: false,
+            }
+        }
+        Some(journal) => PastInstallation {
+            id,
+            backup_dir,
+            timestamp: Some(journal.timestamp),
+            changes: journal.entries,
+            has_journal: true,
+        },
+    };
+    Ok(installation)
+}
+
+/// Reverts the changes of the given past installation, restoring all files from its backup
+/// directory and removing files that it added.
+///
+/// Directories created by the past installation are left in place. Just like installations,
+/// restorations are rolled back if something fails and can be reverted themselves.
+pub async fn restore_installation(config: RestorerConfig) -> anyhow::Result<RestorationReport> {
+    let (reaper_resource_dir, _) =
+        reaboot_util::resolve_reaper_resource_dir(config.custom_reaper_resource_dir)?;
+    reaboot_util::complain_if_reapack_db_busy(&reaper_resource_dir).await?;
+    let installation = load_past_installation(&reaper_resource_dir, config.installation_id)?;
+    let restoration_id = config
+        .restoration_id
+        .unwrap_or_else(reaboot_util::create_default_installation_id);
+    ensure!(
+        restoration_id != installation.id,
+        "Can't use the ID of the restored installation as ID for the restoration"
+    );
+    let backup_dir = reaper_resource_dir.backup_parent_dir().join(restoration_id);
+    ensure!(
+        !backup_dir.exists(),
+        "Backup directory {backup_dir:?} exists already"
+    );
+    let mut report = RestorationReport {
+        installation_id: installation.id.clone(),
+        restored_files: vec![],
+        removed_files: vec![],
+        backup_dir: backup_dir.clone(),
+        dry_run: config.dry_run,
+    };
+    // Plan
+    let mut steps = vec![];
+    for change in installation.changes.iter().rev() {
+        let step = match change {
+            JournalEntry::CreatedDir { .. } => continue,
+            JournalEntry::PlacedFile {
+                path,
+                backup: Some(backup),
+            }
+            | JournalEntry::RemovedFile { path, backup } => {
+                let src_file = installation.backup_dir.join(backup);
+                ensure!(
+                    src_file.exists(),
+                    "Backup file {src_file:?} doesn't exist anymore"
+                );
+                report.restored_files.push(path.clone());
+                RestorationStep::Restore { path, src_file }
+            }
+            JournalEntry::PlacedFile { path, backup: None } => {
+                report.removed_files.push(path.clone());
+                RestorationStep::Remove { path }
+            }
+        };
+        steps.push(step);
+    }
+    if config.dry_run {
+        return Ok(report);
+    }
+    // Apply, rolling back all changes if something fails
+    let mut journal = InstallationJournal::new(reaper_resource_dir.clone(), backup_dir);
+    let apply_result = apply_steps(&mut journal, &reaper_resource_dir, steps);
+    if let Err(error) = apply_result {
+        return match journal.rollback() {
+            Ok(_) => Err(error.context("restoration failed but all changes have been rolled back")),
+            Err(rollback_error) => Err(error.context(format!(
+                "restoration failed and rolling back failed as well: {rollback_error:#}"
+            ))),
+        };
+    }
+    journal.commit()?;
+    Ok(report)
+}
+
+enum RestorationStep<'a> {
+    Restore { path: &'a str, src_file: PathBuf },
+    Remove { path: &'a str },
+}
+
+fn apply_steps(
+    journal: &mut InstallationJournal,
+    reaper_resource_dir: &ReaperResourceDir,
+    steps: Vec<RestorationStep>,
+) -> anyhow::Result<()> {
+    for step in steps {
+        match step {
+            RestorationStep::Restore { path, src_file } => {
+                journal
+                    .copy_file_overwriting_with_backup(
+                        &src_file,
+                        &reaper_resource_dir.join(path),
+                        journal.file_backup(path),
+                    )
+                    .with_context(|| format!("couldn't restore file {path:?}"))?;
+            }
+            RestorationStep::Remove { path } => {
+                journal
+                    .remove_file_with_backup(
+                        &reaper_resource_dir.join(path),
+                        journal.file_backup(path),
+                    )
+                    .with_context(|| format!("couldn't remove file {path:?}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Derives changes from the files in a backup directory that doesn't contain a journal.
+///
+/// Older ReaBoot versions backed up only the ReaPack configuration files and cached repository
+/// indexes, flat within the backup directory.
+fn derive_changes_from_backup_files(backup_dir: &Path) -> anyhow::Result<Vec<JournalEntry>> {
+    let mut changes = vec![];
+    for entry in fs::read_dir(backup_dir)? {
+        let entry = entry?;
+        let Some(file_name) = entry.file_name().to_str().map(|n| n.to_string()) else {
+            continue;
+        };
+        if entry.file_type()?.is_dir() {
+            if file_name == FILE_BACKUP_DIR_NAME {
+                collect_file_backups(&entry.path(), "", &mut changes)?;
+            }
+            continue;
+        }
+        let path = match file_name.as_str() {
+            JOURNAL_FILE_NAME => continue,
+            "reapack.ini" => REAPACK_INI_FILE_PATH.to_string(),
+            "registry.db" => REAPACK_REGISTRY_DB_FILE_PATH.to_string(),
+            n if n.ends_with(".xml") => format!("{REAPACK_CACHE_DIR_PATH}/{n}"),
+            _ => continue,
+        };
+        changes.push(JournalEntry::PlacedFile {
+            path,
+            backup: Some(file_name),
+        });
+    }
+    Ok(changes)
+}
+
+fn collect_file_backups(
+    dir: &Path,
+    relative_dir: &str,
+    changes: &mut Vec<JournalEntry>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some(file_name) = entry.file_name().to_str().map(|n| n.to_string()) else {
+            continue;
+        };
+        let path = if relative_dir.is_empty() {
+            file_name
+        } else {
+            format!("{relative_dir}/{file_name}")
+        };
+        if entry.file_type()?.is_dir() {
+            collect_file_backups(&entry.path(), &path, changes)?;
+        } else {
+            changes.push(JournalEntry::PlacedFile {
+                backup: Some(format!("{FILE_BACKUP_DIR_NAME}/{path}")),
+                path,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub struct PastInstallationsAsMarkdown<'a> {
+    installations: &'a [PastInstallation],
+}
+
+impl<'a> PastInstallationsAsMarkdown<'a> {
+    pub fn new(installations: &'a [PastInstallation]) -> Self {
+        Self { installations }
+    }
+}
+
+impl<'a> Display for PastInstallationsAsMarkdown<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "\n# Past installations")?;
+        if self.installations.is_empty() {
+            writeln!(f, "\nNo backups available.")?;
+            return Ok(());
+        }
+        for installation in self.installations {
+            let time = match installation.timestamp {
+                None => "unknown time".to_string(),
+                Some(t) => t
+                    .to_zoned(jiff::tz::TimeZone::system())
+                    .strftime("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            };
+            writeln!(f, "\n## {} ({time})", installation.id)?;
+            if !installation.has_journal {
+                writeln!(
+                    f,
+                    "\n*No journal available. Added files are unknown and won't be removed when restoring.*\n"
+                )?;
+            }
+            for change in &installation.changes {
+                match change {
+                    JournalEntry::CreatedDir { .. } => {}
+                    JournalEntry::PlacedFile {
+                        path,
+                        backup: Some(_),
+                    } => writeln!(f, "- Replaced `{path}`")?,
+                    JournalEntry::PlacedFile { path, backup: None } => {
+                        writeln!(f, "- Added `{path}`")?
+                    }
+                    JournalEntry::RemovedFile { path, .. } => writeln!(f, "- Removed `{path}`")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct RestorationReportAsMarkdown<'a> {
+    report: &'a RestorationReport,
+}
+
+impl<'a> RestorationReportAsMarkdown<'a> {
+    pub fn new(report: &'a RestorationReport) -> Self {
+        Self { report }
+    }
+}
+
+impl<'a> Display for RestorationReportAsMarkdown<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "\n# Restoration report for installation {}",
+            self.report.installation_id
+        )?;
+        let skipped_suffix = if self.report.dry_run {
+            " **[SKIPPED]**"
+        } else {
+            ""
+        };
+        let restored = &self.report.restored_files;
+        if !restored.is_empty() {
+            writeln!(
+                f,
+                "\n## {} restored file(s){skipped_suffix}",
+                restored.len()
+            )?;
+            for path in restored {
+                writeln!(f, "- `{path}`")?;
+            }
+        }
+        let removed = &self.report.removed_files;
+        if !removed.is_empty() {
+            writeln!(f, "\n## {} removed file(s){skipped_suffix}", removed.len())?;
+            for path in removed {
+                writeln!(f, "- `{path}`")?;
+            }
+        }
+        if restored.is_empty() && removed.is_empty() {
+            writeln!(f, "\nNothing to restore.")?;
+        } else if !self.report.dry_run {
+            writeln!(
+                f,
+                "\nReplaced files have been backed up to `{}`.",
+                self.report.backup_dir.display()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installation_journal::JOURNAL_FILE_NAME;
+
+    #[test]
+    fn skip_installations_with_corrupt_journal() {
+        let dir = tempdir::TempDir::new("reaboot-test-").unwrap();
+        fs::write(dir.path().join("reaper.ini"), "").unwrap();
+        let reaper_resource_dir = ReaperResourceDir::new(dir.path().to_path_buf()).unwrap();
+        let backup_parent_dir = reaper_resource_dir.backup_parent_dir();
+        fs::create_dir_all(backup_parent_dir.join("good")).unwrap();
+        fs::create_dir_all(backup_parent_dir.join("corrupt")).unwrap();
+        fs::write(
+            backup_parent_dir.join("corrupt").join(JOURNAL_FILE_NAME),
+            "{ not json",
+        )
+        .unwrap();
+        let installations = list_past_installations(&reaper_resource_dir).unwrap();
+        let ids: Vec<_> = installations.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["good"]);
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, SystemTime};
+
+use http::Extensions;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::{
+    default_on_request_failure, default_on_request_success, RetryDecision, RetryPolicy, Retryable,
+};
+
+/// We don't wait longer than that, no matter what the server says.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// Retries requests that failed for transient reasons.
+///
+/// Works like `RetryTransientMiddleware` with exponential backoff, but if the server tells us
+/// when to come back (`Retry-After` header, e.g. with "429 Too Many Requests"), it waits exactly
+/// that long.
+pub(crate) struct RetryMiddleware {
+    policy: ExponentialBackoff,
+    max_retries: u32,
+}
+
+impl RetryMiddleware {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            policy: ExponentialBackoff::builder().build_with_max_retries(max_retries),
+            max_retries,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let start_time = SystemTime::now();
+        let mut num_past_retries = 0;
+        loop {
+            // Requests with streaming bodies can't be cloned and therefore not be retried
+            let Some(duplicate_req) = req.try_clone() else {
+                return next.run(req, extensions).await;
+            };
+            let result = next.clone().run(duplicate_req, extensions).await;
+            let retryable = match &result {
+                Ok(res) => default_on_request_success(res),
+                Err(e) => default_on_request_failure(e),
+            };
+            if retryable != Some(Retryable::Transient) || num_past_retries >= self.max_retries {
+                return result;
+            }
+            let retry_after = result
+                .as_ref()
+                .ok()
+                .and_then(|res| get_retry_after(res.headers()));
+            let delay = match retry_after {
+                Some(d) => d.min(MAX_RETRY_AFTER),
+                None => match self.policy.should_retry(start_time, num_past_retries) {
+                    RetryDecision::Retry { execute_after } => execute_after
+                        .duration_since(SystemTime::now())
+                        .unwrap_or_default(),
+                    RetryDecision::DoNotRetry => return result,
+                },
+            };
+            tracing::debug!(
+                msg = "Retrying request",
+                url = %req.url(),
+                num_past_retries,
+                ?delay
+            );
+            tokio::time::sleep(delay).await;
+            num_past_retries += 1;
+        }
+    }
+}
+
+/// Parses the `Retry-After` header, which contains either a number of seconds or an HTTP date.
+fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    static PARSER: jiff::fmt::rfc2822::DateTimeParser = jiff::fmt::rfc2822::DateTimeParser::new();
+    let timestamp = PARSER.parse_timestamp(value).ok()?;
+    let delay = SystemTime::from(timestamp)
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+    Some(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(get_retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(get_retry_after(&headers), Some(Duration::from_secs(30)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(get_retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(get_retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,347 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+use futures::future::join_all;
+
+use reaboot_reapack::index::{Category, Index, IndexPackageType, Package};
+use reaboot_reapack::model::{
+    PackagePath, PackageType, PackageUrl, PackageVersionRef, Remote, VersionRef,
+};
+
+use crate::api::PackageSearchResult;
+use crate::downloader::{Download, Downloader};
+use crate::reaper_resource_dir::ReaperResourceDir;
+use crate::remotes::load_config;
+
+/// Minimum Jaro-Winkler similarity for a term to match a word fuzzily.
+const FUZZY_THRESHOLD: f64 = 0.85;
+
+/// Terms shorter than this only match exactly (as substring), otherwise fuzzy matching would
+/// produce way too many false positives.
+const MIN_FUZZY_TERM_LENGTH: usize = 3;
+
+/// Criteria for searching packages in the repository indexes of the configured remotes.
+#[derive(Clone, Debug, Default)]
+pub struct PackageSearchQuery {
+    /// Whitespace-separated search terms, all of which must match (case-insensitive).
+    ///
+    /// Each term is matched against the package name, display name, category, author and
+    /// description. Terms that don't appear literally can still match similar words (fuzzy
+    /// matching). If empty, all packages match.
+    pub text: String,
+    /// If not empty, only packages of one of these types are included.
+    pub types: Vec<PackageType>,
+    /// If not empty, only packages from one of these remotes are included (case-insensitive).
+    pub remotes: Vec<String>,
+    /// If `true`, considers pre-releases when determining the latest version.
+    ///
+    /// If `None`, the pre-release setting in `reapack.ini` applies.
+    pub include_pre_releases: Option<bool>,
+}
+
+/// Result of a package search.
+#[derive(Clone, Debug, Default)]
+pub struct PackageSearchOutcome {
+    /// Matching packages, the most relevant ones first.
+    pub results: Vec<PackageSearchResult>,
+    /// Problems with individual remotes, e.g. indexes that couldn't be loaded.
+    pub problems: Vec<String>,
+}
+
+/// Searches the repository indexes of all enabled remotes configured in `reapack.ini`.
+///
+/// Indexes are downloaded into `temp_dir`. If the downloader is `None` or a download fails,
+/// ReaBoot falls back to the index that ReaPack has cached in the REAPER resource directory.
+pub async fn search_packages(
+    reaper_resource_dir: &ReaperResourceDir,
+    downloader: Option<&Downloader>,
+    temp_dir: &Path,
+    query: &PackageSearchQuery,
+) -> anyhow::Result<PackageSearchOutcome> {
+    let config = load_config(&reaper_resource_dir.reapack_ini_file())?;
+    let include_pre_releases = query
+        .include_pre_releases
+        .or(config.install.pre_releases)
+        .unwrap_or(false);
+    let remotes: Vec<&Remote> = config
+        .remote_by_name
+        .values()
+        .filter(|r| {
+            r.enabled
+                && (query.remotes.is_empty()
+                    || query
+                        .remotes
+                        .iter()
+                        .any(|q| q.eq_ignore_ascii_case(&r.name)))
+        })
+        .collect();
+    let cache_dir = reaper_resource_dir.reapack_cache_dir();
+    let futures = remotes.into_iter().enumerate().map(|(i, remote)| {
+        let cache_file = cache_dir.join(format!("{}.xml", remote.name));
+        async move {
+            let mut problems = vec![];
+            let downloaded = match downloader {
+                None => None,
+                Some(downloader) => {
+                    let file = temp_dir.join(format!("{i}.xml"));
+                    let download =
+                        Download::new(remote.name.clone(), remote.url.clone(), file.clone(), None);
+                    let result = async {
+                        downloader.download(download, |_| {}).await?;
+                        parse_index_file(&file)
+                    }
+                    .await;
+                    match result {
+                        Ok(index) => Some(index),
+                        Err(e) => {
+                            problems.push(format!(
+                                "Repository index of remote {:?} couldn't be downloaded: {e:#}",
+                                remote.name
+                            ));
+                            None
+                        }
+                    }
+                }
+            };
+            let index = match downloaded {
+                Some(index) => Some(index),
+                None => match parse_index_file(&cache_file) {
+                    Ok(index) => Some(index),
+                    Err(e) => {
+                        problems.push(format!(
+                            "Cached repository index of remote {:?} is unavailable: {e:#}",
+                            remote.name
+                        ));
+                        None
+                    }
+                },
+            };
+            (remote, index, problems)
+        }
+    });
+    let mut outcome = PackageSearchOutcome::default();
+    for (remote, index, problems) in join_all(futures).await {
+        outcome.problems.extend(problems);
+        if let Some(index) = index {
+            outcome
+                .results
+                .extend(search_index(remote, &index, query, include_pre_releases));
+        }
+    }
+    outcome.results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.remote.cmp(&b.remote))
+            .then_with(|| a.category.cmp(&b.category))
+            .then_with(|| a.package.cmp(&b.package))
+    });
+    Ok(outcome)
+}
+
+fn parse_index_file(file: &Path) -> anyhow::Result<Index> {
+    let index = Index::parse(BufReader::new(fs::File::open(file)?))?;
+    Ok(index)
+}
+
+fn search_index(
+    remote: &Remote,
+    index: &Index,
+    query: &PackageSearchQuery,
+    include_pre_releases: bool,
+) -> Vec<PackageSearchResult> {
+    let terms: Vec<String> = query
+        .text
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .collect();
+    index
+        .categories()
+        .flat_map(|category| category.packages.iter().map(move |p| (category, p)))
+        .filter_map(|(category, package)| {
+            let type_matches = query.types.is_empty()
+                || matches!(package.typ, IndexPackageType::Known(t) if query.types.contains(&t));
+            if !type_matches {
+                return None;
+            }
+            let version = if include_pre_releases {
+                package.latest_version_including_pre_releases()?
+            } else {
+                package.latest_stable_version()?
+            };
+            let author = version.author.as_deref();
+            let score = score_package(category, package, author, &terms)?;
+            let version_ref = if include_pre_releases {
+                VersionRef::LatestPre
+            } else {
+                VersionRef::Latest
+            };
+            let package_url = PackageUrl {
+                repository_url: remote.url.clone(),
+                package_version_ref: PackageVersionRef {
+                    package_path: PackagePath {
+                        category: category.name.clone(),
+                        package_name: package.name.clone(),
+                    },
+                    version_ref,
+                },
+            };
+            let typ = match &package.typ {
+                IndexPackageType::Known(t) => t.to_string(),
+                IndexPackageType::Unknown(t) => t.clone(),
+            };
+            let result = PackageSearchResult {
+                remote: remote.name.clone(),
+                category: category.name.clone(),
+                package: package.name.clone(),
+                description: package.desc.clone(),
+                typ,
+                version: version.name.to_string(),
+                author: author.map(|a| a.to_string()),
+                package_url: package_url.to_string(),
+                score,
+            };
+            Some(result)
+        })
+        .collect()
+}
+
+/// Returns the relevance of the given package or `None` if not all terms match.
+fn score_package(
+    category: &Category,
+    package: &Package,
+    author: Option<&str>,
+    terms: &[String],
+) -> Option<f64> {
+    let metadata_description = package.metadata().and_then(|m| m.description());
+    let weighted_fields: Vec<(String, f64)> = [
+        (Some(package.name.as_str()), 4.0),
+        (package.desc.as_deref(), 3.0),
+        (Some(category.name.as_str()), 2.0),
+        (author, 2.0),
+        (metadata_description, 1.0),
+    ]
+    .into_iter()
+    .filter_map(|(field, weight)| Some((field?.to_lowercase(), weight)))
+    .collect();
+    terms.iter().try_fold(0.0, |sum, term| {
+        let best = weighted_fields
+            .iter()
+            .map(|(field, weight)| weight * score_term(field, term))
+            .fold(0.0, f64::max);
+        (best > 0.0).then_some(sum + best)
+    })
+}
+
+/// Scores a single lowercase term against a lowercase field value (0.0 means no match).
+fn score_term(field: &str, term: &str) -> f64 {
+    let mut substring_score: f64 = 0.0;
+    for (i, _) in field.match_indices(term) {
+        let starts_word = field[..i]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        substring_score = substring_score.max(if starts_word { 1.0 } else { 0.8 });
+    }
+    if substring_score > 0.0 {
+        return substring_score;
+    }
+    if term.chars().count() < MIN_FUZZY_TERM_LENGTH {
+        return 0.0;
+    }
+    let similarity = field
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| strsim::jaro_winkler(w, term))
+        .fold(0.0, f64::max);
+    if similarity >= FUZZY_THRESHOLD {
+        similarity * 0.6
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_index_matches_and_ranks() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<index version="1" name="Test">
+  <category name="Various">
+    <package name="Color palette.lua" type="script" desc="Color palette">
+      <version name="1.0" author="Rodilab"><source>https://example.org/a.lua</source></version>
+    </package>
+    <package name="Colorize tracks.lua" type="script">
+      <version name="2.0" author="Someone"><source>https://example.org/b.lua</source></version>
+      <metadata><description>Paints tracks</description></metadata>
+    </package>
+  </category>
+  <category name="Extensions">
+    <package name="Helgobox-x64" type="extension" desc="Helgobox: ReaLearn &amp; Playtime">
+      <version name="1.0"><source>https://example.org/c.dll</source></version>
+      <version name="1.1-pre1"><source>https://example.org/d.dll</source></version>
+    </package>
+  </category>
+</index>"#;
+        let index = Index::parse(xml.as_bytes()).unwrap();
+        let remote = Remote {
+            name: "Test".to_string(),
+            url: "https://example.org/index.xml".parse().unwrap(),
+            enabled: true,
+            auto_install: None,
+        };
+        let search = |text: &str, types: Vec<PackageType>, include_pre_releases: bool| {
+            let query = PackageSearchQuery {
+                text: text.to_string(),
+                types,
+                ..Default::default()
+            };
+            search_index(&remote, &index, &query, include_pre_releases)
+        };
+        let names = |results: &[PackageSearchResult]| -> Vec<String> {
+            let mut results = results.to_vec();
+            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            results.into_iter().map(|r| r.package).collect()
+        };
+        // Empty query matches everything
+        assert_eq!(search("", vec![], false).len(), 3);
+        // Name matches rank higher than metadata description matches
+        assert_eq!(
+            names(&search("col", vec![], false)),
+            ["Color palette.lua", "Colorize tracks.lua"]
+        );
+        assert_eq!(
+            names(&search("paints", vec![], false)),
+            ["Colorize tracks.lua"]
+        );
+        // All terms must match
+        assert_eq!(
+            names(&search("color rodilab", vec![], false)),
+            ["Color palette.lua"]
+        );
+        // Fuzzy matching
+        assert_eq!(names(&search("playtme", vec![], false)), ["Helgobox-x64"]);
+        assert!(search("xyz", vec![], false).is_empty());
+        // Type filter
+        assert_eq!(
+            names(&search("", vec![PackageType::Extension], false)),
+            ["Helgobox-x64"]
+        );
+        // Ready-to-use package URLs
+        let results = search("helgobox", vec![], true);
+        assert_eq!(results[0].version, "1.1-pre1");
+        assert_eq!(
+            results[0].package_url,
+            "https://example.org/index.xml#p=Extensions/Helgobox-x64&v=latest-pre"
+        );
+        let results = search("palette", vec![], false);
+        assert_eq!(
+            results[0].package_url,
+            "https://example.org/index.xml#p=Various/Color%20palette.lua&v=latest"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use tempdir::TempDir;
+
+use reaboot_reapack::database::Database;
+use reaboot_reapack::model::{Config, InstalledPackage, PackageId, PackageUrl};
+
+use crate::file_util::existing_file_or_dir_is_writable;
+use crate::installation_journal::InstallationJournal;
+use crate::installer::REABOOT_TEMP_DIR_PREFIX;
+use crate::reaboot_util;
+use crate::reaper_resource_dir::ReaperResourceDir;
+
+/// Configuration of an uninstallation.
+#[derive(Clone, Debug, Default)]
+pub struct UninstallerConfig {
+    /// Custom REAPER resource directory (by default the main resource directory).
+    pub custom_reaper_resource_dir: Option<PathBuf>,
+    /// ReaPack package URLs of the packages to be uninstalled.
+    ///
+    /// Version refs are ignored. The repository URL must belong to a remote that is configured
+    /// in `reapack.ini`.
+    pub package_urls: Vec<String>,
+    /// Package IDs of the packages to be uninstalled, in the form
+    /// `{REMOTE}/{CATEGORY}/{PACKAGE_NAME}`.
+    pub package_ids: Vec<String>,
+    /// Name of the backup directory for the replaced ReaPack database (by default a timestamp).
+    pub installation_id: Option<String>,
+    /// If `true`, checks everything, but doesn't touch the REAPER resource directory.
+    pub dry_run: bool,
+}
+
+/// What happened (or would happen, in dry mode) during an uninstallation.
+#[derive(Debug)]
+pub struct UninstallationReport {
+    /// Packages that have been removed.
+    pub removed_packages: Vec<InstalledPackage>,
+    /// Requested packages that were not installed in the first place.
+    pub not_installed_packages: Vec<PackageId>,
+    /// Directory which contains the backup of the ReaPack database and the removed package files.
+    pub backup_dir: PathBuf,
+    /// Whether this was just a dry run.
+    pub dry_run: bool,
+}
+
+/// Uninstalls the given ReaPack packages by removing their files and their entries in the
+/// ReaPack database.
+///
+/// Just like the installer, this works on a temporary copy of the ReaPack database and only
+/// replaces the original (with backup) if removing the entries succeeded.
+pub async fn uninstall_packages(config: UninstallerConfig) -> anyhow::Result<UninstallationReport> {
+    let (reaper_resource_dir, _) =
+        reaboot_util::resolve_reaper_resource_dir(config.custom_reaper_resource_dir)?;
+    reaboot_util::complain_if_reapack_db_too_new(&reaper_resource_dir).await?;
+    reaboot_util::complain_if_reapack_db_busy(&reaper_resource_dir).await?;
+    let package_ids = resolve_package_ids(
+        &reaper_resource_dir,
+        &config.package_urls,
+        &config.package_ids,
+    )?;
+    let installation_id = config
+        .installation_id
+        .unwrap_or_else(reaboot_util::create_default_installation_id);
+    let backup_dir = reaper_resource_dir
+        .backup_parent_dir()
+        .join(installation_id);
+    let mut report = UninstallationReport {
+        removed_packages: vec![],
+        not_installed_packages: vec![],
+        backup_dir,
+        dry_run: config.dry_run,
+    };
+    let db_file = reaper_resource_dir.reapack_registry_db_file();
+    if !db_file.exists() {
+        report.not_installed_packages = package_ids;
+        return Ok(report);
+    }
+    // Work on a copy of the database
+    let temp_parent_dir = reaper_resource_dir.temp_reaboot_dir();
+    fs::create_dir_all(&temp_parent_dir)?;
+    let temp_dir = TempDir::new_in(&temp_parent_dir, REABOOT_TEMP_DIR_PREFIX)
+        .context("couldn't create temp directory")?;
+    let temp_db_file = temp_dir.path().join("registry.db");
+    fs::copy(&db_file, &temp_db_file).context("couldn't copy ReaPack database")?;
+    let mut db = Database::open(&temp_db_file).await?;
+    // This might migrate the database, but it's just the copy
+    let installed_packages = db.installed_packages().await?;
+    for package_id in package_ids {
+        let installed_package = installed_packages
+            .iter()
+            .find(|p| p.package_id() == package_id.to_borrowed());
+        match installed_package {
+            None => report.not_installed_packages.push(package_id),
+            Some(p) => report.removed_packages.push(p.clone()),
+        }
+    }
+    let removed_packages = &report.removed_packages;
+    let resource_dir = &reaper_resource_dir;
+    db.with_transaction(|mut transaction| async move {
+        for p in removed_packages {
+            dry_remove_package_files(resource_dir, p)
+                .with_context(|| format!("can't remove files of package {p}"))?;
+            transaction.remove_package(p.package_id()).await?;
+        }
+        Ok(transaction)
+    })
+    .await?;
+    db.close().await?;
+    if config.dry_run || report.removed_packages.is_empty() {
+        return Ok(report);
+    }
+    // Apply, rolling back all changes if something fails
+    let mut journal =
+        InstallationJournal::new(reaper_resource_dir.clone(), report.backup_dir.clone());
+    let apply_result = apply_changes(
+        &mut journal,
+        &reaper_resource_dir,
+        &temp_db_file,
+        &report.removed_packages,
+    );
+    if let Err(error) = apply_result {
+        return match journal.rollback() {
+            Ok(_) => {
+                Err(error.context("uninstallation failed but all changes have been rolled back"))
+            }
+            Err(rollback_error) => Err(error.context(format!(
+                "uninstallation failed and rolling back failed as well: {rollback_error:#}"
+            ))),
+        };
+    }
+    // Persist journal, which makes it possible to restore the previous state later
+    if let Err(e) = journal.commit() {
+        tracing::warn!(msg = "Couldn't persist uninstallation journal", %e);
+    }
+    Ok(report)
+}
+
+fn apply_changes(
+    journal: &mut InstallationJournal,
+    reaper_resource_dir: &ReaperResourceDir,
+    temp_db_file: &Path,
+    removed_packages: &[InstalledPackage],
+) -> anyhow::Result<()> {
+    let db_file = reaper_resource_dir.reapack_registry_db_file();
+    journal
+        .move_file_overwriting_with_backup(
+            temp_db_file,
+            &db_file,
+            journal.config_file_backup(&db_file)?,
+        )
+        .context("moving ReaPack registry DB file failed")?;
+    for p in removed_packages {
+        remove_package_files(journal, reaper_resource_dir, p)
+            .with_context(|| format!("couldn't remove files of package {p}"))?;
+    }
+    Ok(())
+}
+
+/// Checks whether all files of the given installed package could be removed.
+pub(crate) fn dry_remove_package_files(
+    reaper_resource_dir: &ReaperResourceDir,
+    package: &InstalledPackage,
+) -> anyhow::Result<()> {
+    for file in &package.files {
+        dry_remove_file(&reaper_resource_dir.join(&file.path))?;
+    }
+    Ok(())
+}
+
+/// Removes all files of the given installed package by moving them into the backup directory.
+///
+/// Files that don't exist anymore are skipped.
+pub(crate) fn remove_package_files(
+    journal: &mut InstallationJournal,
+    reaper_resource_dir: &ReaperResourceDir,
+    package: &InstalledPackage,
+) -> anyhow::Result<()> {
+    for file in &package.files {
+        journal.remove_file_with_backup(
+            &reaper_resource_dir.join(&file.path),
+            journal.file_backup(&file.path),
+        )?;
+    }
+    Ok(())
+}
+
+fn dry_remove_file(path: &Path) -> anyhow::Result<()> {
+    if path.exists() && !existing_file_or_dir_is_writable(path) {
+        let suffix = if cfg!(windows) {
+            " Is REAPER running already? If yes, exit REAPER and try again."
+        } else {
+            ""
+        };
+        bail!("Removing the file would not work.{suffix}");
+    }
+    Ok(())
+}
+
+/// Converts the given package URLs and package ID expressions to package IDs.
+///
+/// Package URLs are mapped to package IDs by looking up the remote with a matching repository URL
+/// in `reapack.ini`.
+fn resolve_package_ids(
+    reaper_resource_dir: &ReaperResourceDir,
+    package_urls: &[String],
+    package_ids: &[String],
+) -> anyhow::Result<Vec<PackageId>> {
+    let mut resolved: Vec<PackageId> = package_ids
+        .iter()
+        .map(|id| {
+            id.parse()
+                .with_context(|| format!("couldn't parse package ID {id:?}"))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    if !package_urls.is_empty() {
+        let ini_file = reaper_resource_dir.reapack_ini_file();
+        let config = if ini_file.exists() {
+            Config::load_from_ini_file(&ini_file)?
+        } else {
+            Config::default()
+        };
+        for url in package_urls {
+            let package_url = PackageUrl::parse(url)
+                .with_context(|| format!("couldn't parse package URL {url:?}"))?;
+            let remote = config
+                .remote_by_name
+                .values()
+                .find(|r| &r.url == package_url.repository_url())
+                .with_context(|| {
+                    format!(
+                        "No ReaPack remote configured for repository {}",
+                        package_url.repository_url()
+                    )
+                })?;
+            let package_path = package_url.package_version_ref().package_path();
+            resolved.push(PackageId {
+                remote: remote.name.clone(),
+                category: package_path.category().to_string(),
+                package: package_path.package_name().to_string(),
+            });
+        }
+    }
+    // Remove duplicates while preserving order
+    let mut seen = HashSet::new();
+    resolved.retain(|id| seen.insert(id.clone()));
+    Ok(resolved)
+}
+
+pub struct UninstallationReportAsMarkdown<'a> {
+    report: &'a UninstallationReport,
+}
+
+impl<'a> UninstallationReportAsMarkdown<'a> {
+    pub fn new(report: &'a UninstallationReport) -> Self {
+        Self { report }
+    }
+}
+
+impl<'a> Display for UninstallationReportAsMarkdown<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "\n# Uninstallation report")?;
+        let skipped_suffix = if self.report.dry_run {
+            " **[SKIPPED]**"
+        } else {
+            ""
+        };
+        let removed = &self.report.removed_packages;
+        if !removed.is_empty() {
+            writeln!(
+                f,
+                "\n## {} package removal(s){skipped_suffix}",
+                removed.len()
+            )?;
+            for p in removed {
+                writeln!(f, "- {} ({} files)", p.package_id(), p.files.len())?;
+            }
+        }
+        let not_installed = &self.report.not_installed_packages;
+        if !not_installed.is_empty() {
+            writeln!(f, "\n## {} package(s) not installed", not_installed.len())?;
+            for id in not_installed {
+                writeln!(f, "- {id}")?;
+            }
+        }
+        if removed.is_empty() && not_installed.is_empty() {
+            writeln!(f, "\nNothing to uninstall.")?;
+        }
+        Ok(())
+    }
+}
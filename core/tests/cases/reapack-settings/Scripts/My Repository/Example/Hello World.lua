@@ -0,0 +1 @@
+print("Hello world")
\ No newline at end of file
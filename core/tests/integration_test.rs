@@ -1,8 +1,12 @@
 use axum::http::StatusCode;
 use fs_extra::dir::CopyOptions;
-use reaboot_core::api::{ConfirmationRequest, InstallationStage, InstallerConfig};
-use reaboot_core::installer::{InstallerListener, InstallerNewArgs, InstallerTask};
+use reaboot_core::api::{AutoInstallMode, ConfirmationRequest, InstallationStage, InstallerConfig};
+use reaboot_core::bundle::BundleWriter;
+use reaboot_core::installer::{Installer, InstallerListener, InstallerNewArgs, InstallerTask};
+use reaboot_core::reaper_resource_dir::ReaperResourceDir;
 use reaboot_core::recipe::Recipe;
+use reaboot_core::restorer::{list_past_installations, restore_installation, RestorerConfig};
+use reaboot_core::uninstaller::{uninstall_packages, UninstallerConfig};
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::{Column, Connection, Row, SqliteConnection, Value, ValueRef};
 use std::fmt::{Debug, Display};
@@ -10,6 +14,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::spawn;
 use tracing::instrument;
+use url::Url;
 
 /// This integration test performs multiple test installations and checks if the resulting
 /// REAPER resource directory has the correct contents.
@@ -27,8 +32,194 @@ async fn integration_test() {
     case_minimal().await;
     case_custom_package().await;
     case_recipe().await;
+    case_recipe_remotes().await;
+    case_auto_install().await;
+    case_auto_install_declined().await;
+    case_auto_install_confirmed().await;
     case_package_exists_no_reapack().await;
     case_old_reapack().await;
+    case_reapack_settings().await;
+    case_update_installed_packages().await;
+    case_rollback_on_failure().await;
+    case_restore().await;
+    case_install_from_bundle().await;
+    case_local_repository().await;
+    case_uninstall().await;
+}
+
+/// ReaBoot should be able to uninstall a package, removing both its files and its rows in the
+/// ReaPack database.
+async fn case_uninstall() {
+    let actual_dir = copy_installation("uninstall", "outdated-package");
+    let config = UninstallerConfig {
+        custom_reaper_resource_dir: Some(actual_dir.clone()),
+        package_ids: vec!["My Repository/Example/Hello World.lua".to_string()],
+        installation_id: Some("test".to_string()),
+        ..Default::default()
+    };
+    let report = uninstall_packages(config).await.unwrap();
+    assert_eq!(report.removed_packages.len(), 1);
+    assert!(report.not_installed_packages.is_empty());
+    // The directories of the package existed before, so they are left behind. They must be
+    // empty though (removing a non-empty directory fails).
+    for dir in [
+        "Scripts/My Repository/Example",
+        "Scripts/My Repository",
+        "Scripts",
+    ] {
+        fs::remove_dir(actual_dir.join(dir)).unwrap();
+    }
+    let executed = ExecutedTestCase::check("uninstall", actual_dir).await;
+    let registry_sql =
+        fs::read_to_string(executed.actual_dir.join("ReaPack/registry.sql")).unwrap();
+    assert!(!registry_sql.contains("INSERT INTO entries"));
+    assert!(!registry_sql.contains("INSERT INTO files"));
+}
+
+/// ReaBoot should be able to install packages from a repository that's located in a local
+/// directory, with sources pointing to local files as well.
+async fn case_local_repository() {
+    // The index refers to absolute source URLs, so we need to generate a local variant of it
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let local_repository_dir = target_dir.join("local-repository");
+    fs::create_dir_all(&local_repository_dir).unwrap();
+    let src_repository_dir = manifest_dir().join("tests/repository");
+    let src_repository_url = Url::from_directory_path(&src_repository_dir).unwrap();
+    let index = fs::read_to_string(src_repository_dir.join("index.xml"))
+        .unwrap()
+        .replace("http://localhost:56173/", src_repository_url.as_str());
+    fs::write(local_repository_dir.join("index.xml"), index).unwrap();
+    let local_repository_url = Url::from_directory_path(&local_repository_dir).unwrap();
+    let case = TestCase {
+        id: "local-repository",
+        installation: "vanilla",
+        package_urls: vec![format!(
+            "{local_repository_url}#p=Example/Hello%20World.lua&v=latest"
+        )],
+        ..Default::default()
+    };
+    let (installer, actual_dir) = case.prepare().await;
+    installer.install().await.unwrap();
+    // Package files are the same as if installed from the server
+    assert_dirs_equal_if_exist(
+        &actual_dir.join("Scripts"),
+        &manifest_dir().join("tests/cases/custom-package/Scripts"),
+    );
+    // The repository is registered with the URL of its index file
+    let reapack_ini = fs::read_to_string(actual_dir.join("reapack.ini")).unwrap();
+    let local_index_url = local_repository_url.join("index.xml").unwrap();
+    assert!(reapack_ini.contains(local_index_url.as_str()));
+}
+
+/// ReaBoot should be able to bundle packages and install them from the bundle, without
+/// downloading anything.
+async fn case_install_from_bundle() {
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let bundle_file = target_dir.join("bundle.tar.zst");
+    let config = InstallerConfig {
+        package_urls: vec![format!(
+            "http://localhost:56173/index.xml#p=Example/Hello%20World.lua&v=latest"
+        )],
+        temp_parent_dir: Some(target_dir.clone()),
+        install_reaper: Some(false),
+        install_reapack: Some(false),
+        download_cache_dir: Some(target_dir.join("download-cache")),
+        ..Default::default()
+    };
+    let (_, interaction_receiver) = tokio::sync::broadcast::channel(10);
+    let installer_new_args = InstallerNewArgs {
+        config,
+        temp_dir_for_reaper_download: target_dir.join("reaper"),
+        interactions: interaction_receiver,
+        listener: TestInstallerListener::default(),
+    };
+    let mut writer = BundleWriter::new().unwrap();
+    writer
+        .add_platform(installer_new_args, false)
+        .await
+        .unwrap();
+    let manifest = writer.finish(&bundle_file).unwrap();
+    // Index and package file
+    assert_eq!(manifest.files.len(), 2);
+    let case = TestCase {
+        id: "install-from-bundle",
+        installation: "vanilla",
+        bundle: Some(bundle_file),
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should be able to revert a past installation by restoring the files from its backup
+/// directory and removing the files that it added.
+async fn case_restore() {
+    let case = TestCase {
+        id: "restore",
+        installation: "outdated-package",
+        update_installed_packages: true,
+        ..Default::default()
+    };
+    let src_installation_dir = manifest_dir().join("tests/installations/outdated-package");
+    let executed = case.execute().await;
+    let reaper_resource_dir = ReaperResourceDir::new(executed.actual_dir.clone()).unwrap();
+    let past_installations = list_past_installations(&reaper_resource_dir).unwrap();
+    assert_eq!(past_installations.len(), 1);
+    assert_eq!(past_installations[0].id, "test");
+    assert!(past_installations[0].has_journal);
+    let config = RestorerConfig {
+        custom_reaper_resource_dir: Some(executed.actual_dir.clone()),
+        installation_id: "test".to_string(),
+        restoration_id: Some("restore".to_string()),
+        dry_run: false,
+    };
+    restore_installation(config).await.unwrap();
+    let actual_dir = &executed.actual_dir;
+    assert_dirs_equal_if_exist(
+        &actual_dir.join("Scripts"),
+        &src_installation_dir.join("Scripts"),
+    );
+    assert_files_equal(
+        &actual_dir.join("reapack.ini"),
+        &src_installation_dir.join("reapack.ini"),
+    );
+    assert_binary_files_equal(
+        &actual_dir.join("ReaPack/registry.db"),
+        &src_installation_dir.join("ReaPack/registry.db"),
+    );
+    assert!(!actual_dir.join("ReaPack/Cache/My Repository.xml").exists());
+    // The restoration itself can be reverted as well
+    let past_installations = list_past_installations(&reaper_resource_dir).unwrap();
+    assert_eq!(past_installations.len(), 2);
+}
+
+/// If something fails while moving files into the REAPER resource directory, ReaBoot should roll
+/// back all changes made so far and leave the directory exactly as it was.
+///
+/// We provoke a failure in the middle of the final installation phase by occupying the backup
+/// directory with a file: ReaPack state is applied successfully, but backing up the existing
+/// package file fails.
+async fn case_rollback_on_failure() {
+    let case = TestCase {
+        id: "rollback-on-failure",
+        installation: "backup-dir-blocked",
+        package_urls: vec![format!(
+            "http://localhost:56173/index.xml#p=Example/Hello%20World.lua&v=latest"
+        )],
+        ..Default::default()
+    };
+    case.execute_expecting_rollback().await;
+}
+
+/// ReaBoot should be able to update already installed packages to their latest version, without
+/// being told about them explicitly.
+async fn case_update_installed_packages() {
+    let case = TestCase {
+        id: "update-installed-packages",
+        installation: "outdated-package",
+        update_installed_packages: true,
+        ..Default::default()
+    };
+    case.execute().await;
 }
 
 /// If a file of a package exists already but hasn't been installed via ReaPack, ReaBoot should
@@ -38,10 +229,10 @@ async fn case_package_exists_no_reapack() {
     let case = TestCase {
         id: "package-exists-no-reapack",
         installation: "package-exists-no-reapack",
-        recipe: Recipe::default(),
         package_urls: vec![format!(
             "http://localhost:56173/index.xml#p=Example/Hello%20World.lua&v=latest"
         )],
+        ..Default::default()
     };
     case.execute().await;
 }
@@ -53,10 +244,38 @@ async fn case_old_reapack() {
     let case = TestCase {
         id: "old-reapack",
         installation: "old-reapack",
-        recipe: Recipe::default(),
         package_urls: vec![format!(
             "http://localhost:56173/index.xml#p=Example/Hello%20World.lua&v=latest"
         )],
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should apply the ReaPack settings of a recipe to an existing "reapack.ini" without
+/// touching its other entries.
+///
+/// Network security settings of the recipe should be ignored because they are not allowed.
+async fn case_reapack_settings() {
+    let recipe = r#"
+{
+    "name": "Example",
+    "required_packages": [
+        "http://localhost:56173/index.xml#p=Example/Hello%20World.lua"
+    ],
+    "reapack_settings": {
+        "pre_releases": true,
+        "network_timeout": 30,
+        "proxy": "proxy.example.org:8080",
+        "verify_peer": false
+    }
+}
+"#;
+    let case = TestCase {
+        id: "reapack-settings",
+        installation: "old-reapack",
+        recipe: serde_json::from_str(recipe).unwrap(),
+        ..Default::default()
     };
     case.execute().await;
 }
@@ -78,7 +297,121 @@ async fn case_recipe() {
         id: "recipe",
         installation: "vanilla",
         recipe: serde_json::from_str(recipe).unwrap(),
-        package_urls: vec![],
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should register the remotes of a recipe, even if none of their packages is installed.
+///
+/// Existing remotes should be left untouched and remotes with the same URL should be registered
+/// only once.
+async fn case_recipe_remotes() {
+    let recipe = r#"
+{
+    "name": "Example",
+    "required_packages": [
+        "http://localhost:56173/index.xml#p=Example/Hello%20World.lua"
+    ],
+    "remotes": [
+        {
+            "name": "Team Repository",
+            "url": "https://example.org/team/index.xml",
+            "auto_install": true
+        },
+        {
+            "name": "My Alias",
+            "url": "http://localhost:56173/index.xml",
+            "auto_install": true
+        },
+        {
+            "name": "ReaTeam Themes",
+            "url": "https://github.com/ReaTeam/Themes/raw/master/index.xml",
+            "enabled": false
+        }
+    ]
+}
+"#;
+    let case = TestCase {
+        id: "recipe-remotes",
+        installation: "vanilla",
+        recipe: serde_json::from_str(recipe).unwrap(),
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should install all packages of a newly registered remote that has auto-install
+/// enabled.
+async fn case_auto_install() {
+    let recipe = r#"
+{
+    "name": "Example",
+    "remotes": [
+        {
+            "name": "My Repository",
+            "url": "http://localhost:56173/index.xml",
+            "auto_install": true
+        }
+    ]
+}
+"#;
+    let case = TestCase {
+        id: "auto-install",
+        installation: "vanilla",
+        recipe: serde_json::from_str(recipe).unwrap(),
+        auto_install_mode: AutoInstallMode::NewRemotes,
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should only register the remote if the user declines to install all of its packages.
+async fn case_auto_install_declined() {
+    let recipe = r#"
+{
+    "name": "Example",
+    "remotes": [
+        {
+            "name": "My Repository",
+            "url": "http://localhost:56173/index.xml",
+            "auto_install": true
+        }
+    ]
+}
+"#;
+    let case = TestCase {
+        id: "auto-install-declined",
+        installation: "vanilla",
+        recipe: serde_json::from_str(recipe).unwrap(),
+        auto_install_mode: AutoInstallMode::AskForNewRemotes,
+        confirmation_answer: Some(false),
+        ..Default::default()
+    };
+    case.execute().await;
+}
+
+/// ReaBoot should install all packages of a newly registered remote if the user confirms it.
+async fn case_auto_install_confirmed() {
+    let recipe = r#"
+{
+    "name": "Example",
+    "remotes": [
+        {
+            "name": "My Repository",
+            "url": "http://localhost:56173/index.xml",
+            "auto_install": true
+        }
+    ]
+}
+"#;
+    let case = TestCase {
+        id: "auto-install-confirmed",
+        installation: "vanilla",
+        recipe: serde_json::from_str(recipe).unwrap(),
+        auto_install_mode: AutoInstallMode::AskForNewRemotes,
+        confirmation_answer: Some(true),
+        ..Default::default()
     };
     case.execute().await;
 }
@@ -88,10 +421,10 @@ async fn case_custom_package() {
     let case = TestCase {
         id: "custom-package",
         installation: "vanilla",
-        recipe: Recipe::default(),
         package_urls: vec![format!(
             "http://localhost:56173/index.xml#p=Example/Hello%20World.lua&v=latest"
         )],
+        ..Default::default()
     };
     case.execute().await;
 }
@@ -103,12 +436,25 @@ async fn case_minimal() {
     let case = TestCase {
         id: "minimal",
         installation: "vanilla",
-        recipe: Recipe::default(),
-        package_urls: vec![],
+        ..Default::default()
     };
     case.execute().await;
 }
 
+fn normalize_journal_timestamp(journal_file: &Path) {
+    if !journal_file.exists() {
+        return;
+    }
+    let json = fs::read_to_string(journal_file).unwrap();
+    let mut journal: serde_json::Value = serde_json::from_str(&json).unwrap();
+    journal["timestamp"] = "2000-01-01T00:00:00Z".into();
+    fs::write(
+        journal_file,
+        serde_json::to_string_pretty(&journal).unwrap(),
+    )
+    .unwrap();
+}
+
 fn assert_dirs_equal_if_exist(dir1: &Path, dir2: &Path) {
     assert_dir_contains(dir1, dir2);
     assert_dir_contains(dir2, dir1);
@@ -166,39 +512,43 @@ fn assert_text_files_equal(path1: &Path, path2: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
+#[derive(Default)]
 struct TestCase {
     id: &'static str,
     installation: &'static str,
     recipe: Recipe,
     package_urls: Vec<String>,
+    update_installed_packages: bool,
+    bundle: Option<PathBuf>,
+    auto_install_mode: AutoInstallMode,
+    /// Answer to confirmation requests (if `None`, confirmation requests are not answered).
+    confirmation_answer: Option<bool>,
 }
 
 impl TestCase {
     async fn execute(self) -> ExecutedTestCase {
-        println!("\n\n==== Executing test case [{}] ====\n", self.id);
-        let manifest_dir = manifest_dir();
-        let expected_cases_dir = manifest_dir.join("tests/cases");
-        let expected_dir = expected_cases_dir.join(self.id);
-        let src_installations_dir = manifest_dir.join("tests/installations");
-        let src_installation_dir = src_installations_dir.join(self.installation);
+        let id = self.id;
+        let (installer, actual_dir) = self.prepare().await;
+        installer.install().await.unwrap();
+        ExecutedTestCase::check(id, actual_dir).await
+    }
+
+    /// Executes an installation that is expected to fail and asserts that the installation
+    /// directory is left exactly as it was before.
+    async fn execute_expecting_rollback(self) {
+        let src_installation_dir = manifest_dir()
+            .join("tests/installations")
+            .join(self.installation);
+        let (installer, actual_dir) = self.prepare().await;
+        let result = installer.install().await;
+        assert!(result.is_err(), "installation should have failed");
+        assert_dirs_equal_if_exist(&actual_dir, &src_installation_dir);
+    }
+
+    async fn prepare(self) -> (Installer<TestInstallerListener>, PathBuf) {
+        let actual_dir = copy_installation(self.id, self.installation);
         let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
-        let formatted_timestamp = jiff::Timestamp::now()
-            .strftime("%Y-%m-%d_%H-%M-%S")
-            .to_string();
-        let target_test_dir = target_dir.join(&formatted_timestamp);
-        let target_cases_dir = target_test_dir.join("cases");
-        let actual_dir = target_cases_dir.join(self.id);
-        // Copy desired installation to test directory
-        fs_extra::copy_items(
-            &[&src_installation_dir],
-            &actual_dir,
-            &CopyOptions {
-                copy_inside: true,
-                ..Default::default()
-            },
-        )
-        .unwrap();
-        // Install
+        // Create installer
         let config = InstallerConfig {
             custom_reaper_resource_dir: Some(actual_dir.clone()),
             package_urls: self.package_urls,
@@ -206,32 +556,78 @@ impl TestCase {
             selected_features: Default::default(),
             install_reapack: Some(false),
             installation_id: Some("test".to_string()),
+            update_installed_packages: Some(self.update_installed_packages),
+            // Don't touch the download cache of the user
+            download_cache_dir: Some(target_dir.join("download-cache")),
+            bundle: self.bundle,
+            auto_install_mode: Some(self.auto_install_mode),
             ..Default::default()
         };
-        let (_, interaction_receiver) = tokio::sync::broadcast::channel(10);
+        let (interaction_sender, interaction_receiver) = tokio::sync::broadcast::channel(10);
         let installer_new_args = InstallerNewArgs {
             config,
             temp_dir_for_reaper_download: target_dir.join("reaper"),
             interactions: interaction_receiver,
-            listener: TestInstallerListener,
+            listener: TestInstallerListener {
+                confirmation: self
+                    .confirmation_answer
+                    .map(|answer| (interaction_sender, answer)),
+            },
         };
-        let installer = reaboot_core::installer::Installer::new(installer_new_args)
-            .await
-            .unwrap();
+        let installer = Installer::new(installer_new_args).await.unwrap();
         let resolved_config = installer.resolved_config();
         assert!(resolved_config.reaper_is_installable);
         assert_eq!(resolved_config.reaper_exe_exists, true);
         assert_eq!(resolved_config.reaper_ini_exists, true);
         assert_eq!(resolved_config.portable, true);
-        installer.install().await.unwrap();
+        (installer, actual_dir)
+    }
+}
+
+/// Copies the given installation to a fresh test directory for the given test case and returns
+/// that directory.
+fn copy_installation(id: &str, installation: &str) -> PathBuf {
+    println!("\n\n==== Executing test case [{id}] ====\n");
+    let src_installation_dir = manifest_dir()
+        .join("tests/installations")
+        .join(installation);
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let formatted_timestamp = jiff::Timestamp::now()
+        .strftime("%Y-%m-%d_%H-%M-%S")
+        .to_string();
+    let actual_dir = target_dir.join(&formatted_timestamp).join("cases").join(id);
+    fs_extra::copy_items(
+        &[&src_installation_dir],
+        &actual_dir,
+        &CopyOptions {
+            copy_inside: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    actual_dir
+}
+
+struct ExecutedTestCase {
+    expected_dir: PathBuf,
+    actual_dir: PathBuf,
+}
+
+impl ExecutedTestCase {
+    /// Compares the important files in the given REAPER resource directory with the expected
+    /// ones of the given test case.
+    async fn check(id: &str, actual_dir: PathBuf) -> Self {
+        let expected_dir = manifest_dir().join("tests/cases").join(id);
         // Dump ReaPack registry.db to text (we don't want to compare binary DB files because
         // of OS differences)
         let registry_sql = dump_sqlite_database_as_sql(&actual_dir.join("ReaPack/registry.db"))
             .await
             .unwrap();
         fs::write(actual_dir.join("ReaPack/registry.sql"), registry_sql).unwrap();
+        // The journal contains a timestamp, which we need to normalize for comparison
+        normalize_journal_timestamp(&actual_dir.join("ReaBoot/backups/test/journal.json"));
         // Do basic assertions
-        let executed = ExecutedTestCase {
+        let executed = Self {
             expected_dir,
             actual_dir,
         };
@@ -243,14 +639,7 @@ impl TestCase {
         executed.assert_files_equal("ReaPack/registry.sql");
         executed
     }
-}
 
-struct ExecutedTestCase {
-    expected_dir: PathBuf,
-    actual_dir: PathBuf,
-}
-
-impl ExecutedTestCase {
     fn assert_files_equal(&self, rel_path: &str) {
         assert_files_equal(
             &self.actual_dir.join(rel_path),
@@ -266,8 +655,11 @@ impl ExecutedTestCase {
     }
 }
 
-#[derive(Debug)]
-struct TestInstallerListener;
+#[derive(Debug, Default)]
+struct TestInstallerListener {
+    /// Sender and answer for replying to confirmation requests.
+    confirmation: Option<(tokio::sync::broadcast::Sender<bool>, bool)>,
+}
 
 impl InstallerListener for TestInstallerListener {
     #[instrument]
@@ -293,7 +685,11 @@ impl InstallerListener for TestInstallerListener {
     fn debug(&self, _message: impl Display + Debug) {}
 
     #[instrument]
-    fn confirm(&self, _request: ConfirmationRequest) {}
+    fn confirm(&self, _request: ConfirmationRequest) {
+        if let Some((sender, answer)) = &self.confirmation {
+            sender.send(*answer).unwrap();
+        }
+    }
 }
 
 fn start_file_server(directory: impl AsRef<Path>, port: u16) {
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Remote whose packages are installed automatically.
+ */
+export type AutoInstallationInfo = { remote: string, url: string, 
+/**
+ * Number of packages of this remote that are going to be installed automatically.
+ */
+package_count: number, };
@@ -1,4 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoInstallMode } from "./AutoInstallMode";
+import type { HttpConfig } from "./HttpConfig";
+import type { Lockfile } from "./Lockfile";
+import type { ReaPackSettings } from "./ReaPackSettings";
 import type { ReaperPlatform } from "./ReaperPlatform";
 import type { Recipe } from "./Recipe";
 import type { VersionRef } from "./VersionRef";
@@ -63,6 +67,17 @@ keep_temp_dir: boolean,
  * Maximum number of concurrent downloads.
  */
 concurrent_downloads?: number, 
+/**
+ * Maximum number of concurrent downloads from the same host (by default only limited by
+ * `concurrent_downloads`).
+ *
+ * Useful to not get rate-limited by hosts that serve many packages, e.g. GitHub.
+ */
+concurrent_downloads_per_host?: number, 
+/**
+ * Maximum combined download rate in bytes per second (by default unlimited).
+ */
+max_download_bytes_per_second?: number, 
 /**
  * If `true`, nothing will be installed (by default false).
  *
@@ -81,6 +96,12 @@ skip_failed_packages: boolean,
  * An optional recipe.
  */
 recipe?: Recipe, 
+/**
+ * Path or URL from which the recipe has been loaded.
+ *
+ * Relative includes of the recipe are resolved against it.
+ */
+recipe_location?: string, 
 /**
  * The set of recipe features to be installed.
  *
@@ -101,4 +122,70 @@ update_reaper: boolean,
 /**
  * Install ReaPack (by default true).
  */
-install_reapack?: boolean, };
+install_reapack?: boolean, 
+/**
+ * Update packages that are already installed via ReaPack to their latest version
+ * (by default false).
+ *
+ * Just like ReaPack's synchronization, this skips pinned packages and considers
+ * pre-releases only if enabled in the ReaPack settings, for packages that are marked as
+ * "bleeding edge" or for packages that are already on a pre-release.
+ */
+update_installed_packages?: boolean, 
+/**
+ * If provided, installs exactly the packages, versions and files pinned in this lockfile.
+ *
+ * All other package URLs must be contained in the lockfile. A downloaded file whose hash
+ * doesn't match the locked hash is treated as a failure. The ReaPack package is only
+ * installed if it's part of the lockfile.
+ */
+lockfile?: Lockfile, 
+/**
+ * Whether to reuse package files downloaded in previous runs (by default true).
+ *
+ * Cached files are only reused if they match the hash in the repository index.
+ */
+use_download_cache?: boolean, 
+/**
+ * Directory of the download cache (by default `reaboot/downloads` within the user cache
+ * directory).
+ */
+download_cache_dir?: string, 
+/**
+ * Maximum size of the download cache in bytes (by default 1 GiB).
+ *
+ * If the cache grows larger, the least recently used files are removed.
+ */
+max_download_cache_size?: number, 
+/**
+ * If provided, takes all files from this offline bundle (see `reaboot bundle`) instead of
+ * downloading them.
+ *
+ * Installs exactly the packages locked in the bundle for the configured platform. REAPER is
+ * only installed if the bundle contains a REAPER installer for that platform.
+ */
+bundle?: string, 
+/**
+ * Network settings applied to all HTTP requests (by default, ReaBoot uses the proxy
+ * configured in the usual environment variables such as `HTTPS_PROXY`).
+ */
+http?: HttpConfig, 
+/**
+ * ReaPack settings to be written to `reapack.ini`.
+ *
+ * Take precedence over the settings of the recipe. Settings that are provided neither here
+ * nor in the recipe keep their current value.
+ */
+reapack_settings?: ReaPackSettings, 
+/**
+ * Whether the recipe may change the network security settings of ReaPack, that is
+ * `proxy` and `verify_peer` (by default false).
+ *
+ * Recipes can come from anywhere, so these settings of the recipe are ignored unless
+ * allowed explicitly.
+ */
+allow_recipe_network_settings?: boolean, 
+/**
+ * Whether to install all packages of remotes with auto-install enabled (by default `Off`).
+ */
+auto_install_mode?: AutoInstallMode, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LockedPackage } from "./LockedPackage";
+import type { ReaperPlatform } from "./ReaperPlatform";
+
+/**
+ * A lockfile pins each package to an exact version and each package file to an exact download
+ * URL and hash.
+ *
+ * Installing from a lockfile makes sure that each machine ends up with byte-identical files.
+ */
+export type Lockfile = { 
+/**
+ * Lockfile format version.
+ */
+version: number, 
+/**
+ * Platform for which the packages have been resolved.
+ *
+ * Sources are platform-specific, so a lockfile can only be used for installations
+ * targeting the same platform.
+ */
+platform: ReaperPlatform, packages: Array<LockedPackage>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * File that would be installed as part of a package version.
+ */
+export type PackageFileInfo = { 
+/**
+ * Destination path relative to the REAPER resource directory.
+ */
+relative_path: string, 
+/**
+ * Download URL.
+ */
+url: string, 
+/**
+ * Package type of this file, if it overrides the package type.
+ */
+typ: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Link from the package metadata.
+ */
+export type PackageLinkInfo = { 
+/**
+ * Relation such as `website`, `donation` or `screenshot`.
+ */
+rel: string, url: string, name: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PackageDescError } from "./PackageDescError";
+
+/**
+ * Detailed preparation status of a single package.
+ */
+export type PackagePrepStatusInfo = { "kind": "NotFoundInRepo", error: PackageDescError, } | { "kind": "VersionConflict", versions: Array<string>, } | { "kind": "Incompatible" } | { "kind": "ConflictWithOtherPackagesToBeInstalled", relative_path: string, } | { "kind": "ConflictWithAlreadyInstalledFiles", relative_path: string, 
+/**
+ * ID of the already installed package, in the form `REMOTE/CATEGORY/PACKAGE`.
+ */
+installed_package_id: string, } | { "kind": "DownloadFailed", message: string, } | { "kind": "TempInstallFailed", message: string, } | { "kind": "ToBeAdded" } | { "kind": "ToBeReplaced", old_version: string, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Package found by a package search.
+ */
+export type PackageSearchResult = { 
+/**
+ * Name of the ReaPack remote whose index contains the package.
+ */
+remote: string, category: string, package: string, 
+/**
+ * Display name of the package, if the index provides one.
+ */
+description: string | null, 
+/**
+ * Package type such as `script` or `extension`.
+ */
+typ: string, 
+/**
+ * Latest version (including pre-releases if requested).
+ */
+version: string, author: string | null, 
+/**
+ * Package URL that can be passed as-is to the installer.
+ */
+package_url: string, 
+/**
+ * Relevance of the match. Higher is better.
+ */
+score: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConditionalRecipePackage } from "./ConditionalRecipePackage";
+
+/**
+ * Entry in a list of recipe packages.
+ */
+export type RecipePackage = string | ConditionalRecipePackage;
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * ReaPack remote (repository) to be registered in `reapack.ini`.
+ */
+export type RecipeRemote = { name: string, 
+/**
+ * URL of the repository index.
+ */
+url: string, 
+/**
+ * Whether the remote should be enabled. Defaults to `true`.
+ */
+enabled?: boolean | null, 
+/**
+ * Whether ReaPack should automatically install new packages from this remote. If not set,
+ * the global ReaPack setting applies.
+ */
+auto_install?: boolean | null, };
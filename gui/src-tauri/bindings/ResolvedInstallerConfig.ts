@@ -1,5 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoInstallMode } from "./AutoInstallMode";
+import type { Lockfile } from "./Lockfile";
 import type { PackageUrl } from "./PackageUrl";
+import type { ReaPackSettings } from "./ReaPackSettings";
 import type { ReaperPlatform } from "./ReaperPlatform";
 import type { ReaperResourceDir } from "./ReaperResourceDir";
 import type { Recipe } from "./Recipe";
@@ -56,8 +59,36 @@ package_urls: Array<PackageUrl>,
 /**
  * Directory into which ReaBoot writes backups of modified configuration files.
  */
-backup_dir: string, num_download_retries: number, temp_parent_dir: string, keep_temp_dir: boolean, concurrent_downloads: number, dry_run: boolean, reaper_version: VersionRef, skip_failed_packages: boolean, 
+backup_dir: string, num_download_retries: number, temp_parent_dir: string, keep_temp_dir: boolean, concurrent_downloads: number, concurrent_downloads_per_host?: number, max_download_bytes_per_second?: number, dry_run: boolean, reaper_version: VersionRef, skip_failed_packages: boolean, 
 /**
  * Whether to install REAPER if necessary.
  */
-install_reaper: boolean, update_reaper: boolean, install_reapack: boolean, recipe?: Recipe, };
+install_reaper: boolean, update_reaper: boolean, install_reapack: boolean, 
+/**
+ * Whether to update packages that are already installed.
+ */
+update_installed_packages: boolean, 
+/**
+ * ReaPack settings to be written to `reapack.ini` (merged from installer config and recipe).
+ */
+reapack_settings: ReaPackSettings, 
+/**
+ * Names of ReaPack settings of the recipe that are ignored because they haven't been
+ * allowed (see [`InstallerConfig::allow_recipe_network_settings`]).
+ */
+ignored_recipe_reapack_settings: Array<string>, 
+/**
+ * Whether `latest` resolves to the latest version including pre-releases.
+ *
+ * Derived from the pre-release setting in [`Self::reapack_settings`] or, if not provided,
+ * from the existing `reapack.ini`.
+ */
+include_pre_releases: boolean, auto_install_mode: AutoInstallMode, 
+/**
+ * Lockfile which pins all packages and files.
+ */
+lockfile?: Lockfile, 
+/**
+ * Directory of the download cache (`None` if the cache is disabled).
+ */
+download_cache_dir?: string, max_download_cache_size: number, recipe?: Recipe, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Change of a tool such as REAPER or ReaPack.
+ */
+export type ToolingChangeInfo = { name: string, version: string, };
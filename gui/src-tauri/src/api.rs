@@ -1,6 +1,6 @@
 use reaboot_core::api::{
-    ConfirmationRequest, InstallationStage, InstallerConfig, ReabootBackendInfo,
-    ResolvedInstallerConfig,
+    ConfirmationRequest, InstallationStage, InstallerConfig, PreparationReportInfo,
+    ReabootBackendInfo, ResolvedInstallerConfig,
 };
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -63,8 +63,10 @@ pub enum ReabootEvent {
     },
     InstallationDone {
         #[ts(optional)]
-        report_html: Option<String>,
-        report_contains_donation_links: bool,
+        report: Option<PreparationReportInfo>,
+        /// Whether the packages in the report have actually been installed (`false` if
+        /// installation failed or this was a dry run).
+        actually_installed_things: bool,
         #[ts(optional)]
         manual_reaper_install_path: Option<PathBuf>,
     },
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use tauri::State;
 
 use reaboot_core::api::InstallerConfig;
-use reaboot_core::reaboot_util::resolve_config;
+use reaboot_core::reaboot_util::{create_http_client_for_config, resolve_config};
 use reaboot_core::reaper_util;
 
 use crate::api::ReabootCommand;
@@ -13,8 +13,10 @@ use crate::worker::ReabootWorkerCommand;
 use crate::ReabootAppState;
 
 #[tauri::command]
-pub async fn get_reaper_eula() -> Result<String, String> {
-    reaper_util::get_reaper_eula()
+pub async fn get_reaper_eula(state: State<'_, ReabootAppState>) -> Result<String, String> {
+    let http_client = create_http_client_for_config(&state.extract_installer_config())
+        .map_err(|e| e.to_string())?;
+    reaper_util::get_reaper_eula(&http_client)
         .await
         .map_err(|e| e.to_string())
 }
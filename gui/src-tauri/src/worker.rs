@@ -1,11 +1,9 @@
-use markdown::{CompileOptions, ParseOptions};
 use std::path::PathBuf;
 
 use tauri::async_runtime::Receiver;
 
 use reaboot_core::api::InstallerConfig;
 use reaboot_core::installer::{InstallError, Installer, InstallerNewArgs};
-use reaboot_core::{PreparationReportAsMarkdown, PreparationReportMarkdownOptions};
 
 use crate::api::ReabootEvent;
 use crate::app_handle::ReabootAppHandle;
@@ -82,33 +80,9 @@ impl ReabootWorker {
                     InstallError::Other(_) => (None, false, None),
                 },
             };
-        let (report_html, report_contains_donation_links) = if let Some(r) = report {
-            let options = PreparationReportMarkdownOptions {
-                include_main_heading: false,
-                include_donation_links: true,
-                actually_installed_things,
-                optimize_for_termimad: false,
-            };
-            let markdown = PreparationReportAsMarkdown::new(&r, options).to_string();
-            let options = markdown::Options {
-                parse: ParseOptions::gfm(),
-                compile: CompileOptions {
-                    allow_dangerous_html: true,
-                    ..CompileOptions::gfm()
-                },
-            };
-            let html = markdown::to_html_with_options(&markdown, &options).unwrap_or_default();
-            let contains_donation_links = r
-                .package_preparation_outcomes
-                .iter()
-                .any(|o| o.donation_url.is_some());
-            (Some(html), contains_donation_links)
-        } else {
-            (None, false)
-        };
         let done_event = ReabootEvent::InstallationDone {
-            report_html,
-            report_contains_donation_links,
+            report: report.map(|r| r.to_info()),
+            actually_installed_things,
             manual_reaper_install_path,
         };
         self.app_handle.emit_reaboot_event(done_event);
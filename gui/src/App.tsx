@@ -98,7 +98,7 @@ function keepSyncingStateFromBackendToStore() {
                 }
                 break;
             case "InstallationDone":
-                mainStore.setInstallationReport(evt.report_html, evt.report_contains_donation_links);
+                mainStore.setInstallationReport(evt.report, evt.actually_installed_things);
                 mainStore.setManualReaperInstallPath(evt.manual_reaper_install_path);
                 break;
             case "TaskStarted":
@@ -133,7 +133,7 @@ async function confirm(request: ConfirmationRequest) {
         buildButtons: close => {
             return <>
                 <button class="btn" onClick={() => close(true)}>{request.yes_label}</button>
-                {request.no_label && <button class="btn" onClick={() => close(true)}>{request.no_label}</button>}
+                {request.no_label && <button class="btn" onClick={() => close(false)}>{request.no_label}</button>}
             </>;
         }
     });
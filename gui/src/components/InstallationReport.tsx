@@ -0,0 +1,137 @@
+import {PreparationReportInfo} from "../../../core/bindings/PreparationReportInfo.ts";
+import {PackagePreparationInfo} from "../../../core/bindings/PackagePreparationInfo.ts";
+import {PackageStatusCategory} from "../../../core/bindings/PackageStatusCategory.ts";
+import {For, JSX, Show} from "solid-js";
+
+type Props = {
+    report: PreparationReportInfo,
+    // Whether the packages have actually been installed (false if installation failed or this was a dry run)
+    actuallyInstalledThings: boolean,
+}
+
+export function InstallationReport(props: Props) {
+    const packagesWithStatus = (category: PackageStatusCategory) =>
+        props.report.packages.filter(p => p.status_category === category);
+    const nothingChanged = () => props.report.packages.length === 0
+        && props.report.tooling_changes.length === 0
+        && props.report.package_removals.length === 0
+        && props.report.remote_registrations.length === 0
+        && props.report.auto_installations.length === 0
+        && props.report.reapack_setting_changes.length === 0;
+    return <div class="prose prose-sm overflow-y-auto">
+        <Show when={props.report.failure_count > 0}>
+            <Heading label="package failure" count={props.report.failure_count}/>
+            <OutcomeTable packages={packagesWithStatus("Failure")} label3="Error">
+                {p => p.message}
+            </OutcomeTable>
+        </Show>
+        <Show when={props.report.tooling_changes.length > 0}>
+            <Heading label="tooling change" count={props.report.tooling_changes.length}
+                     skipped={!props.actuallyInstalledThings}/>
+            <ul>
+                <For each={props.report.tooling_changes}>
+                    {c => <li><b>{c.name}</b> {c.version}</li>}
+                </For>
+            </ul>
+        </Show>
+        <Show when={props.report.package_removals.length > 0}>
+            <Heading label="package removal" count={props.report.package_removals.length}
+                     skipped={!props.actuallyInstalledThings}/>
+            <ul>
+                <For each={props.report.package_removals}>
+                    {p => <li><b>{p.package}</b> {p.version} ({p.category})</li>}
+                </For>
+            </ul>
+        </Show>
+        <Show when={props.report.remote_registrations.length > 0}>
+            <Heading label="remote registration" count={props.report.remote_registrations.length}
+                     skipped={!props.actuallyInstalledThings}/>
+            <ul>
+                <For each={props.report.remote_registrations}>
+                    {r => <li><b>{r.name}</b> {r.url}{r.enabled ? "" : " (disabled)"}</li>}
+                </For>
+            </ul>
+        </Show>
+        <Show when={props.report.auto_installations.length > 0}>
+            <Heading label="remote auto-installation" count={props.report.auto_installations.length}
+                     skipped={!props.actuallyInstalledThings}/>
+            <ul>
+                <For each={props.report.auto_installations}>
+                    {a => <li><b>{a.remote}</b> {a.url} ({a.package_count} package{a.package_count === 1 ? "" : "s"})</li>}
+                </For>
+            </ul>
+        </Show>
+        <Show when={props.report.reapack_setting_changes.length > 0}>
+            <Heading label="ReaPack setting change" count={props.report.reapack_setting_changes.length}
+                     skipped={!props.actuallyInstalledThings}/>
+            <ul>
+                <For each={props.report.reapack_setting_changes}>
+                    {c => <li><b>{c.name}</b>: {c.old_value ?? "default"} → {c.new_value ?? "default"}</li>}
+                </For>
+            </ul>
+        </Show>
+        <Show when={props.report.replacement_count > 0}>
+            <Heading label="package replacement" count={props.report.replacement_count}
+                     skipped={!props.actuallyInstalledThings}/>
+            <OutcomeTable packages={packagesWithStatus("Replacement")}
+                          label3="Replaces previously installed version">
+                {p => p.status.kind === "ToBeReplaced" ? p.status.old_version : ""}
+            </OutcomeTable>
+        </Show>
+        <Show when={props.report.addition_count > 0}>
+            <Heading label="package addition" count={props.report.addition_count}
+                     skipped={!props.actuallyInstalledThings}/>
+            <OutcomeTable packages={packagesWithStatus("Addition")} label3="Donate">
+                {p =>
+                    <Show when={p.donation_url}>
+                        {url => <a href={url()} target="_blank">Donate</a>}
+                    </Show>
+                }
+            </OutcomeTable>
+        </Show>
+        <Show when={nothingChanged()}>
+            <p>No changes were necessary.</p>
+        </Show>
+    </div>;
+}
+
+// Returns whether the given report contains at least one donation link.
+export function reportContainsDonationLinks(report: PreparationReportInfo) {
+    return report.packages.some(p => p.donation_url);
+}
+
+function Heading(props: { label: string, count: number, skipped?: boolean }) {
+    return <h2>
+        {props.count} {props.label}{props.count > 1 ? "s" : ""}
+        <Show when={props.skipped}>
+            {" "}<b>[SKIPPED]</b>
+        </Show>
+    </h2>;
+}
+
+function OutcomeTable(props: {
+    packages: PackagePreparationInfo[],
+    label3: string,
+    children: (p: PackagePreparationInfo) => JSX.Element,
+}) {
+    return <table>
+        <thead>
+        <tr>
+            <th>Package</th>
+            <th>Version</th>
+            <th>{props.label3}</th>
+        </tr>
+        </thead>
+        <tbody>
+        <For each={props.packages}>
+            {p =>
+                <tr>
+                    <td>{p.package}</td>
+                    <td>{p.version ?? "-"}</td>
+                    <td>{props.children(p)}</td>
+                </tr>
+            }
+        </For>
+        </tbody>
+    </table>;
+}
@@ -4,6 +4,7 @@ import {Recipe} from "../../../core/bindings/Recipe.ts";
 
 type PatchConfigurationArgs = {
     recipe?: Recipe | null,
+    recipeLocation?: string | null,
     customReaperResourceDir?: string | null,
     packageUrls?: string[],
     selectedFeatures?: string[],
@@ -23,6 +24,7 @@ export async function configureInstaller(args: PatchConfigurationArgs) {
     const newConfig = {
         ...oldConfig,
         recipe: args.recipe === undefined ? oldConfig.recipe : (args.recipe ?? undefined),
+        recipe_location: args.recipeLocation === undefined ? oldConfig.recipe_location : (args.recipeLocation ?? undefined),
         custom_reaper_resource_dir: args.customReaperResourceDir === undefined ? oldConfig.custom_reaper_resource_dir : (args.customReaperResourceDir ?? undefined),
         package_urls: args.packageUrls ?? oldConfig.package_urls,
         selected_features: args.selectedFeatures ?? oldConfig.selected_features,
@@ -16,6 +16,7 @@ export async function applyRecipeFromText(text: string) {
     const recipe = await extractRecipe(text);
     await configureInstaller({
         recipe: recipe.raw,
+        recipeLocation: recipe.location ?? null,
         selectedFeatures: getDefaultFeatureIdsFromRecipe(recipe)
     });
     mainStore.setParsedRecipe(recipe);
@@ -4,6 +4,7 @@ import {Match, Show, Switch} from "solid-js";
 import {showDialog} from "../components/GlobalDialog.tsx";
 import {startReaperAndQuit, startReaperInstaller} from "../epics/done.ts";
 import {Help} from "reaboot-commons/src/components/Help.tsx";
+import {InstallationReport, reportContainsDonationLinks} from "../components/InstallationReport.tsx";
 
 export function DonePage() {
     return <div class="grow hero">
@@ -71,22 +72,24 @@ export function DonePage() {
                         }
                     }
                 </Show>
-                <Show when={mainStore.state.installationReportHtml}>
-                    <div>
-                        <Help help="Shows a summary of what was installed and replaced">
-                            <button class="btn btn-link" onClick={showInstallationReport}>
-                                Show installation report
-                            </button>
-                        </Help>
-                        <Show when={mainStore.state.installationReportContainsDonationLinks}>
-                            <div role="alert" class="alert">
-                                <FaSolidHeart class="cursor-pointer" onClick={showInstallationReport}/>
-                                <span>
-                                    Above installation report contains donation links. Please consider supporting the package authors!
-                                </span>
-                            </div>
-                        </Show>
-                    </div>
+                <Show when={mainStore.state.installationReport}>
+                    {report =>
+                        <div>
+                            <Help help="Shows a summary of what was installed and replaced">
+                                <button class="btn btn-link" onClick={showInstallationReport}>
+                                    Show installation report
+                                </button>
+                            </Help>
+                            <Show when={reportContainsDonationLinks(report())}>
+                                <div role="alert" class="alert">
+                                    <FaSolidHeart class="cursor-pointer" onClick={showInstallationReport}/>
+                                    <span>
+                                        Above installation report contains donation links. Please consider supporting the package authors!
+                                    </span>
+                                </div>
+                            </Show>
+                        </div>
+                    }
                 </Show>
             </div>
         </div>
@@ -96,7 +99,8 @@ export function DonePage() {
 async function showInstallationReport() {
     await showDialog<boolean>({
         title: "Installation Report",
-        content: <div class="prose prose-sm overflow-y-auto" innerHTML={mainStore.state.installationReportHtml}/>,
+        content: <InstallationReport report={mainStore.state.installationReport!}
+                                     actuallyInstalledThings={mainStore.state.installationReportActuallyInstalledThings ?? false}/>,
         buildButtons: (close) => {
             return <>
                 <button class="btn" onClick={() => close(false)}>Close</button>
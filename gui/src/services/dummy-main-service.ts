@@ -34,6 +34,12 @@ export class DummyMainService implements MainService {
                     website: "https://www.helgoboss.org/projects/realearn/",
                 },
                 install_reapack: true,
+                update_installed_packages: false,
+                reapack_settings: {},
+                ignored_recipe_reapack_settings: [],
+                include_pre_releases: false,
+                auto_install_mode: "Off",
+                max_download_cache_size: 1073741824,
             }
         });
     }
@@ -4,6 +4,7 @@ import {InstallationStage} from "../../../core/bindings/InstallationStage.ts";
 import {ReabootBackendInfo} from "../../../core/bindings/ReabootBackendInfo.ts";
 import {ResolvedInstallerConfig} from "../../src-tauri/bindings/ResolvedInstallerConfig.ts";
 import {InstallerConfig} from "../../../core/bindings/InstallerConfig.ts";
+import {PreparationReportInfo} from "../../../core/bindings/PreparationReportInfo.ts";
 import {Accessor, createMemo} from "solid-js";
 import {getPage} from "../epics/common.tsx";
 import {getOrEmptyRecord, ParsedRecipe} from "reaboot-commons/src/recipe-util.ts";
@@ -39,12 +40,12 @@ export type MainStoreState = {
     // Current installation stage.
     // Set in response to event from backend.
     installationStage: InstallationStageContainer,
-    // Installation report, HTML-formatted.
+    // Installation report.
     // Set in response to event from backend.
-    installationReportHtml?: string,
-    // Whether the installation report contains donation links.
+    installationReport?: PreparationReportInfo,
+    // Whether the packages in the installation report have actually been installed.
     // Set in response to event from backend.
-    installationReportContainsDonationLinks?: boolean,
+    installationReportActuallyInstalledThings?: boolean,
     // If set, it means that the installer couldn't install REAPER automatically and this contains the path of
     // the installer.
     // Set in response to event from backend.
@@ -111,11 +112,11 @@ export class MainStore {
         this.setState("lastPickedPortableReaperDir", value);
     }
 
-    setInstallationReport(html: string | undefined, containsDonationLinks: boolean) {
+    setInstallationReport(report: PreparationReportInfo | undefined, actuallyInstalledThings: boolean) {
         this.setState(
             produce((state) => {
-                state.installationReportHtml = html;
-                state.installationReportContainsDonationLinks = containsDonationLinks;
+                state.installationReport = report;
+                state.installationReportActuallyInstalledThings = actuallyInstalledThings;
             })
         );
     }
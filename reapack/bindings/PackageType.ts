@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Package type.
+ *
+ * The `#[serde(rename = "...")]` attributes must not change in order to stay compatible with
+ * ReaPack's [Index Format](https://github.com/cfillion/reapack/wiki/Index-Format).
+ *
+ * The numbers must not change in order to stay compatible with ReaPack's database schema.
+ */
+export type PackageType = "script" | "extension" | "effect" | "data" | "theme" | "langpack" | "webinterface" | "projecttpl" | "tracktpl" | "midinotenames" | "autoitem";
@@ -90,7 +90,7 @@ impl Database {
             fs::create_dir_all(parent)?;
         }
         // Open and init DB
-        let mut db = Self::new(db_file, true).await?;
+        let mut db = Self::new(db_file, OpenMode::Create).await?;
         db.init().await?;
         Ok(db)
     }
@@ -102,7 +102,16 @@ impl Database {
     /// connect multiple times during the execution of ReaBoot in order to not having to hold
     /// on to files in an async context.
     pub async fn open(db_file: impl AsRef<Path>) -> anyhow::Result<Self> {
-        Self::new(db_file, false).await
+        Self::new(db_file, OpenMode::ReadWrite).await
+    }
+
+    /// Opens the given database file in read-only mode.
+    ///
+    /// Useful for inspecting a live database. Any attempt to write will fail, including
+    /// migration. So make sure to check [`Self::compatibility_info`] before calling methods
+    /// that potentially migrate, e.g. [`Self::installed_packages`].
+    pub async fn open_read_only(db_file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::new(db_file, OpenMode::ReadOnly).await
     }
 
     /// Closes the database, making sure that everything has been written to disk when the future
@@ -113,11 +122,12 @@ impl Database {
         Ok(())
     }
 
-    async fn new(db_file: impl AsRef<Path>, create_if_missing: bool) -> anyhow::Result<Self> {
+    async fn new(db_file: impl AsRef<Path>, mode: OpenMode) -> anyhow::Result<Self> {
         let options = SqliteConnectOptions::new()
             .filename(db_file)
             .pragma("foreign_keys", "1")
-            .create_if_missing(create_if_missing);
+            .create_if_missing(mode == OpenMode::Create)
+            .read_only(mode == OpenMode::ReadOnly);
         let connection = SqliteConnection::connect_with(&options).await?;
         let db = Self { connection };
         Ok(db)
@@ -210,8 +220,10 @@ impl Database {
     #[instrument(level = "debug")]
     pub async fn migrate(&mut self) -> anyhow::Result<()> {
         let v = self.user_version().await?;
-        if v > REAPACK_DB_USER_VERSION {
-            // Version of DB is greater than the version this ReaBoot version was made for.
+        if v >= REAPACK_DB_USER_VERSION {
+            // Version of DB is up-to-date or even greater than the version this ReaBoot version
+            // was made for. Important to not write anything in this case, the connection might
+            // be read-only.
             return Ok(());
         }
         if v == DbUserVersion::UNINITIALIZED {
@@ -231,6 +243,13 @@ impl Database {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum OpenMode {
+    Create,
+    ReadWrite,
+    ReadOnly,
+}
+
 /// Compatibility of this ReaBoot version with a given ReaPack registry database.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum CompatibilityInfo {
@@ -296,8 +315,7 @@ impl<'a> DatabaseTransaction<'a> {
             typ: convert_model_package_type_to_db(package.typ),
             version: convert_model_version_name_to_db(package.version),
             author: package.author,
-            // We don't care about flags at the moment and use the defaults
-            flags: Some(0),
+            flags: Some(package.flags.as_u32() as i32),
         };
         let entry = entry.insert(&mut self.0).await?;
         for f in package.files {
@@ -389,8 +407,6 @@ pub struct DbEntry {
     pub version: String,
     pub author: String,
     /// Bit flags for "pinned" and "bleeding edge".
-    ///
-    /// Irrelevant for us at the moment.
     pub flags: Option<i32>,
 }
 
@@ -422,6 +438,7 @@ fn convert_db_entry_to_model(entry: DbEntry, db_files: Vec<DbFile>) -> Installed
         typ: convert_db_package_type_to_model(entry.typ),
         version: convert_db_version_name_to_model(entry.version),
         author: entry.author,
+        flags: EnumSet::from_u32_truncated(entry.flags.unwrap_or(0) as u32),
         files,
     }
 }
@@ -507,4 +524,25 @@ mod tests {
         let files = db.files().await.unwrap();
         dbg!(entries, files);
     }
+
+    #[tokio::test]
+    async fn open_db_read_only() {
+        let mut db = Database::open_read_only("src/database/test/registry.db")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.compatibility_info().await.unwrap(),
+            CompatibilityInfo::PerfectlyCompatible
+        );
+        // Must not attempt to write anything
+        let packages = db.installed_packages().await.unwrap();
+        assert!(!packages.is_empty());
+        let result = db
+            .with_transaction(|mut t| async {
+                t.set_user_version(REAPACK_DB_USER_VERSION).await?;
+                Ok(t)
+            })
+            .await;
+        assert!(result.is_err());
+    }
 }
@@ -2,6 +2,7 @@ use crate::model::{PackageType, Platform, Section, VersionName};
 use serde::de::IntoDeserializer;
 use serde::{Deserialize, Deserializer};
 
+use std::fmt::{Display, Formatter};
 use std::io::Read;
 
 use thiserror::Error;
@@ -309,6 +310,13 @@ impl Version {
             VersionEntry::Source(s) => Some(s),
         })
     }
+
+    pub fn changelog(&self) -> Option<&Changelog> {
+        self.entries.iter().find_map(|entry| match entry {
+            VersionEntry::Changelog(c) => Some(c),
+            VersionEntry::Source(_) => None,
+        })
+    }
 }
 
 impl Source {
@@ -339,6 +347,20 @@ impl Source {
 }
 
 impl Metadata {
+    pub fn description(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry {
+            MetadataEntry::Description(d) => Some(d.as_str()),
+            MetadataEntry::Link(_) => None,
+        })
+    }
+
+    pub fn links(&self) -> impl Iterator<Item = &Link> {
+        self.entries.iter().filter_map(|entry| match entry {
+            MetadataEntry::Description(_) => None,
+            MetadataEntry::Link(l) => Some(l),
+        })
+    }
+
     pub fn donation_urls(&self) -> impl Iterator<Item = &str> {
         self.entries.iter().filter_map(|entry| {
             let MetadataEntry::Link(Link {
@@ -355,6 +377,29 @@ impl Metadata {
     }
 }
 
+impl Link {
+    /// Returns the URL of this link.
+    pub fn url(&self) -> &str {
+        self.href.as_deref().unwrap_or(&self.content)
+    }
+
+    /// Returns the display name of this link, if it has one.
+    pub fn display_name(&self) -> Option<&str> {
+        self.href.as_ref().map(|_| self.content.as_str())
+    }
+}
+
+impl Display for Rel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rel::Known(KnownRel::Website) => f.write_str("website"),
+            Rel::Known(KnownRel::Donation) => f.write_str("donation"),
+            Rel::Known(KnownRel::Screenshot) => f.write_str("screenshot"),
+            Rel::Unknown(rel) => f.write_str(rel),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ParseVersionError {
     #[error("invalid version name '{0}'")]
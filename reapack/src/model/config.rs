@@ -1,6 +1,5 @@
 use anyhow::Context;
 use indexmap::IndexMap;
-use ini::{EscapePolicy, Ini, LineSeparator, ParseOption, Properties, WriteOption};
 use std::fs;
 
 use encoding_rs::Encoding;
@@ -8,6 +7,8 @@ use std::path::Path;
 
 use url::Url;
 
+use crate::model::ini_document::IniDocument;
+
 /// This is the currently supported ReaPack config version.
 ///
 /// For ReaBoot, that means:
@@ -26,14 +27,49 @@ pub const REAPACK_CONFIG_VERSION: u32 = 4;
 
 /// ReaPack configuration that's typically saved in the "reapack.ini" file.
 ///
-/// At the moment, this contains just those properties that are relevant for ReaBoot.
-/// Other properties will not be touched.
+/// Contains the general version, the install and network settings and the remotes. Other
+/// properties (e.g. window states) and comments will not be touched when applying the config to
+/// an existing file.
+#[derive(Clone)]
 pub struct Config {
     pub encoding: &'static Encoding,
     pub general_version: u32,
+    pub install: InstallSettings,
+    pub network: NetworkSettings,
     pub remote_by_name: IndexMap<String, Remote>,
 }
 
+/// Settings of the `[install]` section.
+///
+/// `None` means that the key is not present, in which case ReaPack uses its default.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct InstallSettings {
+    /// Whether new packages of enabled remotes should be installed automatically (`autoinstall`).
+    pub auto_install: Option<bool>,
+    /// Whether pre-releases should be considered when installing or updating all packages
+    /// (`prereleases`).
+    pub pre_releases: Option<bool>,
+    /// Whether ReaPack should prompt to uninstall obsolete packages (`promptobsolete`).
+    pub prompt_obsolete: Option<bool>,
+}
+
+/// Settings of the `[network]` section.
+///
+/// `None` means that the key is not present, in which case ReaPack uses its default.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct NetworkSettings {
+    /// Proxy in the form `host:port` (`proxy`). An empty string means no proxy.
+    pub proxy: Option<String>,
+    /// Whether to verify the SSL certificate of the peer (`verifypeer`).
+    pub verify_peer: Option<bool>,
+    /// Number of seconds after which ReaPack considers a downloaded repository index as stale
+    /// and downloads it again (`stalethreshold`).
+    pub stale_threshold: Option<u64>,
+    /// Network timeout in seconds (`timeout`).
+    pub timeout: Option<u32>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Remote {
     pub name: String,
     pub url: Url,
@@ -47,6 +83,8 @@ impl Default for Config {
         Self {
             encoding: get_os_encoding(),
             general_version: REAPACK_CONFIG_VERSION,
+            install: Default::default(),
+            network: Default::default(),
             remote_by_name: create_default_remotes()
                 .map(|r| (r.name.clone(), r))
                 .collect(),
@@ -92,65 +130,116 @@ const DEFAULT_REMOTES: &[(&str, &str)] = &[
 
 impl Config {
     pub fn load_from_ini_file(path: &Path) -> anyhow::Result<Self> {
-        let (ini, encoding) = load_ini(path)?;
-        Ok(Self::from_ini(&ini, encoding))
+        let (doc, encoding) = load_ini(path)?;
+        Ok(Self::from_ini(&doc, encoding))
     }
 
-    fn from_ini(ini: &Ini, encoding: &'static Encoding) -> Self {
-        let general_version = ini
-            .get_from(GENERAL_INI_SECTION, "version")
+    fn from_ini(doc: &IniDocument, encoding: &'static Encoding) -> Self {
+        let general_version = doc
+            .get(GENERAL_INI_SECTION, "version")
             .and_then(|v| v.parse().ok())
             .unwrap_or(0);
-        let remotes = ini
-            .section(REMOTES_INI_SECTION)
-            .map(get_remotes_from_props)
-            .unwrap_or_default();
+        let remotes = get_remotes(doc);
+        let install = InstallSettings {
+            auto_install: doc
+                .get(INSTALL_INI_SECTION, "autoinstall")
+                .and_then(parse_bool),
+            pre_releases: doc
+                .get(INSTALL_INI_SECTION, "prereleases")
+                .and_then(parse_bool),
+            prompt_obsolete: doc
+                .get(INSTALL_INI_SECTION, "promptobsolete")
+                .and_then(parse_bool),
+        };
+        let network = NetworkSettings {
+            proxy: doc.get(NETWORK_INI_SECTION, "proxy").map(|v| v.to_string()),
+            verify_peer: doc
+                .get(NETWORK_INI_SECTION, "verifypeer")
+                .and_then(parse_bool),
+            stale_threshold: doc
+                .get(NETWORK_INI_SECTION, "stalethreshold")
+                .and_then(|v| v.parse().ok()),
+            timeout: doc
+                .get(NETWORK_INI_SECTION, "timeout")
+                .and_then(|v| v.parse().ok()),
+        };
         Self {
             encoding,
             general_version,
+            install,
+            network,
             remote_by_name: remotes,
         }
     }
 
+    /// Writes this config to the given INI file, creating it if it doesn't exist.
+    ///
+    /// Only the modeled keys are changed. All other lines of an existing file (unknown keys,
+    /// comments, blank lines) are preserved.
     pub fn apply_to_ini_file(&self, path: &Path) -> anyhow::Result<()> {
-        let (mut ini, encoding) =
-            load_ini(path).unwrap_or_else(|_| (Ini::new(), get_os_encoding()));
-        self.apply_to_ini(&mut ini);
-        // This could be done more efficient, but for ReaBoot's purpose it's totally okay
-        let mut ini_utf8_bytes = Vec::new();
-        ini.write_to_opt(
-            &mut ini_utf8_bytes,
-            WriteOption {
-                escape_policy: EscapePolicy::Basics,
-                line_separator: LineSeparator::SystemDefault,
-                kv_separator: "=",
-            },
-        )?;
-        let ini_text = String::from_utf8(ini_utf8_bytes)?;
+        let (mut doc, encoding) =
+            load_ini(path).unwrap_or_else(|_| (IniDocument::parse(""), get_os_encoding()));
+        self.apply_to_ini(&mut doc);
+        let ini_text = doc.to_string();
         let (bytes, _, _) = encoding.encode(&ini_text);
         fs::write(path, bytes)?;
         Ok(())
     }
 
-    pub fn apply_to_ini(&self, ini: &mut Ini) {
-        ini.set_to(
-            GENERAL_INI_SECTION,
-            "version".to_string(),
-            self.general_version.to_string(),
-        );
-        ini.delete(REMOTES_INI_SECTION);
-        for (i, remote) in self.remote_by_name.values().enumerate() {
-            ini.set_to(
-                REMOTES_INI_SECTION,
-                format!("remote{i}"),
-                remote.to_ini_value(),
-            );
+    /// Applies this config to the given INI document.
+    ///
+    /// Only the modeled keys are changed. All other lines (unknown keys, comments, blank lines)
+    /// are preserved.
+    fn apply_to_ini(&self, doc: &mut IniDocument) {
+        for (section, key, value) in self.setting_entries() {
+            doc.set(section, key, &value);
         }
-        ini.set_to(
-            REMOTES_INI_SECTION,
-            "size".to_string(),
-            self.remote_by_name.len().to_string(),
+        let remote_entries: Vec<_> = self.remote_entries().collect();
+        doc.replace_keys(REMOTES_INI_SECTION, is_remotes_section_key, remote_entries);
+    }
+
+    /// Returns section, key and value of all settings except the remotes.
+    ///
+    /// Settings that are `None` are left out.
+    fn setting_entries(&self) -> impl Iterator<Item = (&'static str, &'static str, String)> {
+        let install = &self.install;
+        let network = &self.network;
+        let install_entries = [
+            ("autoinstall", install.auto_install.map(format_bool)),
+            ("prereleases", install.pre_releases.map(format_bool)),
+            ("promptobsolete", install.prompt_obsolete.map(format_bool)),
+        ]
+        .map(|(key, value)| (INSTALL_INI_SECTION, key, value));
+        let network_entries = [
+            ("proxy", network.proxy.clone()),
+            ("verifypeer", network.verify_peer.map(format_bool)),
+            (
+                "stalethreshold",
+                network.stale_threshold.map(|v| v.to_string()),
+            ),
+            ("timeout", network.timeout.map(|v| v.to_string())),
+        ]
+        .map(|(key, value)| (NETWORK_INI_SECTION, key, value));
+        let general_entry = (
+            GENERAL_INI_SECTION,
+            "version",
+            Some(self.general_version.to_string()),
         );
+        std::iter::once(general_entry)
+            .chain(install_entries)
+            .chain(network_entries)
+            .filter_map(|(section, key, value)| Some((section, key, value?)))
+    }
+
+    fn remote_entries(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.remote_by_name
+            .values()
+            .enumerate()
+            .map(|(i, remote)| (format!("remote{i}"), remote.to_ini_value()))
+            .chain(std::iter::once((
+                "size".to_string(),
+                self.remote_by_name.len().to_string(),
+            )))
     }
 
     /// Migrates configuration if necessary and returns `true` if it did.
@@ -177,18 +266,11 @@ impl Config {
     }
 }
 
-fn load_ini(path: &Path) -> anyhow::Result<(Ini, &'static Encoding)> {
+fn load_ini(path: &Path) -> anyhow::Result<(IniDocument, &'static Encoding)> {
     let encoding = get_os_encoding();
     let bytes = fs::read(path).context("couldn't read reapack.ini file")?;
     let (ini_text, encoding, _) = encoding.decode(&bytes);
-    let ini = Ini::load_from_str_opt(
-        &ini_text,
-        ParseOption {
-            enabled_quote: false,
-            enabled_escape: false,
-        },
-    )?;
-    Ok((ini, encoding))
+    Ok((IniDocument::parse(&ini_text), encoding))
 }
 
 impl Remote {
@@ -225,11 +307,14 @@ impl Remote {
     }
 }
 
-fn get_remotes_from_props(props: &Properties) -> IndexMap<String, Remote> {
-    let size: u32 = props.get("size").and_then(|s| s.parse().ok()).unwrap_or(0);
+fn get_remotes(doc: &IniDocument) -> IndexMap<String, Remote> {
+    let size: u32 = doc
+        .get(REMOTES_INI_SECTION, "size")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
     (0..size)
         .filter_map(|i| {
-            let value = props.get(format!("remote{i}"))?;
+            let value = doc.get(REMOTES_INI_SECTION, &format!("remote{i}"))?;
             let remote = Remote::from_ini_value(value).ok()?;
             Some((remote.name.clone(), remote))
         })
@@ -245,8 +330,28 @@ fn create_default_remotes() -> impl Iterator<Item = Remote> {
     })
 }
 
-const GENERAL_INI_SECTION: Option<&str> = Some("general");
-const REMOTES_INI_SECTION: Option<&str> = Some("remotes");
+/// Returns whether the given key of the `[remotes]` section is managed by [`Config`].
+fn is_remotes_section_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "size"
+        || key
+            .strip_prefix("remote")
+            .is_some_and(|i| !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Parses booleans the way ReaPack does it (any non-zero integer is `true`).
+fn parse_bool(value: &str) -> Option<bool> {
+    value.trim().parse::<i64>().ok().map(|v| v != 0)
+}
+
+fn format_bool(value: bool) -> String {
+    u32::from(value).to_string()
+}
+
+const GENERAL_INI_SECTION: &str = "general";
+const INSTALL_INI_SECTION: &str = "install";
+const NETWORK_INI_SECTION: &str = "network";
+const REMOTES_INI_SECTION: &str = "remotes";
 
 fn get_os_encoding() -> &'static Encoding {
     #[cfg(windows)]
@@ -294,3 +399,81 @@ fn get_os_encoding() -> &'static Encoding {
         encoding_rs::UTF_8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_preserves_unknown_keys_and_comments() {
+        let original = "\
+; Written by ReaPack
+[about]
+state=
+
+[general]
+version=4
+
+[install]
+autoinstall=0
+; Let users opt into pre-releases
+prereleases=0
+promptobsolete=1
+
+[network]
+proxy=
+stalethreshold=604800
+verifypeer=1
+
+[remotes]
+remote0=ReaPack|https://reapack.com/index.xml|1|2
+size=1
+custom=value
+";
+        let file = std::env::temp_dir().join(format!("reaboot-reapack-{}.ini", std::process::id()));
+        fs::write(&file, original).unwrap();
+        // Loading and applying without changes must not change anything
+        let mut config = Config::load_from_ini_file(&file).unwrap();
+        assert_eq!(
+            config.install,
+            InstallSettings {
+                auto_install: Some(false),
+                pre_releases: Some(false),
+                prompt_obsolete: Some(true),
+            }
+        );
+        assert_eq!(
+            config.network,
+            NetworkSettings {
+                proxy: Some(String::new()),
+                verify_peer: Some(true),
+                stale_threshold: Some(604800),
+                timeout: None,
+            }
+        );
+        config.apply_to_ini_file(&file).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+        // Modify
+        config.install.pre_releases = Some(true);
+        config.network.timeout = Some(30);
+        config.add_remote(Remote {
+            name: "Team".to_string(),
+            url: Url::parse("https://example.org/index.xml").unwrap(),
+            enabled: false,
+            auto_install: Some(true),
+        });
+        config.apply_to_ini_file(&file).unwrap();
+        let modified = fs::read_to_string(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!(
+            modified,
+            original
+                .replace("prereleases=0", "prereleases=1")
+                .replace("verifypeer=1\n", "verifypeer=1\ntimeout=30\n")
+                .replace(
+                    "size=1\n",
+                    "remote1=Team|https://example.org/index.xml|0|1\nsize=2\n"
+                )
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Display, Formatter};
+
+/// Minimal INI document which keeps all lines (including comments, unknown keys and blank lines)
+/// in their original order, so that modifying a few keys doesn't rewrite the whole file.
+///
+/// Section and key names are matched case-insensitively, just like REAPER does it.
+pub(crate) struct IniDocument {
+    lines: Vec<String>,
+    line_separator: &'static str,
+}
+
+impl IniDocument {
+    pub fn parse(text: &str) -> Self {
+        let line_separator = if text.contains("\r\n") {
+            "\r\n"
+        } else if text.is_empty() {
+            system_line_separator()
+        } else {
+            "\n"
+        };
+        Self {
+            lines: text.lines().map(|l| l.to_string()).collect(),
+            line_separator,
+        }
+    }
+
+    /// Returns the value of the given key in the given section.
+    ///
+    /// Keys in front of the first section header belong to the section `""`.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        let range = self.section_body(section)?;
+        self.lines[range].iter().find_map(|line| {
+            let (k, v) = parse_key_value(line)?;
+            k.eq_ignore_ascii_case(key).then_some(v)
+        })
+    }
+
+    /// Sets the value of the given key in the given section.
+    ///
+    /// Existing keys are modified in place. Missing keys are appended to the end of the section.
+    /// Missing sections are appended to the end of the document.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let line = format!("{key}={value}");
+        let range = self.ensure_section(section);
+        let existing_index = range.clone().find(|i| {
+            parse_key_value(&self.lines[*i]).is_some_and(|(k, _)| k.eq_ignore_ascii_case(key))
+        });
+        match existing_index {
+            Some(i) => self.lines[i] = line,
+            None => {
+                let index = self.end_of_section_content(range);
+                self.lines.insert(index, line);
+            }
+        }
+    }
+
+    /// Removes all keys of the given section for which the predicate returns `true` and inserts
+    /// the given entries at the position of the first removed key (or at the end of the section).
+    pub fn replace_keys(
+        &mut self,
+        section: &str,
+        should_remove: impl Fn(&str) -> bool,
+        entries: impl IntoIterator<Item = (String, String)>,
+    ) {
+        let range = self.ensure_section(section);
+        let start = range.start;
+        let mut first_removed_index = None;
+        let mut kept_lines = Vec::new();
+        for line in self.lines.drain(range) {
+            let remove = parse_key_value(&line).is_some_and(|(k, _)| should_remove(k));
+            if remove {
+                first_removed_index.get_or_insert(start + kept_lines.len());
+            } else {
+                kept_lines.push(line);
+            }
+        }
+        let kept_count = kept_lines.len();
+        self.lines.splice(start..start, kept_lines);
+        let insert_index = first_removed_index
+            .unwrap_or_else(|| self.end_of_section_content(start..start + kept_count));
+        let new_lines = entries.into_iter().map(|(k, v)| format!("{k}={v}"));
+        self.lines.splice(insert_index..insert_index, new_lines);
+    }
+
+    /// Returns the range of lines after the header of the given section up to the next section
+    /// header.
+    fn section_body(&self, section: &str) -> Option<std::ops::Range<usize>> {
+        let start = if section.is_empty() {
+            0
+        } else {
+            let header_index = self.lines.iter().position(|l| {
+                parse_section_header(l).is_some_and(|s| s.eq_ignore_ascii_case(section))
+            })?;
+            header_index + 1
+        };
+        let end = self.lines[start..]
+            .iter()
+            .position(|l| parse_section_header(l).is_some())
+            .map(|i| start + i)
+            .unwrap_or(self.lines.len());
+        Some(start..end)
+    }
+
+    fn ensure_section(&mut self, section: &str) -> std::ops::Range<usize> {
+        if let Some(range) = self.section_body(section) {
+            return range;
+        }
+        if self.lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            self.lines.push(String::new());
+        }
+        self.lines.push(format!("[{section}]"));
+        self.lines.len()..self.lines.len()
+    }
+
+    /// Returns the index right after the last non-blank line of the given section body, so that
+    /// blank lines separating sections stay where they are.
+    fn end_of_section_content(&self, range: std::ops::Range<usize>) -> usize {
+        let start = range.start;
+        self.lines[range]
+            .iter()
+            .rposition(|l| !l.trim().is_empty())
+            .map(|i| start + i + 1)
+            .unwrap_or(start)
+    }
+}
+
+impl Display for IniDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            f.write_str(line)?;
+            f.write_str(self.line_separator)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_section_header(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let name = line.strip_prefix('[')?.split_once(']')?.0;
+    Some(name.trim())
+}
+
+fn parse_key_value(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with(';') || trimmed.starts_with('#') || trimmed.starts_with('[') {
+        return None;
+    }
+    let (key, value) = trimmed.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+fn system_line_separator() -> &'static str {
+    if cfg!(windows) {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modify_keeps_comments_and_unknown_entries() {
+        let text = "; Comment\n[General]\nversion=3\nfoo=bar\n\n[remotes]\n# Another comment\nremote0=a\nunknown=1\nsize=1\n";
+        let mut doc = IniDocument::parse(text);
+        assert_eq!(doc.get("general", "VERSION"), Some("3"));
+        doc.set("general", "version", "4");
+        doc.set("general", "new", "x");
+        doc.set("install", "prereleases", "1");
+        doc.replace_keys(
+            "remotes",
+            |k| k.starts_with("remote") || k == "size",
+            [
+                ("remote0".to_string(), "b".to_string()),
+                ("remote1".to_string(), "c".to_string()),
+                ("size".to_string(), "2".to_string()),
+            ],
+        );
+        assert_eq!(
+            doc.to_string(),
+            "; Comment\n[General]\nversion=4\nfoo=bar\nnew=x\n\n[remotes]\n# Another comment\nremote0=b\nremote1=c\nsize=2\nunknown=1\n\n[install]\nprereleases=1\n"
+        );
+    }
+
+    #[test]
+    fn keep_line_separator() {
+        let mut doc = IniDocument::parse("[general]\r\nversion=3\r\n");
+        doc.set("general", "version", "4");
+        assert_eq!(doc.to_string(), "[general]\r\nversion=4\r\n");
+    }
+}
@@ -1,6 +1,6 @@
 use crate::model::package_id::LightPackageId;
 use crate::model::{PackageType, Section, VersionName};
-use enumset::EnumSet;
+use enumset::{EnumSet, EnumSetType};
 
 use std::fmt::{Display, Formatter};
 
@@ -13,9 +13,22 @@ pub struct InstalledPackage {
     pub typ: InstalledPackageType,
     pub version: InstalledVersionName,
     pub author: String,
+    pub flags: EnumSet<InstalledPackageFlag>,
     pub files: Vec<InstalledFile>,
 }
 
+/// Per-package flags that the user can set in ReaPack.
+///
+/// The order of the variants must not change in order to stay compatible with ReaPack's
+/// database schema (it corresponds to the bit position).
+#[derive(Ord, PartialOrd, Hash, Debug, EnumSetType)]
+pub enum InstalledPackageFlag {
+    /// The package is pinned to its current version and must not be updated.
+    Pinned,
+    /// The package should be updated to pre-releases as well ("bleeding edge").
+    BleedingEdge,
+}
+
 #[derive(Clone, Debug)]
 pub struct InstalledFile {
     /// Path relative to REAPER resource folder.
@@ -48,6 +61,14 @@ pub enum InstalledPackageType {
 }
 
 impl InstalledPackage {
+    pub fn is_pinned(&self) -> bool {
+        self.flags.contains(InstalledPackageFlag::Pinned)
+    }
+
+    pub fn is_bleeding_edge(&self) -> bool {
+        self.flags.contains(InstalledPackageFlag::BleedingEdge)
+    }
+
     pub fn package_id(&self) -> LightPackageId {
         LightPackageId {
             remote: &self.remote,
@@ -71,3 +92,12 @@ impl Display for InstalledVersionName {
         }
     }
 }
+
+impl Display for InstalledPackageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstalledPackageType::Known(t) => t.fmt(f),
+            InstalledPackageType::Unknown(raw) => write!(f, "unknown ({raw})"),
+        }
+    }
+}
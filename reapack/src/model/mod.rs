@@ -1,4 +1,5 @@
 mod config;
+mod ini_document;
 mod installed_package;
 mod package_id;
 mod package_type;
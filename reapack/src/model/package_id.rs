@@ -1,4 +1,16 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
 /// Owned package ID.
+///
+/// # Structure
+///
+/// The textual representation follows this schema:
+///
+/// - **PACKAGE_ID =** `{REMOTE}/{CATEGORY}/{PACKAGE_NAME}`
+///
+/// The category may contain `/` characters, the remote and package name may not.
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct PackageId {
     pub remote: String,
@@ -19,7 +31,7 @@ impl PackageId {
         LightPackageId {
             remote: &self.remote,
             category: &self.category,
-            package: &self.category,
+            package: &self.package,
         }
     }
 }
@@ -33,3 +45,75 @@ impl<'a> LightPackageId<'a> {
         }
     }
 }
+
+impl Display for PackageId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.to_borrowed().fmt(f)
+    }
+}
+
+impl<'a> Display for LightPackageId<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{}", self.remote, self.category, self.package)
+    }
+}
+
+impl FromStr for PackageId {
+    type Err = ParsePackageIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (remote, rest) = s
+            .split_once('/')
+            .ok_or(ParsePackageIdError::MissingCategory)?;
+        let (category, package) = rest
+            .rsplit_once('/')
+            .ok_or(ParsePackageIdError::MissingPackageName)?;
+        if remote.is_empty() || category.is_empty() || package.is_empty() {
+            return Err(ParsePackageIdError::EmptySegment);
+        }
+        let package_id = Self {
+            remote: remote.to_string(),
+            category: category.to_string(),
+            package: package.to_string(),
+        };
+        Ok(package_id)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ParsePackageIdError {
+    #[error("Package ID must have the form `remote/category/package` but the category is missing")]
+    MissingCategory,
+    #[error(
+        "Package ID must have the form `remote/category/package` but the package name is missing"
+    )]
+    MissingPackageName,
+    #[error("Package ID contains an empty segment")]
+    EmptySegment,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_package_id() {
+        let id: PackageId = "ReaTeam Scripts/Various/Sub/rodilab_Color palette.lua"
+            .parse()
+            .unwrap();
+        assert_eq!(id.remote, "ReaTeam Scripts");
+        assert_eq!(id.category, "Various/Sub");
+        assert_eq!(id.package, "rodilab_Color palette.lua");
+        assert_eq!(
+            id.to_string(),
+            "ReaTeam Scripts/Various/Sub/rodilab_Color palette.lua"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_package_id() {
+        assert!("ReaTeam Scripts".parse::<PackageId>().is_err());
+        assert!("ReaTeam Scripts/Various".parse::<PackageId>().is_err());
+        assert!("ReaTeam Scripts//Foo".parse::<PackageId>().is_err());
+    }
+}
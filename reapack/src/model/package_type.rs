@@ -1,5 +1,8 @@
 use num_enum::TryFromPrimitive;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use ts_rs::TS;
 
 /// Package type.
 ///
@@ -8,8 +11,20 @@ use serde::Deserialize;
 ///
 /// The numbers must not change in order to stay compatible with ReaPack's database schema.
 #[derive(
-    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, TryFromPrimitive,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Serialize,
+    Deserialize,
+    TryFromPrimitive,
+    TS,
 )]
+#[ts(export)]
 #[serde(rename_all = "lowercase")]
 #[repr(i32)]
 pub enum PackageType {
@@ -36,3 +51,18 @@ pub enum PackageType {
     #[serde(rename = "autoitem")]
     AutomationItem = 11,
 }
+
+impl Display for PackageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = serde_plain::to_string(self).map_err(|_| std::fmt::Error)?;
+        s.fmt(f)
+    }
+}
+
+impl FromStr for PackageType {
+    type Err = serde_plain::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_plain::from_str(s)
+    }
+}
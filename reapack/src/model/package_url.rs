@@ -1,6 +1,7 @@
 use crate::model::VersionRef;
 
 use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
 use ts_rs::TS;
@@ -154,6 +155,38 @@ impl PackagePath {
     }
 }
 
+impl Display for PackageUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.repository_url, self.package_version_ref)
+    }
+}
+
+impl Display for PackageVersionRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "p={}&v={}", self.package_path, self.version_ref)
+    }
+}
+
+impl Display for PackagePath {
+    /// Encodes each path segment, keeping the `/` separators and encoding spaces as `%20` (just
+    /// like ReaPack's own package links do it).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let segments = self
+            .category
+            .split('/')
+            .chain(std::iter::once(self.package_name.as_str()));
+        for (i, segment) in segments.enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            for part in form_urlencoded::byte_serialize(segment.as_bytes()) {
+                f.write_str(&part.replace('+', "%20"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl FromStr for PackageVersionRef {
     type Err = ParsePackageUrlError;
 
@@ -209,3 +242,24 @@ pub enum ParsePackageUrlError {
     #[error("Version reference is invalid")]
     InvalidVersionRef,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_round_trip() {
+        let input = "https://github.com/ReaTeam/ReaScripts/raw/master/index.xml#p=Various/Sub%20dir/rodilab_Color%20palette%20%26%20more.lua&v=1.2.3-pre";
+        let package_url = PackageUrl::parse(input).unwrap();
+        assert_eq!(package_url.category(), "Various/Sub dir");
+        assert_eq!(
+            package_url.package_name(),
+            "rodilab_Color palette & more.lua"
+        );
+        assert_eq!(package_url.to_string(), input);
+        assert_eq!(
+            PackageUrl::parse(package_url.to_string()).unwrap(),
+            package_url
+        );
+    }
+}